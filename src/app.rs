@@ -1,11 +1,11 @@
 #![allow(unused)]
-use std::{any, collections::VecDeque, io::Write, ops::BitOrAssign, os::windows::process::CommandExt, path::{
+use std::{any, collections::{HashMap, HashSet, VecDeque}, io::Write, ops::BitOrAssign, sync::{mpsc, Arc}, path::{
     Path, PathBuf,
 }, process::{Command, CommandArgs, ExitStatus}, time::{Duration, Instant}};
 use eframe::{
     egui::{self, Style, *}, epaint::tessellator::path, App, CreationContext
 };
-use crate::{appdata::AppData, dgui::{mbox::{centered_mbox_modal, MBox, MessageBox}, recents::Recent}, ext::{BoolExt, CloserAtomicBoolExt, Replace, UiExt}, project_wizard::ProjectWizard, projects::ProjectPath, util::{execute::ExecError, marker::Marker}};
+use crate::{appdata::AppData, dgui::{dir_browser::{self, PathPicker}, header_preview::HeaderPreview, mbox::{centered_mbox_modal, MBox, MessageBox}, onboarding::OnboardingWizard, recents::Recent, scaffold_dialog::ScaffoldDialog, template_dialog::SaveTemplateDialog, launch::LaunchConfigDialog, tasks::TaskDialog, env::EnvDialog, toolchain::ToolchainDetails, worktrees::{WorktreeDialog, WorktreeRow}, workspace::MemberRow}, git_status::GitStatusCache, cargo_workspace::Workspace, tasks::TaskRunner, env::{ProjectEnv, ProjectEnvs}, venv::{Venv, VenvCache}, toolchain::{ToolchainCache, ToolchainPin}, tmux::{TmuxEvent, TmuxSessions}, instance::{InstanceListener, Message}, import::ImportedRecent, launch::{LaunchConfig, LaunchConfigs, Launcher}, util::background::BackgroundCache, ext::{BoolExt, CloserAtomicBoolExt, Replace, UiExt}, project_wizard::{CreatedProject, ProjectWizard}, projects::ProjectPath, util::{execute::{DetachedCommands, ExecError}, marker::Marker}};
 use crate::settings::*;
 
 use crate::{settings::Settings, dgui::{self, tabs::{Tab, TabSizeMode, Tabs}}, projects::ProjectType};
@@ -89,19 +89,75 @@ impl ModalUi {
     }
 }
 
-#[derive(Debug, bincode::Encode, bincode::Decode)]
+/// Recent projects are kept in the order that they were added, oldest first.
+#[derive(Debug)]
 pub struct Persist {
-    recent_projects: VecDeque<ProjectPath>
+    recent_projects: VecDeque<ProjectPath>,
+    /// When recent projects were last opened, as Unix timestamps. Only known for projects that were imported
+    /// from editors.
+    opened: HashMap<PathBuf, i64>,
+}
+
+impl Persist {
+    /// The relative path of the persisted data in `<program>/config/`
+    pub const REL_PATH: &'static str = ".persist";
+
+    #[inline]
+    pub fn recent_projects(&self) -> &VecDeque<ProjectPath> {
+        &self.recent_projects
+    }
+
+    #[inline]
+    pub fn recent_projects_mut(&mut self) -> &mut VecDeque<ProjectPath> {
+        &mut self.recent_projects
+    }
+
+    /// Adds a project that was last opened at `opened` (a Unix timestamp). It goes before the first recent project
+    /// that is known to have been opened later.
+    pub fn insert_opened(&mut self, project: ProjectPath, opened: i64) {
+        let index = self.recent_projects.iter()
+            .position(|recent| self.opened.get(recent.path()).is_some_and(|&time| time > opened))
+            .unwrap_or(self.recent_projects.len());
+        self.opened.insert(project.path().to_path_buf(), opened);
+        self.recent_projects.insert(index, project);
+    }
 }
 
 impl Default for Persist {
     fn default() -> Self {
         Self {
             recent_projects: VecDeque::new(),
+            opened: HashMap::new(),
         }
     }
 }
 
+impl bincode::Encode for Persist {
+    fn encode<E: bincode::enc::Encoder>(&self, encoder: &mut E) -> Result<(), bincode::error::EncodeError> {
+        self.recent_projects.encode(encoder)?;
+        // The times of projects that were removed are dropped.
+        let opened = self.recent_projects.iter()
+            .filter_map(|recent| Some((recent.path().to_path_buf(), *self.opened.get(recent.path())?)))
+            .collect::<HashMap<_, _>>();
+        opened.encode(encoder)
+    }
+}
+
+impl<Ctx> bincode::Decode<Ctx> for Persist {
+    fn decode<D: bincode::de::Decoder<Context = Ctx>>(decoder: &mut D) -> Result<Self, bincode::error::DecodeError> {
+        use bincode::error::DecodeError;
+        let recent_projects = VecDeque::decode(decoder)?;
+        // Files from before the times were recorded end after the recent projects.
+        let opened = match HashMap::decode(decoder) {
+            Ok(opened) => opened,
+            Err(DecodeError::UnexpectedEnd { .. }) => HashMap::new(),
+            Err(DecodeError::Io { inner, .. }) if inner.kind() == std::io::ErrorKind::UnexpectedEof => HashMap::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self { recent_projects, opened })
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 struct RecentProjectContext {
     open_editor: bool,
@@ -127,6 +183,8 @@ impl RecentProjectContext {
 pub struct Runtime {
     recents_search_text: String,
     recent_project_context: RecentProjectContext,
+    /// Cargo workspaces whose members are shown.
+    expanded_workspaces: HashSet<PathBuf>,
 }
 
 pub struct ProjectorApp {
@@ -138,6 +196,25 @@ pub struct ProjectorApp {
     app_data: AppData,
     persist: Persist,
     runtime: Runtime,
+    git_status: GitStatusCache,
+    workspaces: BackgroundCache<Arc<Workspace>>,
+    tasks: TaskRunner,
+    launch_configs: LaunchConfigs,
+    /// Why the launch configurations couldn't be loaded. They aren't saved, so that the file isn't overwritten.
+    launch_configs_error: Option<String>,
+    launcher: Launcher,
+    /// Editors, terminals, and other programs that were opened without waiting for them.
+    commands: DetachedCommands,
+    project_envs: ProjectEnvs,
+    /// Why the project environments couldn't be loaded. They aren't saved, so that the file isn't overwritten.
+    project_envs_error: Option<String>,
+    venvs: VenvCache,
+    toolchains: ToolchainCache,
+    tmux_sessions: TmuxSessions,
+    /// Messages from later launches while this instance is running.
+    instance: Option<InstanceListener>,
+    /// The recent projects of editors that are being imported.
+    import: Option<mpsc::Receiver<Vec<ImportedRecent>>>,
     message: MBox<ProjectorApp>,
 }
 
@@ -149,14 +226,24 @@ impl ProjectorApp {
         });
         let app_data = AppData::from("com", "erisianarchitect", "projector").expect("Failed to create AppData object.");
         app_data.ensure_dirs();
+        // There are no settings until the onboarding wizard is finished or skipped.
+        let first_run = !app_data.config().settings_path().exists();
+        let mut startup_message = None;
         let settings = match app_data.config().load_settings() {
             Ok(settings) => settings,
             Err(err) => {
                 eprintln!("Failed to load settings. Loading default settings instead. {err}");
+                // The file is kept, so that saving the default settings doesn't overwrite the user's settings.
+                if !first_run {
+                    startup_message = Some(match app_data.config().set_aside_settings() {
+                        Ok(path) => format!("Failed to load settings: {err}\nThe default settings are used. The settings file was moved to {}.", path.display()),
+                        Err(move_err) => format!("Failed to load settings: {err}\nThe default settings are used, and the settings file couldn't be moved: {move_err}"),
+                    });
+                }
                 Settings::default()
             },
         };
-        let persist = match app_data.config().load::<_, Persist>(".persist") {
+        let persist = match app_data.config().load::<_, Persist>(Persist::REL_PATH) {
             Ok(mut persist) => {
                 // persist.recent_projects.push_back(ProjectPath::Python(PathBuf::from(r#"C:\Users\derek\Documents\code\python\hydra"#)));
                 // persist.recent_projects.push_back(ProjectPath::Web(PathBuf::from(r#"C:\Users\derek\Documents\code\web\erisianarchitect"#)));
@@ -168,6 +255,46 @@ impl ProjectorApp {
                 Persist::default()
             }
         };
+        match app_data.config().load::<_, Vec<PathBuf>>(dir_browser::BOOKMARKS_REL_PATH) {
+            Ok(bookmarks) => dir_browser::set_bookmarks(&cc.egui_ctx, bookmarks),
+            Err(err) => eprintln!("Failed to load directory bookmarks. {err}"),
+        }
+        match app_data.config().load::<_, Vec<String>>(crate::widgets::RECENT_INPUTS_REL_PATH) {
+            Ok(inputs) => crate::widgets::set_recent_inputs(&cc.egui_ctx, inputs),
+            Err(err) => eprintln!("Failed to load recent path inputs. {err}"),
+        }
+        let git_status = GitStatusCache::new(cc.egui_ctx.clone(), app_data.cache());
+        let workspaces = BackgroundCache::new("cargo-workspaces", cc.egui_ctx.clone(), Duration::from_secs(15), HashMap::new(), |path| {
+            Workspace::load(path).ok().map(Arc::new)
+        });
+        let tasks = TaskRunner::new(cc.egui_ctx.clone(), app_data.cache());
+        let mut load_errors = Vec::new();
+        let (launch_configs, launch_configs_error) = match LaunchConfigs::load(app_data.config()) {
+            Ok(configs) => (configs, None),
+            Err(err) => {
+                load_errors.push(format!("Failed to load launch configurations: {err}"));
+                (LaunchConfigs::default(), Some(err.to_string()))
+            }
+        };
+        let launcher = Launcher::new(cc.egui_ctx.clone());
+        let commands = DetachedCommands::new(cc.egui_ctx.clone());
+        let (project_envs, project_envs_error) = match ProjectEnvs::load(app_data.config()) {
+            Ok(envs) => (envs, None),
+            Err(err) => {
+                load_errors.push(format!("Failed to load project environments: {err}"));
+                (ProjectEnvs::default(), Some(err.to_string()))
+            }
+        };
+        if !load_errors.is_empty() {
+            load_errors.push(String::from("They can't be changed until the file is fixed or removed and Projector is restarted."));
+            startup_message = Some(match startup_message {
+                Some(message) => format!("{message}\n\n{}", load_errors.join("\n")),
+                None => load_errors.join("\n"),
+            });
+        }
+        let venvs = VenvCache::new(cc.egui_ctx.clone(), app_data.cache());
+        let toolchains = ToolchainCache::new(cc.egui_ctx.clone());
+        let tmux_sessions = TmuxSessions::new(cc.egui_ctx.clone());
         Box::new(Self {
             tab_index: match settings.general.default_projects_tab {
                 MainTab::Main => 0,
@@ -186,6 +313,20 @@ impl ProjectorApp {
             app_data,
             persist,
             runtime: Runtime::default(),
+            git_status,
+            workspaces,
+            tasks,
+            launch_configs,
+            launch_configs_error,
+            launcher,
+            commands,
+            project_envs,
+            project_envs_error,
+            venvs,
+            toolchains,
+            tmux_sessions,
+            instance: None,
+            import: None,
             // message: Some(Box::new(|app: &mut ProjectorApp, closer: Closer, ui: &mut Ui| {
             //     ui.vertical_centered_justified(|ui| {
             //         ui.with_inner_margin(Margin { top: 0, bottom: 4, left: 0, right: 0 }, |ui| {
@@ -203,91 +344,384 @@ impl ProjectorApp {
             //         });
             //     });
             // })),
-            message: MBox::new_with({
-                struct StartupMessage {
-                    reopen_count: u64,
-                }
-                impl MessageBox<ProjectorApp> for StartupMessage {
-                    fn show(&mut self, data: &mut ProjectorApp, closer: Closer, ui: &mut Ui) {
-                        centered_mbox_modal(ui.ctx(), |ui| {
-                            ui.label(format!("Choose (Reopen count: {})", self.reopen_count));
-                            ui.horizontal(|ui| {
-                                if ui.clicked("Close") {
-                                    closer.close();
-                                }
-                                if ui.clicked("Reopen") {
-                                    data.show_message(StartupMessage {
-                                        reopen_count: self.reopen_count + 1,
-                                    });
-                                }
-                            });
-                        });
-                    }
-                }
-                StartupMessage {
-                    reopen_count: 0,
-                }
-            }),
+            message: if first_run {
+                MBox::new_with(OnboardingWizard::new())
+            } else if let Some(message) = startup_message {
+                MBox::new_with(message)
+            } else {
+                MBox::new()
+            },
         })
     }
 }
 
 impl ProjectorApp {
     fn save_internal(&self) {
-        match self.app_data.config().save(".persist", &self.persist) {
+        match self.app_data.config().save(Persist::REL_PATH, &self.persist) {
             Ok(()) => (),
             Err(err) => {
                 panic!("Failed to save persist data: {err}");
             },
         }
+        if let Err(err) = self.git_status.save(self.app_data.cache()) {
+            eprintln!("Failed to save git status cache: {err}");
+        }
+        if let Err(err) = self.tasks.save(self.app_data.cache()) {
+            eprintln!("Failed to save task statuses: {err}");
+        }
+        if let Err(err) = self.venvs.save(self.app_data.cache()) {
+            eprintln!("Failed to save virtual environment cache: {err}");
+        }
+    }
+
+    /// Applies and saves the settings that were chosen in the onboarding wizard, then adds the chosen projects to the recent projects.
+    pub(crate) fn finish_onboarding(&mut self, settings: Settings, projects: Vec<ProjectPath>) {
+        self.settings = settings;
+        if let Err(err) = self.app_data.config().save_settings(&self.settings) {
+            eprintln!("Failed to save settings: {err}");
+        }
+        for project in projects {
+            if !self.persist.recent_projects.iter().any(|recent| recent.path() == project.path()) {
+                self.persist.recent_projects.push_back(project);
+            }
+        }
+    }
+
+    /// Reads the recently opened projects of installed editors on a background thread. They're added to the recent
+    /// projects by [ProjectorApp::poll_import].
+    fn import_recent_projects(&mut self, ctx: &Context) {
+        if self.import.is_some() {
+            return;
+        }
+        let existing = self.persist.recent_projects.iter().map(|recent| recent.path().to_path_buf()).collect::<Vec<_>>();
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let imported = crate::import::without_existing(crate::import::import_recents(), existing.iter().map(PathBuf::as_path));
+            _ = sender.send(imported);
+            ctx.request_repaint();
+        });
+        self.import = Some(receiver);
+    }
+
+    /// Adds the imported projects to the recent projects, in order of when they were last opened.
+    fn poll_import(&mut self) {
+        let Some(receiver) = &self.import else {
+            return;
+        };
+        let imported = match receiver.try_recv() {
+            Ok(imported) => imported,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => Vec::new(),
+        };
+        self.import = None;
+        let mut count = 0;
+        for recent in imported {
+            // Projects may have been added while the import was running.
+            if self.persist.recent_projects.iter().any(|existing| existing.path() == recent.entry.path().path()) {
+                continue;
+            }
+            let opened = recent.entry.last_open_time().timestamp();
+            self.persist.insert_opened(recent.entry.into_path(), opened);
+            count += 1;
+        }
+        if count == 0 {
+            self.show_message("No new projects were found in the recent projects of installed editors.");
+        } else {
+            self.show_message(format!("Imported {count} recent project{}.", if count == 1 { "" } else { "s" }));
+        }
+    }
+
+    /// What dropping folders onto `tab` does.
+    fn drop_hint(tab: MainTab) -> String {
+        match tab {
+            MainTab::Project(project_type) if project_type != ProjectType::Other => {
+                format!("Drop folders to add their parent directories to the {} project directories", project_type.text())
+            }
+            _ => String::from("Drop folders to add them to the recent projects"),
+        }
+    }
+
+    /// Handles folders and files that are dragged onto the window. Dropped files resolve to their containing project.
+    /// On a project type tab, the parent of each project is added to that type's project directories. Otherwise the
+    /// projects are added to the recent projects.
+    fn handle_dropped_files(&mut self, ctx: &Context, tab: MainTab) {
+        if ctx.input(|input| !input.raw.hovered_files.is_empty()) {
+            let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("drop_overlay")));
+            let rect = ctx.screen_rect();
+            painter.rect_filled(rect, 0.0, Color32::from_black_alpha(180));
+            painter.text(rect.center(), Align2::CENTER_CENTER, Self::drop_hint(tab), FontId::proportional(20.0), Color32::WHITE);
+        }
+        let dropped = ctx.input(|input| input.raw.dropped_files.iter()
+            .filter_map(|file| file.path.clone())
+            .collect::<Vec<_>>());
+        let projects = dropped.iter()
+            .filter_map(crate::projects::containing_project)
+            .collect::<Vec<_>>();
+        if projects.is_empty() {
+            return;
+        }
+        match tab {
+            MainTab::Project(project_type) if project_type != ProjectType::Other => {
+                let Some(directories) = self.settings.projects.project_directories_mut(project_type) else {
+                    return;
+                };
+                let mut added = 0usize;
+                for parent in projects.iter().filter_map(|project| project.parent()) {
+                    if !directories.iter().any(|dir| crate::import::is_same_project(dir, parent)) {
+                        directories.push(parent.to_path_buf());
+                        added += 1;
+                    }
+                }
+                if added == 0 {
+                    return;
+                }
+                if let Err(err) = self.app_data.config().save_settings(&self.settings) {
+                    self.show_message(format!("Failed to save settings: {err}"));
+                    return;
+                }
+                self.show_message(format!(
+                    "Added {added} {} project director{}.",
+                    project_type.text(),
+                    if added == 1 { "y" } else { "ies" },
+                ));
+            }
+            _ => {
+                for project in projects {
+                    if self.persist.recent_projects.iter().any(|recent| crate::import::is_same_project(recent.path(), &project)) {
+                        continue;
+                    }
+                    let project_type = match tab {
+                        MainTab::Project(project_type) => project_type,
+                        _ => ProjectType::detect(&project).unwrap_or(ProjectType::Other),
+                    };
+                    self.persist.recent_projects.push_back(ProjectPath::from_type(project_type, project));
+                }
+            }
+        }
+    }
+
+    /// Receives the messages that later launches forward to this instance.
+    pub fn with_instance(mut self: Box<Self>, instance: InstanceListener) -> Box<Self> {
+        self.instance = Some(instance);
+        self
+    }
+
+    fn handle_instance_messages(&mut self, ctx: &Context) {
+        let Some(instance) = &self.instance else {
+            return;
+        };
+        let mut show = false;
+        while let Some(message) = instance.poll() {
+            let (path, project_type) = match message {
+                Message::Show => {
+                    show = true;
+                    continue;
+                }
+                Message::Open(path, project_type) => (path, project_type),
+                Message::Remove(path) => {
+                    self.persist.recent_projects.retain(|recent| !crate::import::is_same_project(recent.path(), &path));
+                    continue;
+                }
+            };
+            show = true;
+            let Some(project) = crate::projects::containing_project(&path) else {
+                self.show_message(format!("Not a project directory: {}", path.display()));
+                continue;
+            };
+            self.tab_index = 0;
+            if self.persist.recent_projects.iter().any(|recent| crate::import::is_same_project(recent.path(), &project)) {
+                continue;
+            }
+            let project_type = project_type.or_else(|| ProjectType::detect(&project)).unwrap_or(ProjectType::Other);
+            self.persist.recent_projects.push_back(ProjectPath::from_type(project_type, project));
+        }
+        if show {
+            ctx.send_viewport_cmd(ViewportCommand::Visible(true));
+            ctx.send_viewport_cmd(ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(ViewportCommand::Focus);
+        }
     }
 
     pub fn show_message<M: MessageBox<Self> + 'static>(&self, message: M) {
         self.message.open(message);
     }
 
-    fn open_in_editor<P: AsRef<Path>>(&self, path: P) -> Result<ExitStatus, ExecError> {
-        fn inner(app: &ProjectorApp, path: &Path) -> Result<ExitStatus, ExecError> {
+    #[inline]
+    pub(crate) fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    #[inline]
+    pub(crate) fn git_status_cache(&self) -> &GitStatusCache {
+        &self.git_status
+    }
+
+    #[inline]
+    pub(crate) fn task_runner(&self) -> &TaskRunner {
+        &self.tasks
+    }
+
+    #[inline]
+    pub(crate) fn task_runner_mut(&mut self) -> &mut TaskRunner {
+        &mut self.tasks
+    }
+
+    pub(crate) fn set_launch_configs<P: AsRef<Path>>(&mut self, project: P, configs: Vec<LaunchConfig>) {
+        if let Some(err) = &self.launch_configs_error {
+            self.show_message(format!("The launch configurations can't be changed, because they couldn't be loaded: {err}"));
+            return;
+        }
+        self.launch_configs.set(project.as_ref(), configs);
+        if let Err(err) = self.launch_configs.save(self.app_data.config()) {
+            self.show_message(format!("Failed to save launch configurations: {err}"));
+        }
+    }
+
+    #[inline]
+    pub(crate) fn project_env<P: AsRef<Path>>(&self, project: P) -> ProjectEnv {
+        self.project_envs.get(project).cloned().unwrap_or_default()
+    }
+
+    /// The variables that are added to the environment of processes launched in `path`.
+    #[inline]
+    pub(crate) fn env_pairs_for<P: AsRef<Path>>(&self, path: P) -> Vec<(String, String)> {
+        self.project_envs.pairs_for(path)
+    }
+
+    pub(crate) fn set_project_env<P: AsRef<Path>>(&mut self, project: P, env: ProjectEnv) {
+        if let Some(err) = &self.project_envs_error {
+            self.show_message(format!("The project environment can't be changed, because the environments couldn't be loaded: {err}"));
+            return;
+        }
+        self.project_envs.set(project.as_ref(), env);
+        if let Err(err) = self.project_envs.save(self.app_data.config()) {
+            self.show_message(format!("Failed to save project environment: {err}"));
+        }
+    }
+
+    /// The virtual environment of a Python project, from the cache only. Detection can run `poetry` or `pipenv`,
+    /// so a project whose environment isn't known yet is opened without one while it's detected in the background.
+    fn venv_for(&self, path: &Path) -> Option<Venv> {
+        if ProjectType::detect(path) != Some(ProjectType::Python) {
+            return None;
+        }
+        match self.venvs.lookup(path) {
+            Some(venv) => venv,
+            None => {
+                self.show_message(format!(
+                    "The virtual environment of {} is still being detected, so it was opened without it.\nOpen it again in a moment to use the environment.",
+                    path.display(),
+                ));
+                None
+            }
+        }
+    }
+
+    pub(crate) fn open_in_editor<P: AsRef<Path>>(&self, path: P) -> Result<(), ExecError> {
+        fn inner(app: &ProjectorApp, path: &Path) -> Result<(), ExecError> {
             let editor_cmd = &app.settings.general.editor_command;
-            let path_str = format!(r#""{}""#, path.display());
+            let path_str = crate::util::execute::quote_path(path);
+            let (venv_str, python_str) = crate::venv::placeholders(app.venv_for(path).as_ref());
             use strfmt::strfmt;
-            let cmd = strfmt!(editor_cmd, path => path_str).unwrap();
-            crate::util::execute::exec_shell(&cmd)
+            let cmd = strfmt!(editor_cmd, path => path_str, venv => venv_str, python => python_str)
+                .map_err(|_| ExecError::InvalidCommand(editor_cmd.clone()))?;
+            app.commands.spawn("Open Editor", &cmd, &app.project_envs.pairs_for(path))
         }
         inner(self, path.as_ref())
     }
 
-    fn open_terminal_here<P: AsRef<Path>>(&self, path: P) -> Result<ExitStatus, ExecError> {
-        fn inner(app: &ProjectorApp, path: &Path) -> Result<ExitStatus, ExecError> {
+    /// The terminal is started with the project's virtual environment activated, if it has one.
+    pub(crate) fn open_terminal_here<P: AsRef<Path>>(&self, path: P) -> Result<(), ExecError> {
+        fn inner(app: &ProjectorApp, path: &Path) -> Result<(), ExecError> {
             let path = if path.is_file() {
                 path.parent().expect("Path has no parent.")
             } else {
                 path
             };
-            let shell_cmd = &app.settings.general.shell_command;
-            let path_str = format!(r#""{}""#, path.display());
-            use strfmt::strfmt;
-            let cmd = strfmt!(shell_cmd, path => path_str).unwrap();
-            crate::util::execute::exec_shell(&cmd)
+            let venv = app.venv_for(path);
+            let mut env = venv.as_ref().map(|venv| venv.activation_env()).unwrap_or_default();
+            env.extend(app.project_envs.pairs_for(path));
+            match app.settings.general.terminal_mode {
+                TerminalMode::Command => {
+                    let shell_cmd = &app.settings.general.shell_command;
+                    let path_str = crate::util::execute::quote_path(path);
+                    let (venv_str, python_str) = crate::venv::placeholders(venv.as_ref());
+                    use strfmt::strfmt;
+                    let cmd = strfmt!(shell_cmd, path => path_str, venv => venv_str, python => python_str)
+                        .map_err(|_| ExecError::InvalidCommand(shell_cmd.clone()))?;
+                    app.commands.spawn("Open Terminal Here", &cmd, &env)
+                }
+                TerminalMode::Tmux => {
+                    // The session is created with the environment, and the terminal is opened once it's running.
+                    app.tmux_sessions.open_terminal(&app.settings.general, path, env);
+                    Ok(())
+                }
+            }
         }
         inner(self, path.as_ref())
     }
 
-    fn reveal_in_file_explorer<P: AsRef<Path>>(&self, path: P) -> Result<ExitStatus, ExecError> {
-        fn inner(app: &ProjectorApp, path: &Path) -> Result<ExitStatus, ExecError> {
+    pub(crate) fn reveal_in_file_explorer<P: AsRef<Path>>(&self, path: P) -> Result<(), ExecError> {
+        fn inner(app: &ProjectorApp, path: &Path) -> Result<(), ExecError> {
             let path = if path.is_file() {
                 path.parent().expect("Path has no parent.")
             } else {
                 path
             };
             let explorer_cmd = &app.settings.general.explorer_command;
-            let path_str = format!(r#""{}""#, path.display());
+            let path_str = crate::util::execute::quote_path(path);
             use strfmt::strfmt;
             let cmd = strfmt!(explorer_cmd, path => path_str).unwrap();
-            crate::util::execute::exec_shell(&cmd)
+            app.commands.spawn("Reveal in File Explorer", &cmd, &[])
         }
         inner(self, path.as_ref())
     }
+
+    pub(crate) fn open_in_browser(&self, url: &str) -> Result<(), ExecError> {
+        let browser_cmd = &self.settings.general.browser_command;
+        let url_str = crate::util::execute::quote_url(url);
+        use strfmt::strfmt;
+        let cmd = strfmt!(browser_cmd, url => url_str).map_err(|_| ExecError::InvalidCommand(browser_cmd.clone()))?;
+        self.commands.spawn_program("Browser", &cmd, &[])
+    }
+
+    /// The context menu items that open a directory or copy its path.
+    fn path_context_menu(&self, ui: &mut Ui, path: &Path) {
+        if ui.button("🗀 Reveal in File Explorer").clicked() {
+            if let Err(err) = self.reveal_in_file_explorer(path) {
+                self.show_message(format!("There was an error executing Reveal in File Explorer shell command: {}", err));
+            }
+            ui.close_menu();
+        }
+        if ui.button("🗖 Open Terminal Here").clicked() {
+            if let Err(err) = self.open_terminal_here(path) {
+                self.show_message(format!("There was an error executing Open Terminal Here shell command: {}", err));
+            }
+            ui.close_menu();
+        }
+        if ui.button("✏ Open in Editor").clicked() {
+            if let Err(err) = self.open_in_editor(path) {
+                self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
+            }
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button("🗐 Copy Path").clicked() {
+            ui.ctx().copy_text(format!("{}", path.display()));
+            ui.close_menu();
+        }
+    }
+
+    /// Performs a dry run of license header insertion and opens the preview.
+    fn preview_license_headers(&self, ctx: &Context, project: &ProjectPath) {
+        let extensions = self.settings.projects.include_extensions(project.project_type())
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        let template = self.settings.licenses.header_template();
+        self.show_message(HeaderPreview::new(ctx.clone(), project.path(), extensions, template));
+    }
 }
 
 impl App for ProjectorApp {
@@ -320,6 +754,24 @@ impl App for ProjectorApp {
         //         self.message = Some(popup);
         //     }
         // }
+        self.handle_instance_messages(ctx);
+        self.poll_import();
+        if let Some(report) = self.launcher.poll_failure() {
+            self.show_message(report);
+        }
+        if let Some(report) = self.commands.poll_failure() {
+            self.show_message(report);
+        }
+        while let Some(event) = self.tmux_sessions.poll_event() {
+            match event {
+                TmuxEvent::TerminalReady { command, env } => {
+                    if let Err(err) = self.commands.spawn("Open Terminal Here", &command, &env) {
+                        self.show_message(format!("There was an error executing Open Terminal Here shell command: {}", err));
+                    }
+                }
+                TmuxEvent::Failed(report) => self.show_message(report),
+            }
+        }
         panel::TopBottomPanel::bottom("bottom_panel")
             .frame(Frame::new().stroke(Stroke::NONE))
             .show(ctx, |ui| {
@@ -351,6 +803,10 @@ impl App for ProjectorApp {
                                 self.dialog = ModalUi::settings_tab(self.settings.clone(), SettingsTab::Templates);
                                 ui.close_menu();
                             }
+                            if ui.button("Git").clicked() {
+                                self.dialog = ModalUi::settings_tab(self.settings.clone(), SettingsTab::Git);
+                                ui.close_menu();
+                            }
                             if ui.button("Style").clicked() {
                                 self.dialog = ModalUi::settings_tab(self.settings.clone(), SettingsTab::Style);
                                 ui.close_menu();
@@ -365,22 +821,37 @@ impl App for ProjectorApp {
                             self.save_internal();
                             ctx.send_viewport_cmd(ViewportCommand::Close);
                             let curr_exe = std::env::current_exe().expect("Failed to get current exe.");
-                            std::process::Command::new(curr_exe).spawn().expect("Failed to spawn process.");
+                            // The new instance takes over the lock once this one has exited.
+                            std::process::Command::new(curr_exe)
+                                .arg(crate::instance::RESTART_FLAG)
+                                .spawn()
+                                .expect("Failed to spawn process.");
                         }
                         if ui.button("Exit").clicked() {
                             ctx.send_viewport_cmd(ViewportCommand::Close);
                         }
                         if ui.button("Create Project").clicked() {
-                            self.dialog = ModalUi::ProjectWizard(ProjectWizard {
-
-                            });
+                            let templates = crate::templates::list_templates(self.app_data.config().templates_path());
+                            self.dialog = ModalUi::ProjectWizard(ProjectWizard::new(&self.settings, ProjectType::Rust, templates));
                         }
                     });
                 });
             });
+        dir_browser::update_options(ctx, &self.settings);
+        if let Some(bookmarks) = dir_browser::take_changed_bookmarks(ctx) {
+            if let Err(err) = self.app_data.config().save(dir_browser::BOOKMARKS_REL_PATH, &bookmarks) {
+                eprintln!("Failed to save directory bookmarks: {err}");
+            }
+        }
+        if let Some(inputs) = crate::widgets::take_changed_recent_inputs(ctx) {
+            if let Err(err) = self.app_data.config().save(crate::widgets::RECENT_INPUTS_REL_PATH, &inputs) {
+                eprintln!("Failed to save recent path inputs: {err}");
+            }
+        }
         CentralPanel::default().frame(Frame::NONE).show(ctx, |ui| {
             let close = OwnedCloser::new();
             let mut closer = close.make_closer();
+            let mut created_project = None;
             match &mut self.dialog {
                 ModalUi::None => (),
                 ModalUi::Settings(settings_dialog) => {
@@ -392,7 +863,7 @@ impl App for ProjectorApp {
                     );
                 },
                 ModalUi::ProjectWizard(wizard) => {
-                    wizard.show(
+                    created_project = wizard.show(
                         closer,
                         &self.settings,
                         ui,
@@ -402,6 +873,17 @@ impl App for ProjectorApp {
             if close.is_closed() {
                 self.dialog.close();
             }
+            if let Some(CreatedProject { project, git_error }) = created_project {
+                if let Some(err) = git_error {
+                    self.show_message(format!("The project was created, but the git repository could not be initialized.\n{err}"));
+                }
+                if self.settings.general.open_after_create {
+                    if let Err(err) = self.open_in_editor(project.path()) {
+                        self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
+                    }
+                }
+                self.persist.recent_projects.push_back(project);
+            }
             const TABS: &[Tab<'static, MainTab>] = &[
                 Tab::new("Main", MainTab::Main),
                 Tab::new("Rust", MainTab::Project(ProjectType::Rust)),
@@ -417,11 +899,21 @@ impl App for ProjectorApp {
                 .show(ui, |index, tab, ui| {
                     match tab {
                         MainTab::Main => {
-                            if ui.button("Add Directory").clicked() {
-                                if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            ui.horizontal(|ui| {
+                                let add_directory = ui.button("Add Directory").clicked();
+                                if let Some(dir) = PathPicker::folder("add_directory").show(ui.ctx(), add_directory) {
                                     self.persist.recent_projects.push_back(ProjectPath::Other(dir));
                                 }
-                            }
+                                let importing = self.import.is_some();
+                                if ui.add_enabled(!importing, Button::new("Import Recent Projects"))
+                                    .on_hover_text("Import the recently opened projects of VS Code, VSCodium, and JetBrains IDEs.")
+                                    .clicked() {
+                                    self.import_recent_projects(ui.ctx());
+                                }
+                                if importing {
+                                    ui.spinner();
+                                }
+                            });
                             ui.with_inner_margin(Margin { left: 16, right: 16, top: 16, bottom: 8 }, |ui| {
                                 menu::bar(ui, |ui| {
                                     ui.menu_button(crate::charcons::PUSHPIN, |ui| {
@@ -470,19 +962,46 @@ impl App for ProjectorApp {
                                                 ProjectPath::Web(path_buf) => path_buf.as_path(),
                                                 ProjectPath::Other(path_buf) => path_buf.as_path(),
                                             }.to_owned();
-                                            let recent = Recent::new(&proj);
+                                            // Only Rust projects can be Cargo workspaces.
+                                            let workspace = (proj.project_type() == ProjectType::Rust)
+                                                .then(|| self.workspaces.get(&path))
+                                                .flatten();
+                                            let search = self.runtime.recents_search_text.trim().to_lowercase();
+                                            let name_matches = path.file_name()
+                                                .and_then(|name| name.to_str())
+                                                .is_some_and(|name| name.to_lowercase().contains(&search));
+                                            let members = match &workspace {
+                                                Some(workspace) if !search.is_empty() => workspace.members.iter()
+                                                    .filter(|member| member.matches(&search))
+                                                    .cloned()
+                                                    .collect::<Vec<_>>(),
+                                                Some(workspace) if self.runtime.expanded_workspaces.contains(&path) => workspace.members.clone(),
+                                                _ => Vec::new(),
+                                            };
+                                            if !search.is_empty() && !name_matches && members.is_empty() {
+                                                continue;
+                                            }
+                                            let git_status = self.git_status.get(&path);
+                                            let venv = match &proj {
+                                                ProjectPath::Python(_) => self.venvs.get(&path),
+                                                _ => None,
+                                            };
+                                            let toolchains = self.toolchains.get(&path);
+                                            let tmux_session = match self.settings.general.terminal_mode {
+                                                TerminalMode::Tmux => crate::tmux::session_name(&self.settings.general.tmux_session_name, &path)
+                                                    .ok()
+                                                    .filter(|session| self.tmux_sessions.is_running(session)),
+                                                TerminalMode::Command => None,
+                                            };
+                                            let recent = Recent::new(&proj)
+                                                .with_git_status(git_status.as_ref())
+                                                .with_python_version(venv.as_ref().and_then(|venv| venv.version.as_deref()))
+                                                .with_toolchain_warning(toolchains.as_ref().is_some_and(|pins| pins.iter().any(ToolchainPin::is_unsatisfied)))
+                                                .with_tmux_session(tmux_session.is_some());
                                             let recent_resp = recent.ui(ui);
                                             if recent_resp.clicked() {
-                                                let result = self.open_in_editor(&path);
-                                                match result {
-                                                    Ok(exit_status) => {
-                                                        if !exit_status.success() {
-                                                            self.show_message(format!("Open Editor shell command failed with an exit status of {}", exit_status.code().unwrap_or(-1)));
-                                                        }
-                                                    },
-                                                    Err(err) => {
-                                                        self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
-                                                    },
+                                                if let Err(err) = self.open_in_editor(&path) {
+                                                    self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
                                                 }
                                             }
                                             if recent_resp.clicked_by(PointerButton::Secondary) {
@@ -551,52 +1070,116 @@ impl App for ProjectorApp {
                                                 
                                                 if exec_actions {
                                                     if open_editor_toggle {
-                                                        let result = self.open_in_editor(&path);
-                                                        match result {
-                                                            Ok(exit_status) => {
-                                                                if !exit_status.success() {
-                                                                    self.show_message(format!("Open Editor shell command failed with an exit status of {}", exit_status.code().unwrap_or(-1)));
-                                                                }
-                                                            },
-                                                            Err(err) => {
-                                                                self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
-                                                            },
+                                                        if let Err(err) = self.open_in_editor(&path) {
+                                                            self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
                                                         }
                                                     }
                                                     if open_explorer_toggle {
-                                                        let result = self.reveal_in_file_explorer(&path);
-                                                        match result {
-                                                            Ok(exit_status) => {
-                                                                if !exit_status.success() {
-                                                                    self.show_message(format!("Reveal in File Explorer shell command failed with an exit status of {}", exit_status.code().unwrap_or(-1)));
-                                                                }
-                                                            },
-                                                            Err(err) => {
-                                                                self.show_message(format!("There was an error executing Reveal in File Explorer shell command: {}", err));
-                                                            },
+                                                        if let Err(err) = self.reveal_in_file_explorer(&path) {
+                                                            self.show_message(format!("There was an error executing Reveal in File Explorer shell command: {}", err));
                                                         }
                                                     }
                                                     if open_shell_toggle {
-                                                        let result = self.open_terminal_here(&path);
-                                                        match result {
-                                                            Ok(exit_status) => {
-                                                                if !exit_status.success() {
-                                                                    self.show_message(format!("Open Terminal Here shell command failed with an exit status of {}", exit_status.code().unwrap_or(-1)));
-                                                                }
-                                                            },
-                                                            Err(err) => {
-                                                                self.show_message(format!("There was an error executing Open Terminal Here shell command: {}", err));
-                                                            },
+                                                        if let Err(err) = self.open_terminal_here(&path) {
+                                                            self.show_message(format!("There was an error executing Open Terminal Here shell command: {}", err));
                                                         }
                                                     }
                                                     ui.close_menu();
                                                 }
+                                                if let Some(session) = &tmux_session {
+                                                    if ui.button("✖ Kill tmux Session").on_hover_text(session).clicked() {
+                                                        self.tmux_sessions.kill(session);
+                                                        ui.close_menu();
+                                                    }
+                                                }
                                                 ui.separator();
     
                                                 if ui.button("🗐 Copy Path").clicked() {
                                                     ui.ctx().copy_text(format!("{}", path.display()));
                                                     ui.close_menu();
                                                 }
+                                                if ui.button("🖹 Insert License Headers").clicked() {
+                                                    self.preview_license_headers(ui.ctx(), &proj);
+                                                    ui.close_menu();
+                                                }
+                                                if path.is_dir() && ui.button("🗋 Generate .gitignore/README").clicked() {
+                                                    self.show_message(ScaffoldDialog::new(&path, proj.project_type(), self.settings.templates.clone()));
+                                                    ui.close_menu();
+                                                }
+                                                if path.is_dir() && ui.button("🗊 Save as Template").clicked() {
+                                                    self.show_message(SaveTemplateDialog::new(&path, proj.project_type(), self.app_data.config().templates_path()));
+                                                    ui.close_menu();
+                                                }
+                                                if let Some(status) = git_status.as_ref().filter(|status| !status.remotes.is_empty()) {
+                                                    ui.menu_button("🌐 Remote", |ui| {
+                                                        for remote in &status.remotes {
+                                                            let Some(web_url) = remote.web_url() else {
+                                                                ui.add_enabled(false, Button::new(format!("{} (not a web remote)", remote.name)))
+                                                                    .on_disabled_hover_text(&remote.url);
+                                                                continue;
+                                                            };
+                                                            let mut open_url = None;
+                                                            ui.label(format!("{} ({})", remote.name, web_url.base));
+                                                            if ui.button("🌐 Open in Browser").clicked() {
+                                                                open_url = Some(web_url.base.clone());
+                                                            }
+                                                            if let Some(branch) = &status.branch {
+                                                                if ui.button(format!("⎇ Open Branch `{branch}`")).clicked() {
+                                                                    open_url = Some(web_url.branch_url(branch));
+                                                                }
+                                                            }
+                                                            if ui.button("🗐 Copy URL").clicked() {
+                                                                ui.ctx().copy_text(web_url.base.clone());
+                                                                ui.close_menu();
+                                                            }
+                                                            if let Some(url) = open_url {
+                                                                if let Err(err) = self.open_in_browser(&url) {
+                                                                    self.show_message(format!("There was an error executing Browser shell command: {}", err));
+                                                                }
+                                                                ui.close_menu();
+                                                            }
+                                                            ui.separator();
+                                                        }
+                                                    });
+                                                }
+                                                if workspace.is_some() {
+                                                    let expanded = self.runtime.expanded_workspaces.contains(&path);
+                                                    if ui.button(if expanded { "📦 Hide Members" } else { "📦 Show Members" }).clicked() {
+                                                        if expanded {
+                                                            self.runtime.expanded_workspaces.remove(&path);
+                                                        } else {
+                                                            self.runtime.expanded_workspaces.insert(path.clone());
+                                                        }
+                                                        ui.close_menu();
+                                                    }
+                                                }
+                                                if git_status.is_some() && ui.button("⎇ Worktrees").clicked() {
+                                                    self.show_message(WorktreeDialog::new(&path));
+                                                    ui.close_menu();
+                                                }
+                                                if ui.button("▶ Tasks").clicked() {
+                                                    self.show_message(TaskDialog::new(&path));
+                                                    ui.close_menu();
+                                                }
+                                                if ui.button("🔧 Environment").clicked() {
+                                                    self.show_message(EnvDialog::new(&path, self.project_env(&path)));
+                                                    ui.close_menu();
+                                                }
+                                                ui.menu_button("🚀 Launch", |ui| {
+                                                    for config in self.launch_configs.get(&path) {
+                                                        if ui.button(&config.name).clicked() {
+                                                            self.launcher.launch(&self.settings.general, &path, &self.project_envs.pairs_for(&path), config);
+                                                            ui.close_menu();
+                                                        }
+                                                    }
+                                                    if !self.launch_configs.get(&path).is_empty() {
+                                                        ui.separator();
+                                                    }
+                                                    if ui.button("Edit Launch Configurations").clicked() {
+                                                        self.show_message(LaunchConfigDialog::new(&path, self.launch_configs.get(&path).to_vec()));
+                                                        ui.close_menu();
+                                                    }
+                                                });
                                                 ui.separator();
     
                                                 if ui.clicked("🗑 Remove") {
@@ -607,7 +1190,38 @@ impl App for ProjectorApp {
                                             recent_resp.on_hover_ui(move |ui| {
                                                 let path_str = format!("{}", path.display());
                                                 ui.label(&path_str);
+                                                if let Some(pins) = &toolchains {
+                                                    ui.separator();
+                                                    ToolchainDetails::new(pins).ui(ui);
+                                                }
                                             });
+                                            for worktree in git_status.iter().flat_map(|status| status.worktrees.iter()) {
+                                                let worktree_resp = WorktreeRow::new(worktree).ui(ui);
+                                                if worktree_resp.clicked() && worktree.path.is_dir() {
+                                                    if let Err(err) = self.open_in_editor(&worktree.path) {
+                                                        self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
+                                                    }
+                                                }
+                                                worktree_resp.context_menu(|ui| {
+                                                    self.path_context_menu(ui, &worktree.path);
+                                                    if ui.button("⎇ Manage Worktrees").clicked() {
+                                                        self.show_message(WorktreeDialog::new(proj.path()));
+                                                        ui.close_menu();
+                                                    }
+                                                });
+                                                worktree_resp.on_hover_text(format!("{}", worktree.path.display()));
+                                            }
+                                            for member in &members {
+                                                let member_resp = MemberRow::new(member).ui(ui);
+                                                if member_resp.clicked() {
+                                                    if let Err(err) = self.open_in_editor(&member.path) {
+                                                        self.show_message(format!("There was an error executing Open Editor shell command: {}", err));
+                                                    }
+                                                }
+                                                member_resp.context_menu(|ui| {
+                                                    self.path_context_menu(ui, &member.path);
+                                                });
+                                            }
                                         }
                                         if let Some(index) = remove_index {
                                             self.persist.recent_projects.remove(index);
@@ -688,8 +1302,38 @@ impl App for ProjectorApp {
                     }
                 });
             self.tab_index = tab_index;
+            self.handle_dropped_files(ctx, TABS[self.tab_index].copy_value());
             let mbox = self.message.clone();
             mbox.show(self, ui);
         });
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persist_test() {
+        let config = bincode::config::standard();
+        // A file from before the times were recorded.
+        let recents = VecDeque::from([ProjectPath::other("/code/new")]);
+        let old = bincode::encode_to_vec(&recents, config).unwrap();
+        let (persist, _): (Persist, _) = bincode::decode_from_slice(&old, config).unwrap();
+        assert_eq!(persist.recent_projects, recents);
+        assert!(persist.opened.is_empty());
+        // Files are read with a reader, which reports the end of the file differently.
+        let mut persist: Persist = bincode::decode_from_std_read(&mut old.as_slice(), config).unwrap();
+        assert_eq!(persist.recent_projects, recents);
+
+        persist.insert_opened(ProjectPath::other("/code/b"), 200);
+        persist.insert_opened(ProjectPath::other("/code/a"), 100);
+        persist.insert_opened(ProjectPath::other("/code/c"), 300);
+        let paths = |persist: &Persist| persist.recent_projects.iter().map(|recent| recent.path().to_path_buf()).collect::<Vec<_>>();
+        assert_eq!(paths(&persist), ["/code/new", "/code/a", "/code/b", "/code/c"].map(PathBuf::from));
+
+        persist.recent_projects.remove(2);
+        let (decoded, _): (Persist, _) = bincode::decode_from_slice(&bincode::encode_to_vec(&persist, config).unwrap(), config).unwrap();
+        assert_eq!(paths(&decoded), ["/code/new", "/code/a", "/code/c"].map(PathBuf::from));
+        assert_eq!(decoded.opened, HashMap::from([(PathBuf::from("/code/a"), 100), (PathBuf::from("/code/c"), 300)]));
+    }
+}
@@ -25,11 +25,15 @@ impl SettingsSaver {
     pub fn save(&self, settings: &Settings) -> crate::error::Result<()> {
         // save settings to a tempfile first, then upon successfully writing to tempfile, replace
         // the settings file with the tempfile
-        let mut temp = tempfile::NamedTempFile::new()?;
+        // The tempfile is created next to the settings file, because it can't be persisted across file systems.
+        let mut temp = match self.path.parent() {
+            Some(dir) => tempfile::NamedTempFile::new_in(dir)?,
+            None => tempfile::NamedTempFile::new()?,
+        };
         // let file = std::fs::File::create(temp_path.path())?;
         {
             let mut bufwrite = std::io::BufWriter::new(&mut temp);
-            bincode::encode_into_std_write(settings, &mut bufwrite, bincode::config::standard())?;
+            settings.encode_versioned(&mut bufwrite)?;
             bufwrite.flush()?;
         }
 
@@ -49,6 +53,8 @@ pub struct AppConfig {
 impl AppConfig {
     /// The relative path of the settings in `<program>/config/`
     const SETTINGS_REL_PATH: &'static str = ".settings";
+    /// The relative path of the project templates in `<program>/config/`
+    const TEMPLATES_REL_PATH: &'static str = "templates";
     pub fn new<P: Into<PathBuf>>(path: P) -> Self {
         Self {
             path: path.into(),
@@ -94,6 +100,10 @@ impl AppConfig {
         self.relative_path(Self::SETTINGS_REL_PATH)
     }
 
+    pub fn templates_path(&self) -> PathBuf {
+        self.relative_path(Self::TEMPLATES_REL_PATH)
+    }
+
     pub fn save<P: AsRef<Path>, T: bincode::Encode>(&self, relative_path: P, value: &T) -> crate::error::Result<()> {
         let mut temp = self.named_temp_file()?;
         let save_path = self.relative_path(relative_path);
@@ -118,6 +128,15 @@ impl AppConfig {
         Ok(bincode::decode_from_std_read(&mut reader, bincode::config::standard())?)
     }
 
+    /// Like [AppConfig::load], but a file that doesn't exist yet is loaded as the default value. Any other error is
+    /// returned, so that the caller doesn't save the default over a file it couldn't read.
+    pub fn load_or_default<P: AsRef<Path>, T: bincode::Decode<()> + Default>(&self, relative_path: P) -> crate::error::Result<T> {
+        match self.load(relative_path) {
+            Err(crate::error::Error::IoError(err)) if err.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+            result => result,
+        }
+    }
+
     pub fn settings_modified_time(&self) -> std::io::Result<SystemTime> {
         crate::util::fsutil::modified_system_time(self.settings_path())
     }
@@ -132,12 +151,21 @@ impl AppConfig {
 
     #[inline]
     pub fn save_settings(&self, settings: &Settings) -> crate::error::Result<()> {
-        self.save(Self::SETTINGS_REL_PATH, settings)
+        SettingsSaver::new(self.settings_path()).save(settings)
     }
 
     #[inline]
     pub fn load_settings(&self) -> crate::error::Result<Settings> {
-        self.load(Self::SETTINGS_REL_PATH)
+        Settings::decode_versioned(&std::fs::read(self.settings_path())?)
+    }
+
+    /// Moves a settings file that couldn't be loaded out of the way, so that saving the settings doesn't overwrite it.
+    /// Returns the path it was moved to.
+    pub fn set_aside_settings(&self) -> std::io::Result<PathBuf> {
+        let time = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let path = self.relative_path(format!("{}.{time}.bak", Self::SETTINGS_REL_PATH));
+        std::fs::rename(self.settings_path(), &path)?;
+        Ok(path)
     }
 
     pub fn delete<P: AsRef<Path>>(&self, relative_path: P) -> std::io::Result<()> {
@@ -186,6 +214,27 @@ impl AppCache {
     pub fn named_temp_file(&self) -> std::io::Result<NamedTempFile> {
         tempfile::NamedTempFile::new_in(self.path())
     }
+
+    pub fn save<P: AsRef<Path>, T: bincode::Encode>(&self, relative_path: P, value: &T) -> crate::error::Result<()> {
+        let mut temp = self.named_temp_file()?;
+        let save_path = self.relative_path(&relative_path);
+
+        {
+            let mut bufwrite = std::io::BufWriter::new(&mut temp);
+            bincode::encode_into_std_write(value, &mut bufwrite, bincode::config::standard())?;
+            bufwrite.flush()?;
+        }
+
+        temp.persist(save_path.as_path())?;
+
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>, T: bincode::Decode<()>>(&self, relative_path: P) -> crate::error::Result<T> {
+        let file = self.open(&relative_path)?;
+        let mut reader = std::io::BufReader::new(file);
+        Ok(bincode::decode_from_std_read(&mut reader, bincode::config::standard())?)
+    }
 }
 
 /// [AppData] is a wrapper around an [Arc<AppdataInner>]. That means
@@ -222,6 +271,7 @@ impl AppDataInner {
 
     pub fn ensure_dirs(&self) -> std::io::Result<()> {
         std::fs::create_dir_all(self.config.path())?;
+        std::fs::create_dir_all(self.config.templates_path())?;
         std::fs::create_dir_all(self.cache.path())?;
         Ok(())
     }
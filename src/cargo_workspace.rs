@@ -0,0 +1,296 @@
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+
+pub const MANIFEST_FILE: &str = "Cargo.toml";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Toml Error: {0}")]
+    TomlError(#[from] toml::de::Error),
+    #[error("Invalid member pattern: {0}")]
+    GlobError(#[from] globset::Error),
+    #[error("{0} has no [workspace] section.")]
+    NotAWorkspace(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Example,
+}
+
+impl TargetKind {
+    pub const fn text(self) -> &'static str {
+        match self {
+            TargetKind::Lib => "lib",
+            TargetKind::Bin => "bin",
+            TargetKind::Example => "example",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Target {
+    pub kind: TargetKind,
+    pub name: String,
+}
+
+/// A member crate of a workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Package {
+    pub name: String,
+    /// `None` when the manifest has no version (it defaults to `0.0.0`).
+    pub version: Option<String>,
+    /// The directory that contains the package's `Cargo.toml`.
+    pub path: PathBuf,
+    pub targets: Vec<Target>,
+}
+
+impl Package {
+    #[inline]
+    pub fn count(&self, kind: TargetKind) -> usize {
+        self.targets.iter().filter(|target| target.kind == kind).count()
+    }
+
+    /// Case-insensitive match against the package name and target names.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self.targets.iter().any(|target| target.name.to_lowercase().contains(&query))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Workspace {
+    pub root: PathBuf,
+    /// Sorted by name. Includes the root package, if there is one.
+    pub members: Vec<Package>,
+}
+
+fn has_glob_chars(text: &str) -> bool {
+    text.contains(['*', '?', '[', '{'])
+}
+
+fn string_array<'a>(table: &'a toml::Table, key: &str) -> Vec<&'a str> {
+    table.get(key)
+        .and_then(toml::Value::as_array)
+        .map(|array| array.iter().filter_map(toml::Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Collects directories under `dir` whose path relative to `root` matches `matcher`.
+/// `depth` limits how far below `dir` to look. `None` is unlimited (the pattern contains `**`).
+fn expand_glob(root: &Path, dir: &Path, matcher: &GlobMatcher, depth: Option<usize>, found: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if depth == Some(0) {
+        return Ok(());
+    }
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in read_dir {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        if path.file_name().is_some_and(|name| name == "target" || name == ".git") {
+            continue;
+        }
+        if let Ok(relative) = path.strip_prefix(root) {
+            if matcher.is_match(relative) {
+                found.push(path.clone());
+            }
+        }
+        expand_glob(root, &path, matcher, depth.map(|depth| depth - 1), found)?;
+    }
+    Ok(())
+}
+
+/// Expands `[workspace] members`, removing `exclude`d directories and directories without a `Cargo.toml`.
+fn member_directories(root: &Path, members: &[&str], exclude: &[&str]) -> Result<Vec<PathBuf>, WorkspaceError> {
+    let mut directories = Vec::new();
+    for &pattern in members {
+        let pattern = pattern.trim_end_matches('/');
+        if !has_glob_chars(pattern) {
+            directories.push(root.join(pattern));
+            continue;
+        }
+        let components = pattern.split('/').collect::<Vec<_>>();
+        let literal_len = components.iter().take_while(|component| !has_glob_chars(component)).count();
+        let start = components[..literal_len].iter().fold(root.to_path_buf(), |dir, component| dir.join(component));
+        let depth = if pattern.contains("**") { None } else { Some(components.len() - literal_len) };
+        let matcher = GlobBuilder::new(pattern).literal_separator(true).build()?.compile_matcher();
+        expand_glob(root, &start, &matcher, depth, &mut directories)?;
+    }
+    let exclude = exclude.iter().map(|exclude| root.join(exclude.trim_end_matches('/'))).collect::<Vec<_>>();
+    directories.retain(|dir| !exclude.contains(dir) && dir.join(MANIFEST_FILE).is_file());
+    directories.sort();
+    directories.dedup();
+    Ok(directories)
+}
+
+/// Rust source files in `dir` (`name.rs`) and directories with a `main.rs` (`name/main.rs`).
+fn discover_targets(dir: &Path) -> Vec<String> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir.filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "rs") {
+                path.file_stem()?.to_str().map(str::to_owned)
+            } else if path.join("main.rs").is_file() {
+                path.file_name()?.to_str().map(str::to_owned)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Reads a package manifest. `workspace_package` is `[workspace.package]`, used for inherited fields.
+fn read_package(dir: &Path, manifest: &toml::Table, workspace_package: Option<&toml::Table>) -> Option<Package> {
+    let package = manifest.get("package")?.as_table()?;
+    let name = package.get("name")?.as_str()?.to_owned();
+    let version = match package.get("version") {
+        Some(toml::Value::String(version)) => Some(version.clone()),
+        // `version.workspace = true`
+        Some(toml::Value::Table(_)) => workspace_package
+            .and_then(|workspace| workspace.get("version"))
+            .and_then(toml::Value::as_str)
+            .map(str::to_owned),
+        _ => None,
+    };
+    let auto = |key: &str| package.get(key).and_then(toml::Value::as_bool).unwrap_or(true);
+    let explicit = |key: &str| -> Vec<String> {
+        manifest.get(key)
+            .and_then(toml::Value::as_array)
+            .map(|targets| targets.iter()
+                .filter_map(|target| target.get("name")?.as_str().map(str::to_owned))
+                .collect())
+            .unwrap_or_default()
+    };
+    let mut targets = Vec::new();
+    let lib = manifest.get("lib").and_then(toml::Value::as_table);
+    if lib.is_some() || dir.join("src").join("lib.rs").is_file() {
+        let lib_name = lib.and_then(|lib| lib.get("name")?.as_str())
+            .map(str::to_owned)
+            .unwrap_or_else(|| name.replace('-', "_"));
+        targets.push(Target { kind: TargetKind::Lib, name: lib_name });
+    }
+    let mut bins = explicit("bin");
+    if auto("autobins") {
+        if dir.join("src").join("main.rs").is_file() {
+            bins.push(name.clone());
+        }
+        bins.extend(discover_targets(&dir.join("src").join("bin")));
+    }
+    let mut examples = explicit("example");
+    if auto("autoexamples") {
+        examples.extend(discover_targets(&dir.join("examples")));
+    }
+    for (kind, mut names) in [(TargetKind::Bin, bins), (TargetKind::Example, examples)] {
+        names.sort();
+        names.dedup();
+        targets.extend(names.into_iter().map(|name| Target { kind, name }));
+    }
+    Some(Package {
+        name,
+        version,
+        path: dir.to_owned(),
+        targets,
+    })
+}
+
+impl Workspace {
+    /// Reads the workspace whose root `Cargo.toml` is in `root`.
+    pub fn load<P: AsRef<Path>>(root: P) -> Result<Self, WorkspaceError> {
+        fn inner(root: &Path) -> Result<Workspace, WorkspaceError> {
+            let manifest_path = root.join(MANIFEST_FILE);
+            let manifest: toml::Table = std::fs::read_to_string(&manifest_path)?.parse()?;
+            let workspace = manifest.get("workspace")
+                .and_then(toml::Value::as_table)
+                .ok_or(WorkspaceError::NotAWorkspace(manifest_path))?;
+            let workspace_package = workspace.get("package").and_then(toml::Value::as_table);
+            let mut members = Vec::new();
+            // The root package is always a member.
+            if let Some(package) = read_package(root, &manifest, workspace_package) {
+                members.push(package);
+            }
+            for dir in member_directories(root, &string_array(workspace, "members"), &string_array(workspace, "exclude"))? {
+                if dir == root {
+                    continue;
+                }
+                // A member that fails to parse is skipped rather than hiding the rest of the workspace.
+                let Ok(member_manifest) = std::fs::read_to_string(dir.join(MANIFEST_FILE))
+                    .map_err(WorkspaceError::from)
+                    .and_then(|text| Ok(text.parse::<toml::Table>()?)) else {
+                    continue;
+                };
+                if let Some(package) = read_package(&dir, &member_manifest, workspace_package) {
+                    members.push(package);
+                }
+            }
+            members.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+            Ok(Workspace {
+                root: root.to_owned(),
+                members,
+            })
+        }
+        inner(root.as_ref())
+    }
+}
+
+/// Returns `true` if `dir` has a `Cargo.toml` with a `[workspace]` section.
+pub fn is_workspace<P: AsRef<Path>>(dir: P) -> bool {
+    std::fs::read_to_string(dir.as_ref().join(MANIFEST_FILE))
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok())
+        .is_some_and(|manifest| manifest.contains_key("workspace"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_test() {
+        let root = tempfile::tempdir().expect("Failed to create temp dir.");
+        let root = root.path();
+        let write = |relative: &str, contents: &str| {
+            let path = root.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        write("Cargo.toml", "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\nexclude = [\"crates/old\"]\n\n[workspace.package]\nversion = \"1.2.3\"\n");
+        write("crates/core/Cargo.toml", "[package]\nname = \"my-core\"\nversion.workspace = true\n");
+        write("crates/core/src/lib.rs", "");
+        write("crates/core/examples/demo.rs", "fn main() {}");
+        write("crates/old/Cargo.toml", "[package]\nname = \"old\"\nversion = \"0.1.0\"\n");
+        write("crates/notes/README.md", "not a crate");
+        write("tools/cli/Cargo.toml", "[package]\nname = \"cli\"\nversion = \"0.4.0\"\n\n[[bin]]\nname = \"extra\"\npath = \"src/extra.rs\"\n");
+        write("tools/cli/src/main.rs", "fn main() {}");
+        write("tools/cli/src/bin/helper.rs", "fn main() {}");
+
+        assert!(is_workspace(root));
+        let workspace = Workspace::load(root).expect("Failed to load workspace.");
+        let names = workspace.members.iter().map(|member| member.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["cli", "my-core"]);
+        let cli = &workspace.members[0];
+        assert_eq!(cli.version.as_deref(), Some("0.4.0"));
+        assert_eq!(cli.count(TargetKind::Bin), 3);
+        assert_eq!(cli.count(TargetKind::Lib), 0);
+        let core = &workspace.members[1];
+        assert_eq!(core.version.as_deref(), Some("1.2.3"));
+        assert_eq!(core.targets, vec![
+            Target { kind: TargetKind::Lib, name: String::from("my_core") },
+            Target { kind: TargetKind::Example, name: String::from("demo") },
+        ]);
+        assert!(core.matches("DEMO"));
+        assert!(!core.matches("cli"));
+    }
+}
@@ -0,0 +1,559 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app::Persist,
+    appdata::AppData,
+    env::ProjectEnvs,
+    fuzzy::fuzzy_matches,
+    import::is_same_project,
+    instance::{send, try_lock, InstanceError, Message},
+    launch::{failure_report, run_launch, LaunchConfig, LaunchConfigs, LaunchStep, StepAction},
+    project_wizard::{ProjectWizard, WizardError},
+    projects::{scan_directories, ProjectPath, ProjectType},
+    settings::Settings,
+    shell::Shell,
+    templates::list_templates,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(&'static str),
+    #[error("Unknown command: {0}")]
+    UnknownCommand(String),
+    #[error("Unknown option: {0}")]
+    UnknownOption(String),
+    #[error("`{0}` requires a value.")]
+    MissingValue(String),
+    #[error("Unknown project type: {0}. Expected one of rust, python, web, or other.")]
+    UnknownProjectType(String),
+    #[error("No recent project matches `{0}`.")]
+    ProjectNotFound(String),
+    #[error("`{query}` matches more than one recent project:\n{}", matches.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join("\n"))]
+    AmbiguousProject {
+        query: String,
+        matches: Vec<PathBuf>,
+    },
+    #[error("{0} is not a directory.")]
+    NotADirectory(PathBuf),
+    #[error("{project} has no launch configuration named `{name}`.")]
+    LaunchConfigNotFound {
+        project: String,
+        name: String,
+    },
+    #[error("{0}")]
+    LaunchFailed(String),
+    #[error("There is no template named `{0}`.")]
+    TemplateNotFound(String),
+    #[error("Invalid template variable `{0}`. Expected <name>=<value>.")]
+    InvalidVariable(String),
+    #[error("{0}")]
+    WizardError(#[from] WizardError),
+    #[error("Failed to save recent projects: {0}")]
+    SaveFailed(#[from] crate::error::Error),
+    #[error("Failed to read {file}: {error}\nFix or remove the file and try again.")]
+    LoadFailed {
+        file: PathBuf,
+        error: crate::error::Error,
+    },
+    #[error("Failed to find the configuration directory: {0}")]
+    NoConfigDir(crate::error::Error),
+    #[error("Projector is running. {0}")]
+    Running(&'static str),
+    #[error("{0}")]
+    InstanceError(#[from] InstanceError),
+    #[error("Unknown shell: {0}. Expected one of bash, zsh, or fish.")]
+    UnknownShell(String),
+    #[error("Json Error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+const USAGE: &str = "\
+Usage: projector [command]
+       projector [<dir>...]
+
+Without a command, the window is opened, and the directories are added to the recent projects. If Projector is
+already running, the directories are sent to its window instead. A directory with the name of a command must be
+given as a path, such as ./scan.
+
+Commands:
+    list [--type <type>] [--json]  List the recent projects.
+    open <project>                 Open a project in the editor.
+    add <dir> [--type <type>]      Add a directory to the recent projects. The type is detected if --type is omitted.
+    remove <project>               Remove a project from the recent projects.
+    scan                           Add the projects in the project directories to the recent projects.
+    new <name> [options]           Create a project in the first project directory of its type.
+        --template <template>      The template that the project is created from.
+        --type <type>              The type of the project. Defaults to the template's type, or rust.
+        --location <dir>           The directory that the project directory is created in.
+        --description <text>       The description of the project.
+        --var <name>=<value>       The value of a template variable. Can be repeated.
+        --no-git                   Don't initialize a git repository.
+    launch <project> [<config>]    Run a launch configuration of a project. Lists the project's launch configurations if <config> is omitted.
+    path <project>                 Print the directory of a project.
+    shell <shell> [--name <name>]  Print the shell integration script for bash, zsh, or fish. It defines the function <name>
+                                   (default `pj`) that changes directory to a project, and completions for it and projector.
+                                   Load it with `eval \"$(projector shell bash)\"` in ~/.bashrc or ~/.zshrc,
+                                   or `projector shell fish | source` in ~/.config/fish/config.fish.
+    help                           Show this message.
+
+<project> is a directory or the name of a recent project. Names are matched fuzzily, so `prj` matches `projector`.
+<type> is one of rust, python, web, or other.";
+
+/// The arguments of a command. Options are removed as they're read, and the rest are the positional arguments.
+struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    fn new(args: &[String]) -> Self {
+        Self { args: args.to_vec() }
+    }
+
+    /// Removes `name` and returns whether it was present.
+    fn flag(&mut self, name: &str) -> bool {
+        let len = self.args.len();
+        self.args.retain(|arg| arg != name);
+        self.args.len() != len
+    }
+
+    /// Removes every `name <value>` and `name=<value>` and returns the values.
+    fn options(&mut self, name: &str) -> Result<Vec<String>, CliError> {
+        let mut values = Vec::new();
+        let mut index = 0;
+        while index < self.args.len() {
+            if self.args[index] == name {
+                if index + 1 >= self.args.len() {
+                    return Err(CliError::MissingValue(name.to_owned()));
+                }
+                values.push(self.args.remove(index + 1));
+                self.args.remove(index);
+            } else if let Some(value) = self.args[index].strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+                values.push(value.to_owned());
+                self.args.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Removes `name <value>` or `name=<value>` and returns the value. The last value wins if there is more than one.
+    fn option(&mut self, name: &str) -> Result<Option<String>, CliError> {
+        Ok(self.options(name)?.pop())
+    }
+
+    fn project_type(&mut self) -> Result<Option<ProjectType>, CliError> {
+        self.option("--type")?
+            .map(|text| ProjectType::from_text(&text).ok_or(CliError::UnknownProjectType(text)))
+            .transpose()
+    }
+
+    /// The positional arguments. Fails if an option wasn't read.
+    fn positional(self) -> Result<Vec<String>, CliError> {
+        if let Some(option) = self.args.iter().find(|arg| arg.starts_with("--")) {
+            return Err(CliError::UnknownOption(option.clone()));
+        }
+        Ok(self.args)
+    }
+}
+
+/// The name of a project, which is the name of its directory.
+fn project_name(path: &Path) -> &str {
+    path.file_name().and_then(|name| name.to_str()).unwrap_or("")
+}
+
+/// The data that commands read. Loaded without creating a window.
+struct Context {
+    app_data: AppData,
+    settings: Settings,
+    persist: Persist,
+    /// The instance lock, held by commands that change the recent projects. It's `None` if Projector is running,
+    /// because the window keeps its own recent projects and saves them over the file when it exits.
+    lock: Option<File>,
+}
+
+impl Context {
+    fn load() -> Result<Self, CliError> {
+        let app_data = AppData::from("com", "erisianarchitect", "projector").map_err(CliError::NoConfigDir)?;
+        let settings = app_data.config().load_settings().unwrap_or_default();
+        let persist = app_data.config().load_or_default(Persist::REL_PATH)
+            .map_err(|error| CliError::LoadFailed { file: app_data.config().relative_path(Persist::REL_PATH), error })?;
+        Ok(Self {
+            app_data,
+            settings,
+            persist,
+            lock: None,
+        })
+    }
+
+    /// Loads the data for a command that changes the recent projects, and takes the instance lock if Projector isn't
+    /// running.
+    fn load_exclusive() -> Result<Self, CliError> {
+        let mut context = Self::load()?;
+        context.app_data.ensure_dirs().map_err(crate::error::Error::from)?;
+        context.lock = try_lock(context.app_data.config().path())?;
+        Ok(context)
+    }
+
+    fn launch_configs(&self) -> Result<LaunchConfigs, CliError> {
+        let config = self.app_data.config();
+        LaunchConfigs::load(config)
+            .map_err(|error| CliError::LoadFailed { file: config.relative_path(LaunchConfigs::CONFIG_REL_PATH), error })
+    }
+
+    fn env_pairs_for(&self, project: &Path) -> Result<Vec<(String, String)>, CliError> {
+        let config = self.app_data.config();
+        let envs = ProjectEnvs::load(config)
+            .map_err(|error| CliError::LoadFailed { file: config.relative_path(ProjectEnvs::CONFIG_REL_PATH), error })?;
+        Ok(envs.pairs_for(project))
+    }
+
+    fn is_running(&self) -> bool {
+        self.lock.is_none()
+    }
+
+    /// Sends the messages to the running window, which applies them to its recent projects.
+    fn send_to_window(&self, messages: &[Message]) -> Result<(), CliError> {
+        send(self.app_data.config().path(), messages)?;
+        Ok(())
+    }
+
+    fn save_persist(&self) -> Result<(), CliError> {
+        if self.is_running() {
+            return Err(CliError::Running("Close it, then try again."));
+        }
+        self.app_data.config().save(Persist::REL_PATH, &self.persist)?;
+        Ok(())
+    }
+
+    /// Adds a project to the recent projects. Returns `false` if it's already a recent project.
+    fn add_recent(&mut self, project: ProjectPath) -> bool {
+        if self.persist.recent_projects().iter().any(|recent| is_same_project(recent.path(), project.path())) {
+            return false;
+        }
+        self.persist.recent_projects_mut().push_back(project);
+        true
+    }
+
+    /// Finds a project by directory, or by the name of a recent project (case-insensitive). Names are matched
+    /// fuzzily, and the full path is matched if no name matches.
+    fn find_project(&self, query: &str) -> Result<PathBuf, CliError> {
+        let path = Path::new(query);
+        if path.is_dir() {
+            return Ok(std::path::absolute(path).unwrap_or_else(|_| path.to_owned()));
+        }
+        let recents = self.persist.recent_projects().iter()
+            .map(|project| (project.path(), project.path().display().to_string()))
+            .collect::<Vec<_>>();
+        let mut matches = fuzzy_matches(query, recents.iter(), |(path, _)| project_name(path));
+        if matches.is_empty() {
+            matches = fuzzy_matches(query, recents.iter(), |(_, text)| text.as_str());
+        }
+        let Some(&(best, _)) = matches.first() else {
+            return Err(CliError::ProjectNotFound(query.to_owned()));
+        };
+        let mut best_matches = matches.into_iter()
+            .take_while(|&(score, _)| score == best)
+            .map(|(_, (path, _))| path.to_path_buf())
+            .collect::<Vec<_>>();
+        if best_matches.len() == 1 {
+            Ok(best_matches.remove(0))
+        } else {
+            Err(CliError::AmbiguousProject { query: query.to_owned(), matches: best_matches })
+        }
+    }
+}
+
+fn list(context: &Context, args: &[String]) -> Result<(), CliError> {
+    let mut args = Args::new(args);
+    let json = args.flag("--json");
+    let project_type = args.project_type()?;
+    if !args.positional()?.is_empty() {
+        return Err(CliError::Usage("Usage: projector list [--type <type>] [--json]"));
+    }
+    let projects = context.persist.recent_projects().iter()
+        .filter(|project| project_type.is_none_or(|project_type| project.project_type() == project_type))
+        .collect::<Vec<_>>();
+    if json {
+        let projects = projects.iter()
+            .map(|project| serde_json::json!({
+                "name": project_name(project.path()),
+                "type": project.project_type().text().to_lowercase(),
+                "path": project.path().display().to_string(),
+            }))
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&projects)?);
+        return Ok(());
+    }
+    let name_width = projects.iter().map(|project| project_name(project.path()).chars().count()).max().unwrap_or(0);
+    for project in projects {
+        println!("{:<name_width$}  {:<6}  {}", project_name(project.path()), project.project_type().text(), project.path().display());
+    }
+    Ok(())
+}
+
+fn open(context: &Context, args: &[String]) -> Result<(), CliError> {
+    let [query] = Args::new(args).positional()?.try_into().map_err(|_| CliError::Usage("Usage: projector open <project>"))?;
+    let project = context.find_project(&query)?;
+    let config = LaunchConfig {
+        name: String::from("Open Editor"),
+        steps: vec![LaunchStep::new(StepAction::OpenEditor)],
+    };
+    let env = context.env_pairs_for(&project)?;
+    let failures = run_launch(&context.settings.general, &project, &env, &config);
+    if !failures.is_empty() {
+        return Err(CliError::LaunchFailed(failure_report(&config, &failures)));
+    }
+    println!("{}", project.display());
+    Ok(())
+}
+
+fn add(context: &mut Context, args: &[String]) -> Result<(), CliError> {
+    let mut args = Args::new(args);
+    let project_type = args.project_type()?;
+    let [dir] = args.positional()?.try_into().map_err(|_| CliError::Usage("Usage: projector add <dir> [--type <type>]"))?;
+    let dir = PathBuf::from(dir);
+    if !dir.is_dir() {
+        return Err(CliError::NotADirectory(dir));
+    }
+    let dir = std::path::absolute(&dir).unwrap_or(dir);
+    if context.is_running() {
+        context.send_to_window(&[Message::Open(dir.clone(), project_type)])?;
+        println!("Sent {} to the running Projector.", dir.display());
+        return Ok(());
+    }
+    let project_type = project_type.or_else(|| ProjectType::detect(&dir)).unwrap_or(ProjectType::Other);
+    if context.add_recent(ProjectPath::from_type(project_type, &dir)) {
+        context.save_persist()?;
+        println!("Added {} ({}).", dir.display(), project_type.text());
+    } else {
+        println!("{} is already a recent project.", dir.display());
+    }
+    Ok(())
+}
+
+fn remove(context: &mut Context, args: &[String]) -> Result<(), CliError> {
+    let [query] = Args::new(args).positional()?.try_into().map_err(|_| CliError::Usage("Usage: projector remove <project>"))?;
+    let project = context.find_project(&query)?;
+    if context.is_running() {
+        context.send_to_window(&[Message::Remove(project.clone())])?;
+        println!("Sent the removal of {} to the running Projector.", project.display());
+        return Ok(());
+    }
+    let recents = context.persist.recent_projects_mut();
+    let len = recents.len();
+    recents.retain(|recent| !is_same_project(recent.path(), &project));
+    if recents.len() == len {
+        return Err(CliError::ProjectNotFound(query));
+    }
+    context.save_persist()?;
+    println!("Removed {}.", project.display());
+    Ok(())
+}
+
+fn scan(context: &mut Context, args: &[String]) -> Result<(), CliError> {
+    if !Args::new(args).positional()?.is_empty() {
+        return Err(CliError::Usage("Usage: projector scan"));
+    }
+    let roots = context.settings.projects.scan_roots();
+    if roots.is_empty() {
+        println!("There are no project directories. Add them in Settings > Projects.");
+        return Ok(());
+    }
+    let mut added = Vec::new();
+    for (project, _) in scan_directories(roots) {
+        let path = project.path().to_path_buf();
+        let project_type = project.project_type();
+        if context.add_recent(project) {
+            println!("Added {} ({}).", path.display(), project_type.text());
+            added.push(Message::Open(path, Some(project_type)));
+        }
+    }
+    let count = added.len();
+    if count > 0 {
+        if context.is_running() {
+            context.send_to_window(&added)?;
+        } else {
+            context.save_persist()?;
+        }
+    }
+    println!("Found {count} new project{}.", if count == 1 { "" } else { "s" });
+    Ok(())
+}
+
+fn new(context: &mut Context, args: &[String]) -> Result<(), CliError> {
+    const USAGE: &str = "Usage: projector new <name> [--template <template>] [--type <type>] [--location <dir>] [--description <text>] [--var <name>=<value>]... [--no-git]";
+    let mut args = Args::new(args);
+    let template = args.option("--template")?;
+    let project_type = args.project_type()?;
+    let location = args.option("--location")?;
+    let description = args.option("--description")?;
+    let variables = args.options("--var")?;
+    let no_git = args.flag("--no-git");
+    let [name] = args.positional()?.try_into().map_err(|_| CliError::Usage(USAGE))?;
+    let templates = list_templates(context.app_data.config().templates_path());
+    let mut wizard = ProjectWizard::new(&context.settings, project_type.unwrap_or(ProjectType::Rust), templates)
+        .with_name(name)
+        .with_description(description.unwrap_or_default());
+    if let Some(template) = template {
+        if !wizard.select_template_named(&template) {
+            return Err(CliError::TemplateNotFound(template));
+        }
+        // The template's type is the default, but an explicit type wins.
+        if let Some(project_type) = project_type {
+            wizard.set_project_type(project_type);
+        }
+    }
+    if let Some(location) = location {
+        wizard = wizard.with_location(location);
+    }
+    if no_git {
+        wizard = wizard.with_init_git(false);
+    }
+    for variable in variables {
+        let (name, value) = variable.split_once('=').ok_or_else(|| CliError::InvalidVariable(variable.clone()))?;
+        wizard.set_variable(name, value);
+    }
+    let created = wizard.create(&context.settings)?;
+    if let Some(err) = created.git_error {
+        eprintln!("The project was created, but the git repository could not be initialized.\n{err}");
+    }
+    let path = created.project.path().to_path_buf();
+    if context.is_running() {
+        context.send_to_window(&[Message::Open(path.clone(), Some(created.project.project_type()))])?;
+    } else if context.add_recent(created.project) {
+        context.save_persist()?;
+    }
+    println!("{}", path.display());
+    Ok(())
+}
+
+fn launch(context: &Context, args: &[String]) -> Result<(), CliError> {
+    let [project, rest @ ..] = args else {
+        return Err(CliError::Usage("Usage: projector launch <project> [<config>]"));
+    };
+    let project = context.find_project(project)?;
+    let configs = context.launch_configs()?;
+    let Some(name) = rest.first() else {
+        for config in configs.get(&project) {
+            println!("{}", config.name);
+        }
+        return Ok(());
+    };
+    let config = configs.get(&project).iter()
+        .find(|config| &config.name == name)
+        .ok_or_else(|| CliError::LaunchConfigNotFound { project: project.display().to_string(), name: name.clone() })?;
+    let env = context.env_pairs_for(&project)?;
+    let failures = run_launch(&context.settings.general, &project, &env, config);
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::LaunchFailed(failure_report(config, &failures)))
+    }
+}
+
+fn path(context: &Context, args: &[String]) -> Result<(), CliError> {
+    let [query] = Args::new(args).positional()?.try_into().map_err(|_| CliError::Usage("Usage: projector path <project>"))?;
+    println!("{}", context.find_project(&query)?.display());
+    Ok(())
+}
+
+fn shell(args: &[String]) -> Result<(), CliError> {
+    const USAGE: &str = "Usage: projector shell <bash|zsh|fish> [--name <name>]";
+    let mut args = Args::new(args);
+    let function = args.option("--name")?.unwrap_or_else(|| String::from("pj"));
+    if function.is_empty() || !function.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(CliError::Usage(USAGE));
+    }
+    let [name] = args.positional()?.try_into().map_err(|_| CliError::Usage(USAGE))?;
+    let shell = Shell::from_name(&name).ok_or(CliError::UnknownShell(name))?;
+    let program = std::env::current_exe()
+        .map(|exe| exe.display().to_string())
+        .unwrap_or_else(|_| String::from("projector"));
+    print!("{}", shell.init_script(&program, &function));
+    if std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        eprintln!("\nTo load this script in every shell, add this to your shell's startup file:\n    {}", shell.install_hint());
+    }
+    Ok(())
+}
+
+/// Prints the names of the recent projects for shell completion.
+fn complete(context: &Context) -> Result<(), CliError> {
+    let mut names = context.persist.recent_projects().iter()
+        .map(|project| project_name(project.path()))
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    for name in names {
+        println!("{name}");
+    }
+    Ok(())
+}
+
+/// The names that [run] treats as commands rather than directories.
+pub const COMMANDS: [&str; 13] = [
+    "list", "open", "add", "remove", "scan", "new", "launch", "path", "shell", "complete", "help", "--help", "-h",
+];
+
+/// Runs a command from the command-line arguments (without the program name). Returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    let Some((command, args)) = args.split_first() else {
+        println!("{USAGE}");
+        return 0;
+    };
+    let result = match command.as_str() {
+        "list" => Context::load().and_then(|context| list(&context, args)),
+        "open" => Context::load().and_then(|context| open(&context, args)),
+        "add" => Context::load_exclusive().and_then(|mut context| add(&mut context, args)),
+        "remove" => Context::load_exclusive().and_then(|mut context| remove(&mut context, args)),
+        "scan" => Context::load_exclusive().and_then(|mut context| scan(&mut context, args)),
+        "new" => Context::load_exclusive().and_then(|mut context| new(&mut context, args)),
+        "launch" => Context::load().and_then(|context| launch(&context, args)),
+        "path" => Context::load().and_then(|context| path(&context, args)),
+        "shell" => shell(args),
+        "complete" => Context::load().and_then(|context| complete(&context)),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(CliError::UnknownCommand(command.clone())),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("{err}");
+            if matches!(err, CliError::UnknownCommand(_)) {
+                eprintln!("{USAGE}");
+            }
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_test() {
+        let args = ["app", "--type", "rust", "--var=a=1", "--json", "--var", "b=2"].map(String::from);
+        let mut args = Args::new(&args);
+        assert!(args.flag("--json"));
+        assert!(!args.flag("--no-git"));
+        assert_eq!(args.project_type().unwrap(), Some(ProjectType::Rust));
+        assert_eq!(args.options("--var").unwrap(), ["a=1", "b=2"]);
+        assert_eq!(args.positional().unwrap(), ["app"]);
+
+        let mut args = Args::new(&["--type".to_owned()]);
+        assert!(matches!(args.option("--type"), Err(CliError::MissingValue(_))));
+        let args = Args::new(&["--nope".to_owned()]);
+        assert!(matches!(args.positional(), Err(CliError::UnknownOption(_))));
+        let mut args = Args::new(&["--type=cobol".to_owned()]);
+        assert!(matches!(args.project_type(), Err(CliError::UnknownProjectType(_))));
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    hash::Hash,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use eframe::egui::{
+    text::{CCursor, CCursorRange},
+    text_edit::TextEditState,
+    *,
+};
+
+use crate::{
+    dgui::directory_view::{complete_path, DirectoryView, PathType},
+    projects::ProjectType,
+    settings::{FileDialogMode, Settings},
+};
+
+/// The relative path of the directory browser's bookmarks in `<program>/config/`
+pub const BOOKMARKS_REL_PATH: &str = ".bookmarks";
+
+/// The most completions that are listed under the path field.
+const MAX_COMPLETIONS: usize = 8;
+
+/// Shared by every [PathPicker]. The mode and project roots follow the settings, and the bookmarks are persisted by the app.
+#[derive(Debug, Clone)]
+struct BrowserOptions {
+    mode: FileDialogMode,
+    roots: Vec<(ProjectType, PathBuf)>,
+    bookmarks: Vec<PathBuf>,
+    bookmarks_changed: bool,
+}
+
+impl Default for BrowserOptions {
+    fn default() -> Self {
+        Self {
+            mode: FileDialogMode::Native,
+            roots: Vec::new(),
+            bookmarks: Vec::new(),
+            bookmarks_changed: false,
+        }
+    }
+}
+
+fn options(ctx: &Context) -> Arc<Mutex<BrowserOptions>> {
+    ctx.data_mut(|data| data.get_temp_mut_or_default::<Arc<Mutex<BrowserOptions>>>(Id::new("dir_browser_options")).clone())
+}
+
+/// Sets the bookmarks that were loaded from [BOOKMARKS_REL_PATH].
+pub fn set_bookmarks(ctx: &Context, bookmarks: Vec<PathBuf>) {
+    options(ctx).lock().unwrap().bookmarks = bookmarks;
+}
+
+/// Updates the dialog mode and the project roots from `settings`.
+pub fn update_options(ctx: &Context, settings: &Settings) {
+    let roots = settings.projects.scan_roots();
+    let options = options(ctx);
+    let mut options = options.lock().unwrap();
+    options.mode = settings.general.file_dialog;
+    options.roots = roots;
+}
+
+/// Returns the bookmarks if they were changed since the last call.
+pub fn take_changed_bookmarks(ctx: &Context) -> Option<Vec<PathBuf>> {
+    let options = options(ctx);
+    let mut options = options.lock().unwrap();
+    std::mem::take(&mut options.bookmarks_changed).then(|| options.bookmarks.clone())
+}
+
+fn home_dir() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf())
+}
+
+/// The text of a path in the path field. Directories end with a separator so that their entries are completed.
+fn path_text(path: &Path, is_dir: bool) -> String {
+    let mut text = path.display().to_string();
+    if is_dir && !text.ends_with(std::path::is_separator) {
+        text.push(std::path::MAIN_SEPARATOR);
+    }
+    text
+}
+
+enum BrowserAction {
+    Picked(PathBuf),
+    Cancelled,
+}
+
+/// An egui directory browser. Used instead of the native file dialog when [FileDialogMode::BuiltIn] is chosen.
+struct DirectoryBrowser {
+    title: &'static str,
+    /// The type of path that is picked.
+    pick: PathType,
+    view: DirectoryView,
+    selected: Option<PathBuf>,
+    path_text: String,
+    completions: Vec<PathBuf>,
+    error: Option<String>,
+}
+
+impl DirectoryBrowser {
+    fn new(title: &'static str, pick: PathType, start: Option<PathBuf>) -> Self {
+        // Files are picked from a view that also lists directories so that they can be navigated into.
+        let view_types = match pick {
+            PathType::Directories => PathType::Directories,
+            _ => PathType::FilesAndDirectories,
+        };
+        let start = start.filter(|dir| dir.is_dir())
+            .or_else(home_dir)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from(std::path::MAIN_SEPARATOR_STR));
+        let (view, error) = match DirectoryView::new(start, view_types).and_populate() {
+            Ok(view) => (view, None),
+            Err(err) => (err.view, Some(err.err.to_string())),
+        };
+        let path_text = path_text(view.root(), true);
+        Self {
+            title,
+            pick,
+            view,
+            selected: None,
+            path_text,
+            completions: Vec::new(),
+            error,
+        }
+    }
+
+    fn navigate(&mut self, dir: PathBuf) {
+        match self.view.navigate(dir) {
+            Ok(()) => {
+                self.error = None;
+                self.selected = None;
+                self.path_text = path_text(self.view.root(), true);
+                self.completions.clear();
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    fn update_completions(&mut self) {
+        self.completions = complete_path(&self.path_text, self.pick, self.view.show_hidden());
+        self.completions.truncate(MAX_COMPLETIONS);
+    }
+
+    /// The path that "Select" picks.
+    fn picked(&self) -> Option<PathBuf> {
+        match self.pick {
+            PathType::Directories => Some(self.selected.clone().unwrap_or_else(|| self.view.root().to_path_buf())),
+            _ => self.selected.clone().filter(|path| self.pick.path_is_type(path)),
+        }
+    }
+
+    /// Opens a directory, or picks a path of the picked type.
+    fn activate(&mut self, path: PathBuf) -> Option<BrowserAction> {
+        if path.is_dir() {
+            self.navigate(path);
+            None
+        } else if self.pick.path_is_type(&path) {
+            Some(BrowserAction::Picked(path))
+        } else {
+            self.error = Some(format!("{} does not exist.", path.display()));
+            None
+        }
+    }
+
+    fn breadcrumbs_ui(&mut self, ui: &mut Ui) {
+        let ancestors = self.view.root().ancestors().map(Path::to_path_buf).collect::<Vec<_>>();
+        let mut navigate = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+            for (index, dir) in ancestors.iter().rev().enumerate() {
+                if index > 1 {
+                    ui.label("›");
+                }
+                let name = dir.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| dir.display().to_string());
+                if ui.selectable_label(index + 1 == ancestors.len(), name).clicked() {
+                    navigate = Some(dir.clone());
+                }
+            }
+        });
+        if let Some(dir) = navigate {
+            self.navigate(dir);
+        }
+    }
+
+    fn path_ui(&mut self, ui: &mut Ui) -> Option<BrowserAction> {
+        let id = ui.id().with("dir_browser_path");
+        // Tab completes the path instead of moving the focus.
+        if ui.memory(|memory| memory.has_focus(id))
+            && !self.completions.is_empty()
+            && ui.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Tab)) {
+            let completion = self.completions[0].clone();
+            self.path_text = path_text(&completion, completion.is_dir());
+            self.update_completions();
+            if let Some(mut state) = TextEditState::load(ui.ctx(), id) {
+                let end = self.path_text.chars().count();
+                state.cursor.set_char_range(Some(CCursorRange::one(CCursor::new(end))));
+                state.store(ui.ctx(), id);
+            }
+        }
+        let output = TextEdit::singleline(&mut self.path_text)
+            .id(id)
+            .hint_text("Type a path")
+            .desired_width(ui.available_width())
+            .show(ui);
+        if output.response.changed() {
+            self.update_completions();
+        }
+        let mut action = None;
+        if output.response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)) {
+            action = self.activate(PathBuf::from(self.path_text.trim()));
+        }
+        let mut clicked = None;
+        for completion in self.completions.iter() {
+            let name = completion.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let icon = if completion.is_dir() { "📁" } else { "📄" };
+            if ui.selectable_label(false, format!("   {icon} {name}")).clicked() {
+                clicked = Some(completion.clone());
+            }
+        }
+        if let Some(path) = clicked {
+            action = self.activate(path);
+        }
+        action
+    }
+
+    fn places_ui(&mut self, ui: &mut Ui, options: &mut BrowserOptions) {
+        let mut navigate = None;
+        let mut remove = None;
+        ui.strong("Places");
+        if let Some(home) = home_dir() {
+            if ui.selectable_label(false, "🏠 Home").on_hover_text(home.display().to_string()).clicked() {
+                navigate = Some(home);
+            }
+        }
+        if !options.roots.is_empty() {
+            ui.add_space(4.0);
+            ui.strong("Project Roots");
+            for (project_type, dir) in options.roots.iter() {
+                let name = dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| dir.display().to_string());
+                if ui.selectable_label(self.view.root() == dir, format!("{name} ({})", project_type.text()))
+                    .on_hover_text(dir.display().to_string())
+                    .clicked() {
+                    navigate = Some(dir.clone());
+                }
+            }
+        }
+        ui.add_space(4.0);
+        ui.strong("Bookmarks");
+        if options.bookmarks.is_empty() {
+            ui.label(RichText::new("No bookmarks").color(Color32::GRAY));
+        }
+        for (index, dir) in options.bookmarks.iter().enumerate() {
+            let name = dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| dir.display().to_string());
+            let response = ui.selectable_label(self.view.root() == dir, format!("★ {name}"))
+                .on_hover_text(dir.display().to_string());
+            if response.clicked() {
+                navigate = Some(dir.clone());
+            }
+            response.context_menu(|ui| {
+                if ui.button("Remove Bookmark").clicked() {
+                    remove = Some(index);
+                    ui.close_menu();
+                }
+            });
+        }
+        if let Some(index) = remove {
+            options.bookmarks.remove(index);
+            options.bookmarks_changed = true;
+        }
+        if let Some(dir) = navigate {
+            self.navigate(dir);
+        }
+    }
+
+    fn entries_ui(&mut self, ui: &mut Ui) -> Option<BrowserAction> {
+        let mut activate = None;
+        let mut select = None;
+        ScrollArea::vertical()
+            .id_salt("dir_browser_entries")
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                if self.view.entries().is_empty() {
+                    ui.label(RichText::new("This directory is empty.").color(Color32::GRAY));
+                }
+                for path in self.view.entries() {
+                    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                    let icon = if path.is_dir() { "📁" } else { "📄" };
+                    let response = ui.selectable_label(self.selected.as_ref() == Some(path), format!("{icon} {name}"));
+                    if response.double_clicked() {
+                        activate = Some(path.clone());
+                    } else if response.clicked() {
+                        select = Some(path.clone());
+                    }
+                }
+            });
+        if let Some(path) = select {
+            self.selected = Some(path);
+        }
+        activate.and_then(|path| self.activate(path))
+    }
+
+    fn show(&mut self, ctx: &Context, options: &mut BrowserOptions) -> Option<BrowserAction> {
+        let mut action = None;
+        let response = Modal::new(Id::new("dir_browser_modal")).show(ctx, |ui| {
+            ui.set_width(640.0);
+            ui.horizontal(|ui| {
+                ui.heading(self.title);
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    let mut show_hidden = self.view.show_hidden();
+                    if ui.checkbox(&mut show_hidden, "Show Hidden").changed() {
+                        if let Err(err) = self.view.set_show_hidden(show_hidden) {
+                            self.error = Some(err.to_string());
+                        }
+                        self.update_completions();
+                    }
+                    let root = self.view.root().to_path_buf();
+                    let bookmarked = options.bookmarks.contains(&root);
+                    let bookmark_text = if bookmarked { "★ Bookmarked" } else { "☆ Bookmark" };
+                    if ui.button(bookmark_text).on_hover_text("Bookmark the current directory.").clicked() {
+                        if bookmarked {
+                            options.bookmarks.retain(|dir| dir != &root);
+                        } else {
+                            options.bookmarks.push(root);
+                        }
+                        options.bookmarks_changed = true;
+                    }
+                    if ui.add_enabled(self.view.root().parent().is_some(), Button::new("⬆ Up")).clicked() {
+                        if let Some(parent) = self.view.root().parent().map(Path::to_path_buf) {
+                            self.navigate(parent);
+                        }
+                    }
+                });
+            });
+            ui.separator();
+            self.breadcrumbs_ui(ui);
+            action = self.path_ui(ui);
+            if let Some(err) = &self.error {
+                ui.colored_label(Color32::RED, err);
+            }
+            ui.separator();
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.set_width(170.0);
+                    ScrollArea::vertical()
+                        .id_salt("dir_browser_places")
+                        .max_height(300.0)
+                        .show(ui, |ui| self.places_ui(ui, options));
+                });
+                ui.separator();
+                ui.vertical(|ui| {
+                    ui.set_height(300.0);
+                    if let Some(entry_action) = self.entries_ui(ui) {
+                        action = Some(entry_action);
+                    }
+                });
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                let picked = self.picked();
+                let picked_text = picked.as_ref().map(|path| path.display().to_string()).unwrap_or_default();
+                if ui.add_enabled(picked.is_some(), Button::new("Select")).clicked() {
+                    action = picked.map(BrowserAction::Picked);
+                }
+                if ui.button("Cancel").clicked() {
+                    action = Some(BrowserAction::Cancelled);
+                }
+                ui.label(RichText::new(picked_text).monospace().color(Color32::GRAY));
+            });
+        });
+        if response.should_close() && action.is_none() {
+            action = Some(BrowserAction::Cancelled);
+        }
+        action
+    }
+}
+
+/// Picks a directory or a file with the dialog that is chosen in Settings > General > File Dialog.
+///
+/// The built-in browser stays open over several frames, so [PathPicker::show] must be called every frame with the
+/// same id, and the picked path is returned by a later call.
+pub struct PathPicker {
+    id: Id,
+    pick: PathType,
+    title: &'static str,
+    start_dir: Option<PathBuf>,
+}
+
+impl PathPicker {
+    pub fn folder(id_salt: impl Hash) -> Self {
+        Self {
+            id: Id::new(("path_picker", id_salt)),
+            pick: PathType::Directories,
+            title: "Select Directory",
+            start_dir: None,
+        }
+    }
+
+    pub fn file(id_salt: impl Hash) -> Self {
+        Self {
+            id: Id::new(("path_picker", id_salt)),
+            pick: PathType::Files,
+            title: "Select File",
+            start_dir: None,
+        }
+    }
+
+    /// The directory that the dialog starts in.
+    pub fn start_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.start_dir = Some(dir.into());
+        self
+    }
+
+    /// Opens the dialog when `open` is true. Returns the picked path.
+    pub fn show(self, ctx: &Context, open: bool) -> Option<PathBuf> {
+        let options = options(ctx);
+        let mode = options.lock().unwrap().mode;
+        if open {
+            match mode {
+                FileDialogMode::Native => {
+                    let mut dialog = rfd::FileDialog::new();
+                    if let Some(dir) = &self.start_dir {
+                        dialog = dialog.set_directory(dir);
+                    }
+                    return match self.pick {
+                        PathType::Directories => dialog.pick_folder(),
+                        _ => dialog.pick_file(),
+                    };
+                }
+                FileDialogMode::BuiltIn => {
+                    let browser = DirectoryBrowser::new(self.title, self.pick, self.start_dir.clone());
+                    ctx.data_mut(|data| data.insert_temp(self.id, Arc::new(Mutex::new(browser))));
+                }
+            }
+        }
+        let browser = ctx.data(|data| data.get_temp::<Arc<Mutex<DirectoryBrowser>>>(self.id))?;
+        let action = browser.lock().unwrap().show(ctx, &mut options.lock().unwrap())?;
+        ctx.data_mut(|data| data.remove::<Arc<Mutex<DirectoryBrowser>>>(self.id));
+        match action {
+            BrowserAction::Picked(path) => Some(path),
+            BrowserAction::Cancelled => None,
+        }
+    }
+}
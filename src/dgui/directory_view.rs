@@ -1,7 +1,5 @@
 use std::path::{Path, PathBuf};
 
-
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PathType {
     Files,
@@ -32,18 +30,71 @@ pub struct PopulateError {
 pub struct DirectoryView {
     root: PathBuf,
     path_types: PathType,
+    show_hidden: bool,
     entries: Vec<PathBuf>,
 }
 
+/// Whether a path is hidden. Paths that start with a `.` are hidden.
+pub fn is_hidden<P: AsRef<Path>>(path: P) -> bool {
+    path.as_ref().file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// Sorts directories before files, then by name, ignoring case.
+fn sort_entries(entries: &mut [PathBuf]) {
+    entries.sort_by_cached_key(|path| (
+        !path.is_dir(),
+        path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default(),
+    ));
+}
+
+/// The entries that `text` can be completed to. The last component of `text` is matched as a prefix of the
+/// names in its parent directory, ignoring case. A `text` that ends with a separator lists its directory.
+pub fn complete_path(text: &str, path_types: PathType, show_hidden: bool) -> Vec<PathBuf> {
+    let (dir, prefix) = if text.ends_with(std::path::is_separator) {
+        (Path::new(text), "")
+    } else {
+        let path = Path::new(text);
+        match (path.parent(), path.file_name().and_then(|name| name.to_str())) {
+            (Some(parent), Some(name)) => (parent, name),
+            _ => return Vec::new(),
+        }
+    };
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let Ok(read_dir) = dir.read_dir() else {
+        return Vec::new();
+    };
+    let prefix = prefix.to_lowercase();
+    // Hidden entries are completed when their name is being typed.
+    let show_hidden = show_hidden || prefix.starts_with('.');
+    let mut completions = read_dir.filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.to_lowercase().starts_with(&prefix)))
+        .filter(|path| show_hidden || !is_hidden(path))
+        .filter(|path| path_types.path_is_type(path) || path.is_dir())
+        .collect::<Vec<_>>();
+    sort_entries(&mut completions);
+    completions
+}
+
 impl DirectoryView {
     pub fn new<P: Into<PathBuf>>(root: P, path_types: PathType) -> Self {
         Self {
             root: root.into(),
             path_types,
+            show_hidden: false,
             entries: Vec::new(),
         }
     }
 
+    pub fn with_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self
+    }
+
     pub fn and_populate(mut self) -> Result<Self, PopulateError> {
         match self.refresh() {
             Ok(()) => Ok(self),
@@ -54,15 +105,101 @@ impl DirectoryView {
         }
     }
 
+    #[inline]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    #[inline]
+    pub fn path_types(&self) -> PathType {
+        self.path_types
+    }
+
+    #[inline]
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    /// The entries of the root, directories first.
+    #[inline]
+    pub fn entries(&self) -> &[PathBuf] {
+        &self.entries
+    }
+
+    pub fn set_show_hidden(&mut self, show_hidden: bool) -> std::io::Result<()> {
+        if self.show_hidden == show_hidden {
+            return Ok(());
+        }
+        self.show_hidden = show_hidden;
+        self.refresh()
+    }
+
+    /// Changes the root to `dir`. The root is unchanged if `dir` can't be read.
+    pub fn navigate<P: Into<PathBuf>>(&mut self, dir: P) -> std::io::Result<()> {
+        let previous = std::mem::replace(&mut self.root, dir.into());
+        let previous_entries = std::mem::take(&mut self.entries);
+        if let Err(err) = self.refresh() {
+            self.root = previous;
+            self.entries = previous_entries;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Changes the root to its parent. Returns `false` if the root has no parent.
+    pub fn navigate_up(&mut self) -> std::io::Result<bool> {
+        let Some(parent) = self.root.parent().map(Path::to_path_buf) else {
+            return Ok(false);
+        };
+        self.navigate(parent)?;
+        Ok(true)
+    }
+
     pub fn refresh(&mut self) -> std::io::Result<()> {
         self.entries.clear();
         for entry in self.root.read_dir()? {
             let entry = entry?;
             let path = entry.path();
+            if !self.show_hidden && is_hidden(&path) {
+                continue;
+            }
             if self.path_types.path_is_type(&path) {
                 self.entries.push(path);
             }
         }
+        sort_entries(&mut self.entries);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directory_view_test() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir(root.join("beta")).unwrap();
+        std::fs::create_dir(root.join("Alpha")).unwrap();
+        std::fs::create_dir(root.join(".hidden")).unwrap();
+        std::fs::write(root.join("a.txt"), "").unwrap();
+
+        let mut view = DirectoryView::new(root, PathType::FilesAndDirectories).and_populate().unwrap();
+        assert_eq!(view.entries(), [root.join("Alpha"), root.join("beta"), root.join("a.txt")]);
+        view.set_show_hidden(true).unwrap();
+        assert_eq!(view.entries().len(), 4);
+        assert!(view.navigate(root.join("missing")).is_err());
+        assert_eq!(view.root(), root);
+        view.navigate(root.join("beta")).unwrap();
+        assert!(view.entries().is_empty());
+        assert!(view.navigate_up().unwrap());
+        assert_eq!(view.root(), root);
+
+        let text = format!("{}{}", root.display(), std::path::MAIN_SEPARATOR);
+        assert_eq!(complete_path(&text, PathType::Directories, false), [root.join("Alpha"), root.join("beta")]);
+        assert_eq!(complete_path(&format!("{text}al"), PathType::Directories, false), [root.join("Alpha")]);
+        assert_eq!(complete_path(&format!("{text}.h"), PathType::Directories, false), [root.join(".hidden")]);
+        assert_eq!(complete_path(&format!("{text}a"), PathType::Files, false), [root.join("Alpha"), root.join("a.txt")]);
+    }
+}
@@ -10,6 +10,14 @@ pub struct Dirs {
     // especially since those operations will be rare and non-sequential.
     directories: Vec<PathBuf>,
     set: HashSet<PathBuf>,
+    /// Directories that are kept in the list but skipped when scanning.
+    disabled: HashSet<PathBuf>,
+}
+
+impl Default for Dirs {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Dirs {
@@ -17,9 +25,62 @@ impl Dirs {
         Self {
             directories: Vec::new(),
             set: HashSet::new(),
+            disabled: HashSet::new(),
+        }
+    }
+
+    /// Creates the list from the directories in order and the directories that are disabled. Duplicates are removed.
+    pub fn from_parts<'a, I: IntoIterator<Item = &'a PathBuf>>(directories: I, disabled: &[PathBuf]) -> Self {
+        let mut dirs = Self::new();
+        for dir in directories {
+            dirs.append(dir.clone());
+        }
+        dirs.disabled = disabled.iter().filter(|dir| dirs.set.contains(*dir)).cloned().collect();
+        dirs
+    }
+
+    /// The directories in order and the directories that are disabled, in the order that they are listed.
+    pub fn to_parts(&self) -> (Vec<PathBuf>, Vec<PathBuf>) {
+        let disabled = self.directories.iter().filter(|dir| self.disabled.contains(*dir)).cloned().collect();
+        (self.directories.clone(), disabled)
+    }
+
+    #[inline]
+    pub fn directories(&self) -> &[PathBuf] {
+        &self.directories
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.directories.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.directories.is_empty()
+    }
+
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.set.contains(path.as_ref())
+    }
+
+    pub fn is_enabled<P: AsRef<Path>>(&self, path: P) -> bool {
+        !self.disabled.contains(path.as_ref())
+    }
+
+    pub fn set_enabled<P: AsRef<Path> + Into<PathBuf>>(&mut self, path: P, enabled: bool) {
+        if enabled {
+            self.disabled.remove(path.as_ref());
+        } else if self.set.contains(path.as_ref()) {
+            self.disabled.insert(path.into());
         }
     }
 
+    /// The enabled directories in order.
+    pub fn enabled(&self) -> impl Iterator<Item = &PathBuf> {
+        self.directories.iter().filter(|dir| !self.disabled.contains(*dir))
+    }
+
     // Operations:
     // - Move
     // - Append
@@ -65,6 +126,7 @@ impl Dirs {
 
     pub fn remove<P: AsRef<Path> + Into<PathBuf>>(&mut self, path: P) -> bool {
         let path: PathBuf = path.into();
+        self.disabled.remove(&path);
         if self.set.remove(&path) {
             for i in 0..self.directories.len() {
                 if self.directories[i] == path {
@@ -109,4 +171,21 @@ mod tests {
             println!("{i}: {}", dir.display());
         }
     }
+
+    #[test]
+    fn dirs_parts_test() {
+        let paths = ["a", "b", "c", "b"].map(PathBuf::from);
+        let mut dirs = Dirs::from_parts(&paths, &[PathBuf::from("c"), PathBuf::from("missing")]);
+        assert_eq!(dirs.directories(), &paths[..3]);
+        assert_eq!(dirs.enabled().collect::<Vec<_>>(), [&paths[0], &paths[1]]);
+        dirs.move_to(2, 0);
+        dirs.set_enabled("a", false);
+        assert_eq!(dirs.to_parts(), (
+            vec![PathBuf::from("c"), PathBuf::from("a"), PathBuf::from("b")],
+            vec![PathBuf::from("c"), PathBuf::from("a")],
+        ));
+        dirs.remove("c");
+        dirs.set_enabled("a", true);
+        assert_eq!(dirs.to_parts(), (vec![PathBuf::from("a"), PathBuf::from("b")], Vec::new()));
+    }
 }
\ No newline at end of file
@@ -1,4 +1,108 @@
+use std::hash::Hash;
 
-pub struct DirsEdit {
-    
-}
\ No newline at end of file
+use eframe::egui::*;
+
+use crate::{
+    dgui::{directory_view::PathType, dirs::Dirs},
+    widgets::{validate_path, PathInput, PathValidation},
+};
+
+/// Edits a list of directories. Directories can be added (typed or picked), removed, reordered by dragging, and
+/// disabled. Directories that no longer exist are flagged.
+pub struct DirsEdit<'a> {
+    dirs: &'a mut Dirs,
+    id: Id,
+}
+
+impl<'a> DirsEdit<'a> {
+    pub fn new(dirs: &'a mut Dirs, id_salt: impl Hash) -> Self {
+        Self {
+            dirs,
+            id: Id::new(("dirs_edit", id_salt)),
+        }
+    }
+}
+
+impl Widget for DirsEdit<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let mut remove = None;
+        let mut toggle = None;
+        let mut moved = None;
+        let mut added = None;
+        let mut response = ui.vertical(|ui| {
+            if self.dirs.is_empty() {
+                ui.label(RichText::new("No directories").color(Color32::GRAY));
+            }
+            for (index, dir) in self.dirs.directories().iter().enumerate() {
+                let row = ui.horizontal(|ui| {
+                    ui.dnd_drag_source(self.id.with(index), index, |ui| {
+                        ui.label("☰");
+                    }).response
+                        .on_hover_cursor(CursorIcon::Grab)
+                        .on_hover_text("Drag to change the order. Directories are scanned from top to bottom.");
+                    let mut enabled = self.dirs.is_enabled(dir);
+                    if ui.checkbox(&mut enabled, "").on_hover_text("Scan this directory.").changed() {
+                        toggle = Some((index, enabled));
+                    }
+                    if ui.small_button("🗑").on_hover_text("Remove this directory.").clicked() {
+                        remove = Some(index);
+                    }
+                    let text = RichText::new(dir.display().to_string()).monospace();
+                    ui.label(if enabled { text } else { text.color(Color32::GRAY) });
+                    if !dir.is_dir() {
+                        ui.colored_label(Color32::from_rgb(230, 160, 40), "⚠ Missing")
+                            .on_hover_text("This directory no longer exists.");
+                    }
+                }).response;
+                // Dropping on the top half of a row inserts above it, and on the bottom half inserts below it.
+                let Some(pointer) = ui.input(|input| input.pointer.interact_pos()) else {
+                    continue;
+                };
+                let below = pointer.y >= row.rect.center().y;
+                if row.dnd_hover_payload::<usize>().is_some() {
+                    let y = if below { row.rect.bottom() } else { row.rect.top() };
+                    ui.painter().hline(row.rect.x_range(), y, Stroke::new(2.0, ui.visuals().selection.bg_fill));
+                }
+                if let Some(from) = row.dnd_release_payload::<usize>() {
+                    moved = Some((*from, if below { index + 1 } else { index }));
+                }
+            }
+            let input_id = self.id.with("input");
+            let mut input = ui.data(|data| data.get_temp::<String>(input_id)).unwrap_or_default();
+            ui.horizontal(|ui| {
+                let add = ui.button("➕ Add Directory").clicked();
+                ui.add(PathInput::new(&mut input).directory().must_exist(true).hint_text("Type a path or browse"));
+                if add {
+                    if let Ok(dir) = validate_path(&input, PathType::Directories, PathValidation { exists: true, writable: false }) {
+                        added = Some(dir);
+                        input.clear();
+                    }
+                }
+            });
+            ui.data_mut(|data| data.insert_temp(input_id, input));
+        }).response;
+        let mut changed = false;
+        if let Some((index, enabled)) = toggle {
+            let dir = self.dirs.directories()[index].clone();
+            self.dirs.set_enabled(dir, enabled);
+            changed = true;
+        }
+        if let Some(index) = remove {
+            let dir = self.dirs.directories()[index].clone();
+            changed |= self.dirs.remove(dir);
+        }
+        if let Some((from, to)) = moved {
+            if from != to && from + 1 != to {
+                self.dirs.move_to(from, to);
+                changed = true;
+            }
+        }
+        if let Some(dir) = added {
+            changed |= self.dirs.append(dir);
+        }
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+}
@@ -0,0 +1,143 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use eframe::egui::*;
+
+use crate::{
+    app::ProjectorApp,
+    dgui::mbox::{centered_mbox_modal, MessageBox},
+    env::{is_secret_name, mask, EnvSource, EnvVar, ProjectEnv, DOTENV_FILES},
+    ext::UiExt,
+    settings::Closer,
+};
+
+/// Edits the environment variables of a project and shows the effective environment of launched processes.
+pub struct EnvDialog {
+    project: PathBuf,
+    env: ProjectEnv,
+    show_inherited: bool,
+    /// The names of secret variables whose values are shown.
+    revealed: HashSet<String>,
+}
+
+impl EnvDialog {
+    pub fn new<P: Into<PathBuf>>(project: P, env: ProjectEnv) -> Self {
+        Self {
+            project: project.into(),
+            env,
+            show_inherited: false,
+            revealed: HashSet::new(),
+        }
+    }
+
+    fn vars_ui(&mut self, ui: &mut Ui) {
+        let mut remove = None;
+        Grid::new("project_env_grid")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (index, (name, value)) in self.env.vars.iter_mut().enumerate() {
+                    TextEdit::singleline(name)
+                        .desired_width(200.0)
+                        .hint_text("NAME")
+                        .font(TextStyle::Monospace)
+                        .show(ui);
+                    TextEdit::singleline(value)
+                        .desired_width(350.0)
+                        .password(is_secret_name(name))
+                        .font(TextStyle::Monospace)
+                        .show(ui);
+                    if ui.button("🗑").clicked() {
+                        remove = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(index) = remove {
+            self.env.vars.remove(index);
+        }
+        if ui.button("➕ Variable").clicked() {
+            self.env.vars.push((String::new(), String::new()));
+        }
+        ui.checkbox(&mut self.env.load_dotenv, format!("Load {}", DOTENV_FILES.join(" and ")))
+            .on_hover_text("Variables from .env.local override .env. Variables defined here override both.");
+    }
+
+    fn effective_ui(&mut self, ui: &mut Ui) {
+        let mut vars = self.env.resolve(&self.project);
+        if self.show_inherited {
+            let inherited = std::env::vars()
+                .filter(|(name, _)| !vars.iter().any(|var| &var.name == name))
+                .map(|(name, value)| EnvVar { name, value, source: EnvSource::Inherited })
+                .collect::<Vec<_>>();
+            vars.extend(inherited);
+        }
+        vars.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        ui.horizontal(|ui| {
+            ui.label("Effective environment");
+            ui.checkbox(&mut self.show_inherited, "Show inherited");
+        });
+        if vars.is_empty() {
+            ui.label("No variables are added to the environment of launched processes.");
+            return;
+        }
+        ScrollArea::vertical()
+            .id_salt("effective_env_scroll")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                Grid::new("effective_env_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for var in &vars {
+                            ui.monospace(&var.name);
+                            let revealed = self.revealed.contains(&var.name);
+                            if var.is_secret() && !revealed {
+                                ui.monospace(mask(&var.value));
+                            } else {
+                                ui.add(Label::new(RichText::new(&var.value).monospace()).truncate());
+                            }
+                            ui.label(RichText::new(var.source.text()).color(Color32::GRAY));
+                            if var.is_secret() {
+                                if ui.button(if revealed { "Hide" } else { "Show" }).clicked() {
+                                    if revealed {
+                                        self.revealed.remove(&var.name);
+                                    } else {
+                                        self.revealed.insert(var.name.clone());
+                                    }
+                                }
+                            } else {
+                                ui.label("");
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
+
+impl MessageBox<ProjectorApp> for EnvDialog {
+    fn show(&mut self, app: &mut ProjectorApp, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(700.0);
+            ui.label(format!("Environment of {}", self.project.display()));
+            ui.label(RichText::new("Added to the editor, terminal, task, and launch configuration processes of the project.").color(Color32::GRAY));
+            ui.separator();
+            self.vars_ui(ui);
+            ui.separator();
+            self.effective_ui(ui);
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.clicked("Save") {
+                    self.env.vars.retain(|(name, _)| !name.trim().is_empty());
+                    for (name, _) in self.env.vars.iter_mut() {
+                        *name = name.trim().to_owned();
+                    }
+                    app.set_project_env(&self.project, std::mem::take(&mut self.env));
+                    closer.close();
+                }
+                if ui.clicked("Cancel") {
+                    closer.close();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,187 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use eframe::egui::*;
+
+use crate::{
+    dgui::mbox::{centered_mbox_modal, MessageBox},
+    ext::UiExt,
+    license_headers::{ChangeKind, HeaderChange, HeaderError, HeaderPlan, HeaderTemplate},
+    settings::Closer,
+};
+
+/// Dry-run preview for license header insertion. Nothing is written until "Apply" is clicked.
+pub struct HeaderPreview {
+    root: PathBuf,
+    /// Receives the changes while the project is walked on a background thread.
+    plan: Option<mpsc::Receiver<Result<HeaderPlan, HeaderError>>>,
+    error: Option<String>,
+    changes: Vec<HeaderChange>,
+    /// The files that couldn't be read.
+    skipped: Vec<(PathBuf, std::io::Error)>,
+    selected: Vec<bool>,
+    /// Set after the changes have been applied. Contains the number of files written and the failures.
+    result: Option<(usize, Vec<(PathBuf, std::io::Error)>)>,
+}
+
+impl HeaderPreview {
+    /// Plans the changes for `root` on a background thread.
+    pub fn new<P: Into<PathBuf>>(ctx: Context, root: P, extensions: Vec<String>, template: HeaderTemplate) -> Self {
+        let root = root.into();
+        let (sender, receiver) = mpsc::channel();
+        let walk_root = root.clone();
+        std::thread::spawn(move || {
+            sender.send(crate::license_headers::plan_headers(&walk_root, &extensions, &template)).ok();
+            ctx.request_repaint();
+        });
+        Self {
+            root,
+            plan: Some(receiver),
+            error: None,
+            changes: Vec::new(),
+            skipped: Vec::new(),
+            selected: Vec::new(),
+            result: None,
+        }
+    }
+
+    /// Returns true while the changes are being planned.
+    fn poll_plan(&mut self) -> bool {
+        let Some(receiver) = &self.plan else {
+            return false;
+        };
+        match receiver.try_recv() {
+            Ok(Ok(plan)) => {
+                self.selected = vec![true; plan.changes.len()];
+                self.changes = plan.changes;
+                self.skipped = plan.skipped;
+            }
+            Ok(Err(err)) => self.error = Some(format!("Failed to prepare license headers: {err}")),
+            Err(mpsc::TryRecvError::Empty) => return true,
+            Err(mpsc::TryRecvError::Disconnected) => self.error = Some(String::from("Failed to prepare license headers.")),
+        }
+        self.plan = None;
+        false
+    }
+
+    fn apply(&mut self) {
+        let changes = self.changes.iter()
+            .zip(self.selected.iter())
+            .filter(|(_, &selected)| selected)
+            .map(|(change, _)| change.clone())
+            .collect::<Vec<_>>();
+        let errors = crate::license_headers::write_changes(&changes);
+        self.result = Some((changes.len() - errors.len(), errors));
+    }
+
+    fn skipped_ui(&self, ui: &mut Ui) {
+        if self.skipped.is_empty() {
+            return;
+        }
+        ui.colored_label(Color32::RED, format!("{} file(s) could not be read and were skipped:", self.skipped.len()));
+        ScrollArea::vertical()
+            .id_salt("header_preview_skipped")
+            .max_height(100.0)
+            .show(ui, |ui| {
+                for (path, err) in &self.skipped {
+                    let rel_path = path.strip_prefix(&self.root).unwrap_or(path);
+                    ui.label(format!("{}: {err}", rel_path.display()));
+                }
+            });
+    }
+
+    fn diff_ui(change: &HeaderChange, ui: &mut Ui) {
+        for line in change.diff().lines() {
+            let color = if line.starts_with('+') {
+                Color32::from_rgb(120, 200, 120)
+            } else if line.starts_with('-') {
+                Color32::from_rgb(220, 110, 110)
+            } else {
+                Color32::GRAY
+            };
+            ui.add(Label::new(RichText::new(line).monospace().color(color)).selectable(false));
+        }
+    }
+}
+
+impl<D> MessageBox<D> for HeaderPreview {
+    fn show(&mut self, _data: &mut D, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(600.0);
+            if self.poll_plan() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Looking for files in {}...", self.root.display()));
+                });
+                if ui.clicked("Cancel") {
+                    closer.close();
+                }
+                return;
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(Color32::RED, error);
+                if ui.clicked("Close") {
+                    closer.close();
+                }
+                return;
+            }
+            if let Some((written, errors)) = &self.result {
+                ui.label(format!("Updated {written} file(s) in {}.", self.root.display()));
+                if !errors.is_empty() {
+                    ui.colored_label(Color32::RED, format!("{} file(s) could not be written:", errors.len()));
+                    ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            for (path, err) in errors {
+                                ui.label(format!("{}: {err}", path.display()));
+                            }
+                        });
+                }
+                if ui.clicked("Close") {
+                    closer.close();
+                }
+                return;
+            }
+            if self.changes.is_empty() {
+                ui.label(format!("All license headers in {} are up to date.", self.root.display()));
+                self.skipped_ui(ui);
+                if ui.clicked("Close") {
+                    closer.close();
+                }
+                return;
+            }
+            let selected_count = self.selected.iter().filter(|&&selected| selected).count();
+            ui.label(format!("Dry run: {} file(s) in {} would be changed.", self.changes.len(), self.root.display()));
+            self.skipped_ui(ui);
+            ui.separator();
+            ScrollArea::vertical()
+                .max_height(450.0)
+                .auto_shrink(Vec2b::new(false, true))
+                .show(ui, |ui| {
+                    for (index, change) in self.changes.iter().enumerate() {
+                        let rel_path = change.path.strip_prefix(&self.root).unwrap_or(&change.path);
+                        let kind = match change.kind {
+                            ChangeKind::Insert => "insert",
+                            ChangeKind::Update => "update",
+                        };
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.selected[index], "");
+                            CollapsingHeader::new(format!("{} ({kind})", rel_path.display()))
+                                .id_salt(("header_preview", index))
+                                .show(ui, |ui| {
+                                    Self::diff_ui(change, ui);
+                                });
+                        });
+                    }
+                });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.add_enabled(selected_count > 0, Button::new(format!("Apply ({selected_count})"))).clicked() {
+                    self.apply();
+                }
+                if ui.clicked("Cancel") {
+                    closer.close();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::PathBuf;
+
+use eframe::egui::*;
+
+use crate::{
+    app::ProjectorApp,
+    dgui::mbox::{centered_mbox_modal, MessageBox},
+    ext::UiExt,
+    launch::{LaunchConfig, LaunchStep, OnFailure, StepAction},
+    settings::Closer,
+};
+
+/// The actions that can be chosen for a step, with empty arguments.
+const ACTIONS: [StepAction; 5] = [
+    StepAction::OpenEditor,
+    StepAction::OpenTerminal,
+    StepAction::RevealInFileExplorer,
+    StepAction::OpenUrl(String::new()),
+    StepAction::Command(String::new()),
+];
+
+/// Creates, edits, and removes the launch configurations of a project.
+pub struct LaunchConfigDialog {
+    project: PathBuf,
+    configs: Vec<LaunchConfig>,
+    selected: usize,
+    error: Option<String>,
+}
+
+impl LaunchConfigDialog {
+    pub fn new<P: Into<PathBuf>>(project: P, configs: Vec<LaunchConfig>) -> Self {
+        Self {
+            project: project.into(),
+            configs,
+            selected: 0,
+            error: None,
+        }
+    }
+
+    fn configs_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ComboBox::new("launch_config_combo", "")
+                .selected_text(self.configs.get(self.selected).map_or("", |config| config.name.as_str()))
+                .show_ui(ui, |ui| {
+                    for (index, config) in self.configs.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected, index, &config.name);
+                    }
+                });
+            if ui.clicked("➕ New") {
+                self.configs.push(LaunchConfig {
+                    name: format!("Launch {}", self.configs.len() + 1),
+                    steps: vec![LaunchStep::new(StepAction::OpenEditor)],
+                });
+                self.selected = self.configs.len() - 1;
+            }
+            if !self.configs.is_empty() && ui.clicked("🗑 Delete") {
+                self.configs.remove(self.selected);
+                self.selected = self.selected.saturating_sub(1);
+            }
+        });
+    }
+
+    fn step_ui(ui: &mut Ui, step: &mut LaunchStep) {
+        ui.horizontal(|ui| {
+            ComboBox::from_id_salt("action")
+                .selected_text(step.action.text())
+                .show_ui(ui, |ui| {
+                    for action in ACTIONS {
+                        let selected = std::mem::discriminant(&action) == std::mem::discriminant(&step.action);
+                        if ui.selectable_label(selected, action.text()).clicked() && !selected {
+                            step.action = action;
+                        }
+                    }
+                });
+            match &mut step.action {
+                StepAction::OpenUrl(text) | StepAction::Command(text) => {
+                    TextEdit::singleline(text)
+                        .desired_width(f32::INFINITY)
+                        .font(TextStyle::Monospace)
+                        .show(ui);
+                }
+                _ => (),
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Working directory");
+            TextEdit::singleline(&mut step.working_dir)
+                .desired_width(200.0)
+                .hint_text("Project directory")
+                .show(ui);
+            ui.checkbox(&mut step.wait, "Wait")
+                .on_hover_text("Wait for the step to exit before starting the next step. A step that isn't waited on can't fail.");
+            ui.add_enabled_ui(step.wait, |ui| {
+                ComboBox::from_id_salt("on_failure")
+                    .selected_text(format!("On failure: {}", step.on_failure.text()))
+                    .show_ui(ui, |ui| {
+                        for on_failure in [OnFailure::Stop, OnFailure::Continue] {
+                            ui.selectable_value(&mut step.on_failure, on_failure, on_failure.text());
+                        }
+                    });
+            });
+        });
+        let mut remove_var = None;
+        for (index, (key, value)) in step.env.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                TextEdit::singleline(key).desired_width(150.0).hint_text("NAME").show(ui);
+                ui.label("=");
+                TextEdit::singleline(value).desired_width(250.0).show(ui);
+                if ui.button("🗑").clicked() {
+                    remove_var = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove_var {
+            step.env.remove(index);
+        }
+        if ui.button("➕ Environment Variable").clicked() {
+            step.env.push((String::new(), String::new()));
+        }
+    }
+
+    fn steps_ui(&mut self, ui: &mut Ui) {
+        let Some(config) = self.configs.get_mut(self.selected) else {
+            ui.label("This project has no launch configurations.");
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            TextEdit::singleline(&mut config.name).desired_width(250.0).show(ui);
+        });
+        let mut move_up = None;
+        let mut remove = None;
+        ScrollArea::vertical()
+            .id_salt("launch_steps_scroll")
+            .max_height(400.0)
+            .show(ui, |ui| {
+                let step_count = config.steps.len();
+                for (index, step) in config.steps.iter_mut().enumerate() {
+                    ui.push_id(index, |ui| {
+                        Frame::group(ui.style()).show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Step {}", index + 1));
+                                if ui.add_enabled(index > 0, Button::new("⏶")).clicked() {
+                                    move_up = Some(index);
+                                }
+                                if ui.add_enabled(index + 1 < step_count, Button::new("⏷")).clicked() {
+                                    move_up = Some(index + 1);
+                                }
+                                if ui.button("🗑").clicked() {
+                                    remove = Some(index);
+                                }
+                            });
+                            Self::step_ui(ui, step);
+                        });
+                    });
+                }
+            });
+        if let Some(index) = move_up {
+            config.steps.swap(index - 1, index);
+        }
+        if let Some(index) = remove {
+            config.steps.remove(index);
+        }
+        if ui.button("➕ Step").clicked() {
+            config.steps.push(LaunchStep::new(StepAction::Command(String::new())));
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for config in &self.configs {
+            if config.name.trim().is_empty() {
+                return Err(String::from("Launch configurations must have a name."));
+            }
+            if self.configs.iter().filter(|other| other.name.trim() == config.name.trim()).count() > 1 {
+                return Err(format!("There is more than one launch configuration named `{}`.", config.name.trim()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MessageBox<ProjectorApp> for LaunchConfigDialog {
+    fn show(&mut self, app: &mut ProjectorApp, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(700.0);
+            ui.label(format!("Launch configurations of {}", self.project.display()));
+            ui.separator();
+            self.configs_ui(ui);
+            ui.separator();
+            self.steps_ui(ui);
+            if let Some(error) = &self.error {
+                ui.separator();
+                ui.colored_label(Color32::RED, error);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.clicked("Save") {
+                    match self.validate() {
+                        Ok(()) => {
+                            for config in self.configs.iter_mut() {
+                                config.name = config.name.trim().to_owned();
+                            }
+                            app.set_launch_configs(&self.project, std::mem::take(&mut self.configs));
+                            closer.close();
+                        }
+                        Err(err) => self.error = Some(err),
+                    }
+                }
+                if ui.clicked("Cancel") {
+                    closer.close();
+                }
+            });
+        });
+    }
+}
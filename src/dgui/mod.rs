@@ -3,4 +3,15 @@ pub mod recents;
 pub mod dirs;
 pub mod directory_view;
 pub mod dirs_edit;
-pub mod mbox;
\ No newline at end of file
+pub mod mbox;
+pub mod header_preview;
+pub mod scaffold_dialog;
+pub mod template_dialog;
+pub mod worktrees;
+pub mod workspace;
+pub mod tasks;
+pub mod launch;
+pub mod env;
+pub mod toolchain;
+pub mod onboarding;
+pub mod dir_browser;
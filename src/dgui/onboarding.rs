@@ -0,0 +1,235 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use eframe::egui::*;
+
+use crate::{
+    app::ProjectorApp,
+    dgui::{dirs_edit::DirsEdit, mbox::{centered_mbox_modal, MessageBox}},
+    ext::UiExt,
+    import::{import_recents, without_existing},
+    projects::{scan_directories, ProjectPath, ProjectType},
+    settings::{command_presets_ui, Closer, Settings},
+    tools::{detected_tools, editor_presets, terminal_presets, CommandPreset},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Step {
+    Welcome,
+    Tools,
+    Directories,
+    Projects,
+}
+
+impl Step {
+    const ALL: [Step; 4] = [Step::Welcome, Step::Tools, Step::Directories, Step::Projects];
+
+    const fn title(self) -> &'static str {
+        match self {
+            Step::Welcome => "Welcome to Projector",
+            Step::Tools => "Editor and Terminal",
+            Step::Directories => "Project Directories",
+            Step::Projects => "Recent Projects",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&step| step == self).unwrap_or(0)
+    }
+}
+
+/// A project that can be added to the recent projects.
+struct Candidate {
+    project: ProjectPath,
+    /// Where the project was found.
+    origin: String,
+    selected: bool,
+}
+
+/// Finds projects in the project directories and imports the recent projects of installed editors.
+fn find_candidates(directories: Vec<(ProjectType, PathBuf)>) -> Vec<Candidate> {
+    let mut candidates = scan_directories(directories).into_iter()
+        .map(|(project, root)| Candidate { project, origin: root.display().to_string(), selected: true })
+        .collect::<Vec<_>>();
+    let existing = candidates.iter().map(|candidate| candidate.project.path().to_path_buf()).collect::<Vec<_>>();
+    for imported in without_existing(import_recents(), existing.iter().map(PathBuf::as_path)) {
+        let origin = format!("Recently opened in {}", imported.source.text());
+        candidates.push(Candidate { project: imported.entry.into_path(), origin, selected: true });
+    }
+    candidates
+}
+
+/// Shown on the first launch, when there is no settings file. The choices are saved as the initial [Settings].
+pub struct OnboardingWizard {
+    step: Step,
+    settings: Settings,
+    editor_presets: Vec<CommandPreset>,
+    terminal_presets: Vec<CommandPreset>,
+    scan: Option<mpsc::Receiver<Vec<Candidate>>>,
+    candidates: Vec<Candidate>,
+}
+
+impl OnboardingWizard {
+    pub fn new() -> Self {
+        let tools = detected_tools();
+        Self {
+            step: Step::Welcome,
+            settings: Settings::default(),
+            editor_presets: editor_presets(tools),
+            terminal_presets: terminal_presets(tools),
+            scan: None,
+            candidates: Vec::new(),
+        }
+    }
+
+    fn start_scan(&mut self, ctx: Context) {
+        let directories = self.settings.projects.scan_roots();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            sender.send(find_candidates(directories)).ok();
+            ctx.request_repaint();
+        });
+        self.candidates.clear();
+        self.scan = Some(receiver);
+    }
+
+    fn welcome_ui(&mut self, ui: &mut Ui) {
+        ui.label("Projector opens, creates, and keeps track of your projects.");
+        ui.label("This will set up the programs that projects are opened with, the directories that projects are kept in, and your recent projects.");
+        ui.label(RichText::new("Everything can be changed later in Settings.").color(Color32::GRAY));
+    }
+
+    fn tools_ui(&mut self, ui: &mut Ui) {
+        let general = &mut self.settings.general;
+        if self.editor_presets.is_empty() && self.terminal_presets.is_empty() {
+            ui.colored_label(Color32::from_rgb(230, 160, 40), "No known editors or terminals were found. Enter the commands that open them.");
+        }
+        ui.label("Editor Command");
+        command_presets_ui(ui, "onboarding_editor_presets", &mut general.editor_command, &self.editor_presets);
+        ui.label("Open Shell Command");
+        command_presets_ui(ui, "onboarding_shell_presets", &mut general.shell_command, &self.terminal_presets);
+        ui.label(RichText::new("`{path}` is replaced with the path of the project.").color(Color32::GRAY));
+    }
+
+    fn directories_ui(&mut self, ui: &mut Ui) {
+        ui.label("Choose the directories that your projects are kept in. New projects are created in the first directory of their type.");
+        for project_type in [ProjectType::Rust, ProjectType::Python, ProjectType::Web] {
+            ui.separator();
+            ui.strong(project_type.text());
+            let mut dirs = self.settings.projects.project_dirs(project_type);
+            if ui.add(DirsEdit::new(&mut dirs, ("onboarding_directories", project_type))).changed() {
+                self.settings.projects.set_project_dirs(project_type, &dirs);
+            }
+        }
+    }
+
+    fn projects_ui(&mut self, ui: &mut Ui) {
+        if let Some(receiver) = &self.scan {
+            match receiver.try_recv() {
+                Ok(candidates) => {
+                    self.candidates = candidates;
+                    self.scan = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Looking for projects...");
+                    });
+                    return;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.scan = None,
+            }
+        }
+        if self.candidates.is_empty() {
+            ui.label("No projects were found in the project directories or in the recent projects of installed editors.");
+            return;
+        }
+        ui.label("These projects were found in your project directories and the recent projects of installed editors. Selected projects are added to the recent projects.");
+        ui.horizontal(|ui| {
+            if ui.button("Select All").clicked() {
+                self.candidates.iter_mut().for_each(|candidate| candidate.selected = true);
+            }
+            if ui.button("Select None").clicked() {
+                self.candidates.iter_mut().for_each(|candidate| candidate.selected = false);
+            }
+        });
+        ScrollArea::vertical()
+            .id_salt("onboarding_projects_scroll")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                Grid::new("onboarding_projects_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for candidate in self.candidates.iter_mut() {
+                            let name = candidate.project.path().file_name()
+                                .and_then(|name| name.to_str())
+                                .unwrap_or("")
+                                .to_owned();
+                            ui.checkbox(&mut candidate.selected, name)
+                                .on_hover_text(candidate.project.path().display().to_string());
+                            ui.label(candidate.project.project_type().text());
+                            ui.label(RichText::new(&candidate.origin).color(Color32::GRAY));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn finish(&mut self, app: &mut ProjectorApp) {
+        let projects = self.candidates.drain(..)
+            .filter(|candidate| candidate.selected)
+            .map(|candidate| candidate.project)
+            .collect();
+        app.finish_onboarding(std::mem::take(&mut self.settings), projects);
+    }
+}
+
+impl Default for OnboardingWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageBox<ProjectorApp> for OnboardingWizard {
+    fn show(&mut self, app: &mut ProjectorApp, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(600.0);
+            ui.horizontal(|ui| {
+                ui.heading(self.step.title());
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    ui.label(RichText::new(format!("{} of {}", self.step.index() + 1, Step::ALL.len())).color(Color32::GRAY));
+                });
+            });
+            ui.separator();
+            match self.step {
+                Step::Welcome => self.welcome_ui(ui),
+                Step::Tools => self.tools_ui(ui),
+                Step::Directories => self.directories_ui(ui),
+                Step::Projects => self.projects_ui(ui),
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if self.step != Step::Welcome && ui.clicked("Back") {
+                    self.step = Step::ALL[self.step.index() - 1];
+                }
+                if self.step == Step::Projects {
+                    if ui.add_enabled(self.scan.is_none(), Button::new("Finish")).clicked() {
+                        self.finish(app);
+                        closer.close();
+                    }
+                } else if ui.clicked("Next") {
+                    self.step = Step::ALL[self.step.index() + 1];
+                    if self.step == Step::Projects {
+                        self.start_scan(ui.ctx().clone());
+                    }
+                }
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.clicked("Skip") {
+                        app.finish_onboarding(std::mem::take(&mut self.settings), Vec::new());
+                        closer.close();
+                    }
+                });
+            });
+        });
+    }
+}
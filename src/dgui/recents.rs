@@ -7,7 +7,7 @@ use chrono::Timelike;
 use eframe::{
     egui::*,
 };
-use crate::projects::ProjectPath;
+use crate::{git_status::GitStatus, projects::ProjectPath};
 
 #[repr(u8)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, bincode::Encode, bincode::Decode)]
@@ -187,13 +187,76 @@ impl RecentsSort {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Recent<'a> {
     path: &'a ProjectPath,
+    git_status: Option<&'a GitStatus>,
+    python_version: Option<&'a str>,
+    toolchain_warning: bool,
+    tmux_session: bool,
 }
 
 impl<'a> Recent<'a> {
 
     #[inline]
     pub const fn new(path: &'a ProjectPath) -> Self {
-        Self { path }
+        Self { path, git_status: None, python_version: None, toolchain_warning: false, tmux_session: false }
+    }
+
+    #[inline]
+    pub const fn with_git_status(mut self, git_status: Option<&'a GitStatus>) -> Self {
+        self.git_status = git_status;
+        self
+    }
+
+    /// The interpreter version of the project's virtual environment.
+    #[inline]
+    pub const fn with_python_version(mut self, python_version: Option<&'a str>) -> Self {
+        self.python_version = python_version;
+        self
+    }
+
+    /// Shows a warning when a local toolchain doesn't satisfy the project's requirements.
+    #[inline]
+    pub const fn with_toolchain_warning(mut self, toolchain_warning: bool) -> Self {
+        self.toolchain_warning = toolchain_warning;
+        self
+    }
+
+    /// Shows that the project's tmux session is running.
+    #[inline]
+    pub const fn with_tmux_session(mut self, tmux_session: bool) -> Self {
+        self.tmux_session = tmux_session;
+        self
+    }
+
+    /// `⎇ main ● ↑2 ↓1  3 hours ago`
+    fn git_status_job(status: &GitStatus) -> text::LayoutJob {
+        let font_id = FontId::monospace(12.0);
+        let mut job = text::LayoutJob::default();
+        let mut append = |text: &str, color: Color32| {
+            job.append(text, 0.0, TextFormat::simple(font_id.clone(), color));
+        };
+        let branch = match (&status.branch, &status.head) {
+            (Some(branch), _) => branch.as_str(),
+            (None, Some(head)) => head.as_str(),
+            (None, None) => "(no commits)",
+        };
+        append(&format!("⎇ {branch} "), Color32::LIGHT_GRAY);
+        if status.dirty {
+            append("●", Color32::from_rgb(230, 160, 40));
+        } else {
+            append("✔", Color32::from_rgb(90, 190, 90));
+        }
+        if let Some((ahead, behind)) = status.ahead_behind {
+            if ahead > 0 {
+                append(&format!(" ↑{ahead}"), Color32::LIGHT_GRAY);
+            }
+            if behind > 0 {
+                append(&format!(" ↓{behind}"), Color32::LIGHT_GRAY);
+            }
+        }
+        if let Some(time) = status.last_commit_time.and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0)) {
+            append(&format!("  {}", crate::util::time::relative_time(time, chrono::Utc::now())), Color32::GRAY);
+        }
+        job
     }
 
     pub fn ui(&self, ui: &mut Ui) -> Response {
@@ -214,8 +277,39 @@ impl<'a> Recent<'a> {
         );
 
         
-        let name_rect = left_rect.shrink(4.0);
+        let mut name_rect = left_rect.shrink(4.0);
         let type_rect = right_rect.shrink(4.0);
+
+        if self.toolchain_warning {
+            let galley = ui.fonts(|fonts| fonts.layout_no_wrap(String::from("⚠"), FontId::monospace(14.0), Color32::from_rgb(230, 160, 40)));
+            let warning_pos = pos2(name_rect.right() - galley.size().x, name_rect.center().y - galley.size().y * 0.5);
+            p.galley(warning_pos, galley, Color32::LIGHT_GRAY);
+            name_rect.max.x = warning_pos.x - 8.0;
+        }
+
+        if self.tmux_session {
+            let galley = ui.fonts(|fonts| fonts.layout_no_wrap(String::from("▣ tmux"), FontId::monospace(12.0), Color32::from_rgb(90, 190, 90)));
+            let tmux_pos = pos2(name_rect.right() - galley.size().x, name_rect.center().y - galley.size().y * 0.5);
+            p.galley(tmux_pos, galley, Color32::LIGHT_GRAY);
+            name_rect.max.x = tmux_pos.x - 8.0;
+        }
+
+        if let Some(version) = self.python_version {
+            let galley = ui.fonts(|fonts| fonts.layout_no_wrap(format!("🐍 {version}"), FontId::monospace(12.0), Color32::from_rgb(255, 212, 59)));
+            let version_pos = pos2(name_rect.right() - galley.size().x, name_rect.center().y - galley.size().y * 0.5);
+            p.galley(version_pos, galley, Color32::LIGHT_GRAY);
+            name_rect.max.x = version_pos.x - 8.0;
+        }
+
+        if let Some(status) = self.git_status {
+            let galley = ui.fonts(|fonts| fonts.layout_job(Self::git_status_job(status)));
+            let git_pos = pos2(name_rect.right() - galley.size().x, name_rect.center().y - galley.size().y * 0.5);
+            // The name gets at least half of the row.
+            let git_left = git_pos.x.max(name_rect.center().x);
+            p.with_clip_rect(Rect::from_min_max(pos2(git_left, name_rect.top()), name_rect.max))
+                .galley(git_pos, galley, Color32::LIGHT_GRAY);
+            name_rect.max.x = git_left - 8.0;
+        }
         
         let (path, type_name, type_color) = match self.path {
             ProjectPath::Rust(path_buf) => (path_buf, "Rust", Color32::from_rgb(185, 71, 0)),
@@ -325,6 +419,21 @@ impl RecentEntry {
             last_open_time: time,
         }
     }
+
+    #[inline]
+    pub const fn path(&self) -> &ProjectPath {
+        &self.path
+    }
+
+    #[inline]
+    pub const fn last_open_time(&self) -> chrono::DateTime<chrono::Utc> {
+        self.last_open_time
+    }
+
+    #[inline]
+    pub fn into_path(self) -> ProjectPath {
+        self.path
+    }
 }
 
 impl bincode::Encode for RecentEntry {
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use eframe::egui::*;
+
+use crate::{
+    dgui::mbox::{centered_mbox_modal, MessageBox},
+    ext::UiExt,
+    projects::ProjectType,
+    scaffold::{write_scaffold, ScaffoldOptions, GITIGNORE_FILE, README_FILE},
+    settings::{Closer, Templates},
+};
+
+/// Generates a `.gitignore` and README for a project that already exists.
+pub struct ScaffoldDialog {
+    directory: PathBuf,
+    project_type: ProjectType,
+    templates: Templates,
+    name: String,
+    description: String,
+    options: ScaffoldOptions,
+    result: Option<Result<Vec<PathBuf>, String>>,
+}
+
+impl ScaffoldDialog {
+    pub fn new<P: Into<PathBuf>>(directory: P, project_type: ProjectType, templates: Templates) -> Self {
+        let directory: PathBuf = directory.into();
+        let name = directory.file_name()
+            .and_then(std::ffi::OsStr::to_str)
+            .unwrap_or("")
+            .to_owned();
+        Self {
+            options: ScaffoldOptions {
+                gitignore: !directory.join(GITIGNORE_FILE).exists(),
+                readme: !directory.join(README_FILE).exists(),
+                overwrite: false,
+            },
+            directory,
+            project_type,
+            templates,
+            name,
+            description: String::new(),
+            result: None,
+        }
+    }
+}
+
+impl<D> MessageBox<D> for ScaffoldDialog {
+    fn show(&mut self, _data: &mut D, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(400.0);
+            if let Some(result) = &self.result {
+                match result {
+                    Ok(written) if written.is_empty() => {
+                        ui.label("Nothing was written. The files already exist.");
+                    }
+                    Ok(written) => {
+                        for path in written {
+                            ui.label(format!("Wrote {}", path.display()));
+                        }
+                    }
+                    Err(err) => {
+                        ui.colored_label(Color32::RED, err);
+                    }
+                }
+                if ui.clicked("Close") {
+                    closer.close();
+                }
+                return;
+            }
+            ui.label(format!("Generate files for {}", self.directory.display()));
+            ui.separator();
+            Grid::new("scaffold_dialog_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Name");
+                    ui.text_edit_singleline(&mut self.name);
+                    ui.end_row();
+                    ui.label("Description");
+                    ui.text_edit_multiline(&mut self.description);
+                    ui.end_row();
+                });
+            ui.checkbox(&mut self.options.gitignore, GITIGNORE_FILE);
+            ui.checkbox(&mut self.options.readme, README_FILE);
+            ui.checkbox(&mut self.options.overwrite, "Overwrite existing files");
+            ui.separator();
+            ui.horizontal(|ui| {
+                let can_generate = self.options.gitignore || self.options.readme;
+                if ui.add_enabled(can_generate, Button::new("Generate")).clicked() {
+                    self.result = Some(write_scaffold(
+                        &self.directory,
+                        self.project_type,
+                        &self.templates,
+                        &self.name,
+                        &self.description,
+                        self.options,
+                    ).map_err(|err| format!("Failed to generate files: {err}")));
+                }
+                if ui.clicked("Cancel") {
+                    closer.close();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,163 @@
+use std::{path::PathBuf, sync::Arc};
+
+use eframe::egui::*;
+
+use crate::{
+    app::ProjectorApp,
+    dgui::mbox::{centered_mbox_modal, MessageBox},
+    ext::UiExt,
+    settings::Closer,
+    tasks::{detect_tasks, Task, TaskRun, TaskStatus},
+    util::time::relative_time,
+};
+
+/// Lists the tasks of a project, runs them, and shows their output.
+pub struct TaskDialog {
+    dir: PathBuf,
+    tasks: Vec<Task>,
+    /// The run whose output is shown.
+    selected: Option<Arc<TaskRun>>,
+    error: Option<String>,
+}
+
+impl TaskDialog {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        let mut dialog = Self {
+            dir: dir.into(),
+            tasks: Vec::new(),
+            selected: None,
+            error: None,
+        };
+        dialog.refresh();
+        dialog
+    }
+
+    fn refresh(&mut self) {
+        let detected = detect_tasks(&self.dir);
+        self.tasks = detected.tasks;
+        self.error = (!detected.errors.is_empty()).then(|| {
+            detected.errors.iter()
+                .map(|(source, err)| format!("Failed to read the {} tasks: {err}", source.text()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+    }
+
+    fn status_label(ui: &mut Ui, status: Option<TaskStatus>, running: bool) {
+        if running {
+            ui.spinner();
+            return;
+        }
+        let Some(status) = status else {
+            ui.label("");
+            return;
+        };
+        let (text, color) = match status.exit_code {
+            Some(0) => (String::from("✔"), Color32::GREEN),
+            Some(code) => (format!("✖ {code}"), Color32::RED),
+            None => (String::from("✖ killed"), Color32::RED),
+        };
+        let resp = ui.colored_label(color, text);
+        if let Some(finished) = chrono::DateTime::from_timestamp(status.finished, 0) {
+            resp.on_hover_text(relative_time(finished, chrono::Utc::now()));
+        }
+    }
+
+    fn tasks_ui(&mut self, app: &mut ProjectorApp, ui: &mut Ui) {
+        let mut run: Option<Task> = None;
+        ScrollArea::vertical()
+            .id_salt("tasks_scroll")
+            .max_height(240.0)
+            .show(ui, |ui| {
+                Grid::new("tasks_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for task in &self.tasks {
+                            let current = app.task_runner().run_of(&self.dir, task);
+                            let running = current.as_ref().is_some_and(|run| run.is_running());
+                            ui.label(RichText::new(task.source.text()).color(Color32::GRAY));
+                            let name = ui.label(RichText::new(&task.name).monospace());
+                            if let Some(description) = &task.description {
+                                name.on_hover_text(description);
+                            }
+                            Self::status_label(ui, app.task_runner().last_status(&self.dir, task), running);
+                            ui.horizontal(|ui| {
+                                if running {
+                                    if ui.button("⏹").on_hover_text("Stop").clicked() {
+                                        if let Some(current) = &current {
+                                            current.kill();
+                                        }
+                                    }
+                                } else if ui.button("▶").on_hover_text(&task.command).clicked() {
+                                    run = Some(task.clone());
+                                }
+                                if let Some(current) = current {
+                                    if ui.button("📄").on_hover_text("Show Output").clicked() {
+                                        self.selected = Some(current);
+                                    }
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+        if let Some(task) = run {
+            let env = app.env_pairs_for(&self.dir);
+            match app.task_runner_mut().run(&self.dir, &task, &env) {
+                Ok(run) => self.selected = Some(run),
+                Err(err) => self.error = Some(format!("Failed to run {}: {err}", task.command)),
+            }
+        }
+    }
+
+    fn output_ui(&self, ui: &mut Ui) {
+        let Some(run) = &self.selected else {
+            return;
+        };
+        ui.separator();
+        ui.label(format!("Output of {}", run.task.command));
+        let mut output = run.output();
+        ScrollArea::vertical()
+            .id_salt("task_output_scroll")
+            .max_height(300.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                TextEdit::multiline(&mut output)
+                    .font(TextStyle::Monospace)
+                    .desired_width(f32::INFINITY)
+                    .interactive(false)
+                    .show(ui);
+            });
+    }
+}
+
+impl MessageBox<ProjectorApp> for TaskDialog {
+    fn show(&mut self, app: &mut ProjectorApp, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(700.0);
+            ui.label(format!("Tasks of {}", self.dir.display()));
+            ui.separator();
+            if self.tasks.is_empty() {
+                ui.label("No tasks were found. Tasks are read from Cargo.toml, .cargo/config.toml, package.json, Makefile, justfile, and pyproject.toml.");
+            } else {
+                self.tasks_ui(app, ui);
+            }
+            self.output_ui(ui);
+            if let Some(error) = &self.error {
+                ui.separator();
+                ui.colored_label(Color32::RED, error);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.clicked("Reload") {
+                    self.refresh();
+                }
+                // Tasks keep running in the background after the dialog is closed.
+                if ui.clicked("Close") {
+                    closer.close();
+                }
+            });
+        });
+    }
+}
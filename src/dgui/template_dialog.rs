@@ -0,0 +1,182 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use eframe::egui::*;
+
+use crate::{
+    dgui::mbox::{centered_mbox_modal, MessageBox},
+    ext::UiExt,
+    projects::ProjectType,
+    settings::Closer,
+    templates::{is_valid_variable_name, save_as_template, TemplateManifest, TemplateVariable, NAME_VARIABLE},
+};
+
+/// Saves an existing project as a reusable template.
+pub struct SaveTemplateDialog {
+    project: PathBuf,
+    templates_dir: PathBuf,
+    manifest: TemplateManifest,
+    /// Receives the result while the project is copied on a background thread.
+    saving: Option<mpsc::Receiver<Result<PathBuf, String>>>,
+    result: Option<Result<PathBuf, String>>,
+}
+
+impl SaveTemplateDialog {
+    pub fn new<P0: Into<PathBuf>, P1: Into<PathBuf>>(project: P0, project_type: ProjectType, templates_dir: P1) -> Self {
+        let project: PathBuf = project.into();
+        let name = project.file_name()
+            .and_then(std::ffi::OsStr::to_str)
+            .unwrap_or("")
+            .to_owned();
+        Self {
+            manifest: TemplateManifest {
+                name: name.clone(),
+                description: String::new(),
+                project_type,
+                variables: vec![TemplateVariable::new(NAME_VARIABLE, name)],
+            },
+            project,
+            templates_dir: templates_dir.into(),
+            saving: None,
+            result: None,
+        }
+    }
+
+    /// Whether an earlier variable has the same name as the variable at `index`.
+    fn is_duplicate(&self, index: usize) -> bool {
+        let name = &self.manifest.variables[index].name;
+        self.manifest.variables[..index].iter().any(|variable| &variable.name == name)
+    }
+
+    /// Copies the project into the templates directory on a background thread.
+    fn save(&mut self, ctx: Context) {
+        let (sender, receiver) = mpsc::channel();
+        let project = self.project.clone();
+        let templates_dir = self.templates_dir.clone();
+        let manifest = self.manifest.clone();
+        std::thread::spawn(move || {
+            let result = save_as_template(&project, &templates_dir, &manifest)
+                .map(|template| template.path)
+                .map_err(|err| format!("Failed to save template: {err}"));
+            _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        self.saving = Some(receiver);
+    }
+
+    /// Returns true while the template is being saved.
+    fn poll_save(&mut self) -> bool {
+        let Some(receiver) = &self.saving else {
+            return false;
+        };
+        match receiver.try_recv() {
+            Ok(result) => self.result = Some(result),
+            Err(mpsc::TryRecvError::Empty) => return true,
+            Err(mpsc::TryRecvError::Disconnected) => self.result = Some(Err(String::from("Failed to save template."))),
+        }
+        self.saving = None;
+        false
+    }
+
+    fn variables_ui(&mut self, ui: &mut Ui) {
+        let mut remove_index = None;
+        let duplicates = (0..self.manifest.variables.len()).map(|index| self.is_duplicate(index)).collect::<Vec<_>>();
+        Grid::new("save_template_variables_grid")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Variable");
+                ui.label("Literal");
+                ui.end_row();
+                for (index, variable) in self.manifest.variables.iter_mut().enumerate() {
+                    let valid = is_valid_variable_name(&variable.name) && !duplicates[index];
+                    let response = TextEdit::singleline(&mut variable.name)
+                        .desired_width(120.0)
+                        .text_color_opt((!valid).then_some(Color32::RED))
+                        .show(ui)
+                        .response;
+                    if duplicates[index] {
+                        response.on_hover_text("Another variable has this name.");
+                    }
+                    TextEdit::singleline(&mut variable.default)
+                        .desired_width(200.0)
+                        .hint_text("Text to replace")
+                        .show(ui);
+                    if ui.button(crate::charcons::XBOX).on_hover_text("Remove variable").clicked() {
+                        remove_index = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(index) = remove_index {
+            self.manifest.variables.remove(index);
+        }
+        if ui.button("Add Variable").clicked() {
+            self.manifest.variables.push(TemplateVariable::new("", ""));
+        }
+    }
+}
+
+impl<D> MessageBox<D> for SaveTemplateDialog {
+    fn show(&mut self, _data: &mut D, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(450.0);
+            if self.poll_save() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Copying {}...", self.project.display()));
+                });
+                return;
+            }
+            if let Some(result) = &self.result {
+                match result {
+                    Ok(path) => {
+                        ui.label(format!("Template saved to {}", path.display()));
+                    }
+                    Err(err) => {
+                        ui.colored_label(Color32::RED, err);
+                    }
+                }
+                if ui.clicked("Close") {
+                    closer.close();
+                }
+                return;
+            }
+            ui.label(format!("Save {} as a template", self.project.display()));
+            ui.separator();
+            Grid::new("save_template_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Template Name");
+                    ui.text_edit_singleline(&mut self.manifest.name);
+                    ui.end_row();
+                    ui.label("Description");
+                    ui.text_edit_multiline(&mut self.manifest.description);
+                    ui.end_row();
+                    ui.label("Project Type");
+                    ComboBox::new("save_template_type_combo", "")
+                        .selected_text(self.manifest.project_type.text())
+                        .show_ui(ui, |ui| {
+                            for project_type in ProjectType::ALL {
+                                ui.selectable_value(&mut self.manifest.project_type, project_type, project_type.text());
+                            }
+                        });
+                    ui.end_row();
+                });
+            ui.separator();
+            ui.label("Every occurrence of a literal (in file contents and paths) becomes `{{variable}}` in the template.");
+            self.variables_ui(ui);
+            ui.separator();
+            ui.horizontal(|ui| {
+                let has_duplicates = (0..self.manifest.variables.len()).any(|index| self.is_duplicate(index));
+                let save = ui.add_enabled(!has_duplicates, Button::new("Save"))
+                    .on_disabled_hover_text("Every variable needs a different name.");
+                if save.clicked() {
+                    self.save(ui.ctx().clone());
+                }
+                if ui.clicked("Cancel") {
+                    closer.close();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,29 @@
+use eframe::egui::*;
+
+use crate::toolchain::ToolchainPin;
+
+/// The pinned toolchains of a project, shown in the details of its recent entry.
+pub struct ToolchainDetails<'a> {
+    pins: &'a [ToolchainPin],
+}
+
+impl<'a> ToolchainDetails<'a> {
+    #[inline]
+    pub const fn new(pins: &'a [ToolchainPin]) -> Self {
+        Self { pins }
+    }
+
+    /// `Rust 1.75.0 (rust-toolchain.toml): installed 1.80.0 ⚠`
+    pub fn ui(&self, ui: &mut Ui) {
+        for pin in self.pins {
+            let installed = pin.installed.as_deref().unwrap_or("not installed");
+            let text = format!("{} {} ({}): {installed}", pin.kind.text(), pin.requirement, pin.source);
+            match pin.satisfied {
+                Some(true) => ui.label(format!("{text} ✔")),
+                Some(false) => ui.colored_label(Color32::from_rgb(230, 160, 40), format!("{text} ⚠ does not satisfy the requirement")),
+                None if pin.installed.is_none() => ui.colored_label(Color32::from_rgb(230, 160, 40), format!("{text} ⚠")),
+                None => ui.colored_label(Color32::GRAY, format!("{text} (can't be checked)")),
+            };
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use eframe::egui::*;
+
+use crate::cargo_workspace::{Package, TargetKind};
+
+/// A member crate of a Cargo workspace, shown beneath the recent entry of the workspace.
+pub struct MemberRow<'a> {
+    package: &'a Package,
+}
+
+impl<'a> MemberRow<'a> {
+    #[inline]
+    pub const fn new(package: &'a Package) -> Self {
+        Self { package }
+    }
+
+    /// `lib · 3 bins · 1 example`
+    fn targets_summary(&self) -> String {
+        [TargetKind::Lib, TargetKind::Bin, TargetKind::Example].into_iter()
+            .filter_map(|kind| match self.package.count(kind) {
+                0 => None,
+                1 => Some(kind.text().to_owned()),
+                count => Some(format!("{count} {}s", kind.text())),
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
+    pub fn ui(&self, ui: &mut Ui) -> Response {
+        const HEIGHT: f32 = 24.0;
+        const INDENT: f32 = 24.0;
+        let width = ui.available_width();
+        let (rect, resp) = ui.allocate_exact_size(vec2(width, HEIGHT), Sense::click());
+        let style = ui.style().visuals.widgets.style(&resp);
+        let p = ui.painter().with_clip_rect(rect);
+        p.rect(rect, CornerRadius::ZERO, style.bg_fill, style.bg_stroke, StrokeKind::Inside);
+        let text_rect = Rect::from_min_max(pos2(rect.left() + INDENT, rect.top()), rect.max).shrink(4.0);
+        let text_p = p.with_clip_rect(text_rect);
+        let name = match &self.package.version {
+            Some(version) => format!("└ 📦 {} v{version}", self.package.name),
+            None => format!("└ 📦 {}", self.package.name),
+        };
+        text_p.text(text_rect.left_center(), Align2::LEFT_CENTER, name, FontId::monospace(14.0), Color32::WHITE);
+        text_p.text(text_rect.right_center(), Align2::RIGHT_CENTER, self.targets_summary(), FontId::monospace(12.0), Color32::LIGHT_GRAY);
+        resp.on_hover_ui(|ui| {
+            ui.label(self.package.path.display().to_string());
+            for target in &self.package.targets {
+                ui.label(format!("{}: {}", target.kind.text(), target.name));
+            }
+        })
+    }
+}
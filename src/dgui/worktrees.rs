@@ -0,0 +1,327 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use eframe::egui::*;
+
+use crate::{
+    app::ProjectorApp,
+    dgui::mbox::{centered_mbox_modal, MessageBox},
+    ext::UiExt,
+    git::{add_worktree, list_worktrees, local_branches, prune_worktrees, remove_worktree, worktree_directory, Worktree},
+    settings::Closer,
+    util::execute::ExecError,
+};
+
+/// A linked worktree, shown beneath the recent entry of its repository.
+pub struct WorktreeRow<'a> {
+    worktree: &'a Worktree,
+}
+
+impl<'a> WorktreeRow<'a> {
+    #[inline]
+    pub const fn new(worktree: &'a Worktree) -> Self {
+        Self { worktree }
+    }
+
+    pub fn ui(&self, ui: &mut Ui) -> Response {
+        const HEIGHT: f32 = 24.0;
+        const INDENT: f32 = 24.0;
+        let width = ui.available_width();
+        let (rect, resp) = ui.allocate_exact_size(vec2(width, HEIGHT), Sense::click());
+        let style = ui.style().visuals.widgets.style(&resp);
+        let p = ui.painter().with_clip_rect(rect);
+        p.rect(rect, CornerRadius::ZERO, style.bg_fill, style.bg_stroke, StrokeKind::Inside);
+        let text_rect = Rect::from_min_max(pos2(rect.left() + INDENT, rect.top()), rect.max).shrink(4.0);
+        let name = self.worktree.path.file_name()
+            .and_then(std::ffi::OsStr::to_str)
+            .unwrap_or("");
+        let branch = self.worktree.branch.as_deref()
+            .or(self.worktree.head.as_deref())
+            .unwrap_or("");
+        let color = if self.worktree.prunable { Color32::GRAY } else { Color32::WHITE };
+        let text_p = p.with_clip_rect(text_rect);
+        text_p.text(text_rect.left_center(), Align2::LEFT_CENTER, format!("└ {name}"), FontId::monospace(14.0), color);
+        text_p.text(text_rect.right_center(), Align2::RIGHT_CENTER, format!("⎇ {branch}"), FontId::monospace(12.0), Color32::LIGHT_GRAY);
+        resp
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpenAction {
+    Explorer,
+    Terminal,
+    Editor,
+}
+
+impl OpenAction {
+    const fn text(self) -> &'static str {
+        match self {
+            OpenAction::Explorer => "Reveal in File Explorer",
+            OpenAction::Terminal => "Open Terminal Here",
+            OpenAction::Editor => "Open Editor",
+        }
+    }
+}
+
+/// A git command that the dialog runs on a background thread.
+enum Operation {
+    Refresh,
+    Add {
+        directory: PathBuf,
+        branch: String,
+    },
+    Remove {
+        path: PathBuf,
+        force: bool,
+    },
+    Prune,
+}
+
+impl Operation {
+    /// Runs the operation, then lists the worktrees and branches again.
+    fn run(self, repo: PathBuf) -> OperationResult {
+        let changes_repo = !matches!(self, Operation::Refresh);
+        let adds = matches!(self, Operation::Add { .. });
+        let result = match self {
+            Operation::Refresh => Ok(()),
+            Operation::Add { directory, branch } => add_worktree(&repo, &directory, &branch),
+            Operation::Remove { path, force } => remove_worktree(&repo, &path, force),
+            Operation::Prune => prune_worktrees(&repo),
+        };
+        OperationResult {
+            added: adds && result.is_ok(),
+            error: result.err().map(|err| err.to_string()),
+            changed: changes_repo,
+            lists: list_worktrees(&repo)
+                .and_then(|worktrees| Ok((worktrees, local_branches(&repo)?)))
+                .map_err(|err| err.to_string()),
+        }
+    }
+}
+
+struct OperationResult {
+    /// Whether a worktree was created.
+    added: bool,
+    error: Option<String>,
+    /// Whether the operation may have changed the repository.
+    changed: bool,
+    lists: Result<(Vec<Worktree>, Vec<String>), String>,
+}
+
+/// Lists, creates, and removes the worktrees of a repository.
+pub struct WorktreeDialog {
+    repo: PathBuf,
+    worktrees: Vec<Worktree>,
+    branches: Vec<String>,
+    branch: String,
+    force_remove: bool,
+    error: Option<String>,
+    /// Receives the result of the running git command.
+    pending: Option<mpsc::Receiver<OperationResult>>,
+    loaded: bool,
+}
+
+impl WorktreeDialog {
+    pub fn new<P: Into<PathBuf>>(repo: P) -> Self {
+        Self {
+            repo: repo.into(),
+            worktrees: Vec::new(),
+            branches: Vec::new(),
+            branch: String::new(),
+            force_remove: false,
+            error: None,
+            pending: None,
+            loaded: false,
+        }
+    }
+
+    fn start(&mut self, ctx: &Context, operation: Operation) {
+        let (sender, receiver) = mpsc::channel();
+        let repo = self.repo.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            sender.send(operation.run(repo)).ok();
+            ctx.request_repaint();
+        });
+        self.pending = Some(receiver);
+    }
+
+    /// Applies the result of the running git command. Returns true if the repository may have changed.
+    fn poll(&mut self) -> bool {
+        let Some(receiver) = &self.pending else {
+            return false;
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return false,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.pending = None;
+                return false;
+            }
+        };
+        self.pending = None;
+        self.loaded = true;
+        self.error = result.error;
+        match result.lists {
+            Ok((worktrees, branches)) => {
+                self.worktrees = worktrees;
+                self.branches = branches;
+            }
+            Err(err) => self.error = Some(err),
+        }
+        if result.added {
+            self.branch.clear();
+        }
+        result.changed
+    }
+
+    #[inline]
+    fn busy(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn report(&mut self, action: &str, result: Result<(), ExecError>) {
+        if let Err(err) = result {
+            self.error = Some(format!("There was an error executing {action} shell command: {err}"));
+        }
+    }
+
+    fn worktrees_ui(&mut self, app: &mut ProjectorApp, ui: &mut Ui) {
+        let busy = self.busy();
+        let mut open: Option<(OpenAction, PathBuf)> = None;
+        let mut remove: Option<PathBuf> = None;
+        Grid::new("worktrees_grid")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for (index, worktree) in self.worktrees.iter().enumerate() {
+                    let branch = match (&worktree.branch, &worktree.head) {
+                        (Some(branch), _) => branch.clone(),
+                        (None, Some(head)) => format!("({head})"),
+                        (None, None) => String::from("(bare)"),
+                    };
+                    ui.label(branch);
+                    ui.label(worktree.path.display().to_string());
+                    let mut flags = Vec::new();
+                    if index == 0 {
+                        flags.push("main");
+                    }
+                    if worktree.locked {
+                        flags.push("locked");
+                    }
+                    if worktree.prunable {
+                        flags.push("stale");
+                    }
+                    ui.label(flags.join(", "));
+                    ui.horizontal(|ui| {
+                        let exists = worktree.path.is_dir();
+                        if ui.add_enabled(exists, Button::new("🗀")).on_hover_text("Reveal in File Explorer").clicked() {
+                            open = Some((OpenAction::Explorer, worktree.path.clone()));
+                        }
+                        if ui.add_enabled(exists, Button::new("🗖")).on_hover_text("Open Terminal Here").clicked() {
+                            open = Some((OpenAction::Terminal, worktree.path.clone()));
+                        }
+                        if ui.add_enabled(exists, Button::new("✏")).on_hover_text("Open in Editor").clicked() {
+                            open = Some((OpenAction::Editor, worktree.path.clone()));
+                        }
+                        if index != 0 && !worktree.prunable && ui.add_enabled(!busy, Button::new("🗑")).on_hover_text("Remove worktree (the branch is kept)").clicked() {
+                            remove = Some(worktree.path.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        if let Some((action, path)) = open {
+            let result = match action {
+                OpenAction::Explorer => app.reveal_in_file_explorer(&path),
+                OpenAction::Terminal => app.open_terminal_here(&path),
+                OpenAction::Editor => app.open_in_editor(&path),
+            };
+            self.report(action.text(), result);
+        }
+        if let Some(path) = remove {
+            let force = self.force_remove;
+            self.start(ui.ctx(), Operation::Remove { path, force });
+        }
+    }
+
+    fn create_ui(&mut self, app: &mut ProjectorApp, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Branch");
+            TextEdit::singleline(&mut self.branch)
+                .desired_width(200.0)
+                .hint_text("New or existing branch")
+                .show(ui);
+            ComboBox::new("worktree_branch_combo", "")
+                .selected_text("Existing")
+                .show_ui(ui, |ui| {
+                    for branch in &self.branches {
+                        if ui.selectable_label(&self.branch == branch, branch).clicked() {
+                            self.branch = branch.clone();
+                        }
+                    }
+                });
+        });
+        let branch = self.branch.trim().to_owned();
+        let directory = worktree_directory(&self.repo, &app.settings().git.worktree_directory, &branch);
+        match &directory {
+            Ok(directory) if !branch.is_empty() => {
+                let verb = if self.branches.contains(&branch) { "Check out" } else { "Create" };
+                ui.label(format!("{verb} `{branch}` at {}", directory.display()));
+            }
+            Ok(_) => (),
+            Err(err) => {
+                ui.colored_label(Color32::RED, format!("Invalid worktree directory in Settings > Git: {err}"));
+            }
+        }
+        let can_create = !self.busy() && !branch.is_empty() && directory.as_ref().is_ok_and(|directory| !directory.exists());
+        if ui.add_enabled(can_create, Button::new("Create Worktree")).clicked() {
+            if let Ok(directory) = directory {
+                self.start(ui.ctx(), Operation::Add { directory, branch });
+            }
+        }
+    }
+
+    #[inline]
+    fn has_stale(&self) -> bool {
+        self.worktrees.iter().any(|worktree| worktree.prunable)
+    }
+}
+
+impl MessageBox<ProjectorApp> for WorktreeDialog {
+    fn show(&mut self, app: &mut ProjectorApp, closer: Closer, ui: &mut Ui) {
+        centered_mbox_modal(ui.ctx(), |ui| {
+            ui.set_width(700.0);
+            if !self.loaded && !self.busy() {
+                self.start(ui.ctx(), Operation::Refresh);
+            }
+            if self.poll() {
+                app.git_status_cache().invalidate(&self.repo);
+            }
+            ui.horizontal(|ui| {
+                ui.label(format!("Worktrees of {}", self.repo.display()));
+                if self.busy() {
+                    ui.spinner();
+                }
+            });
+            ui.separator();
+            self.worktrees_ui(app, ui);
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!self.busy() && self.has_stale(), Button::new("Prune Stale")).on_hover_text("Removes worktrees whose directories no longer exist.").clicked() {
+                    self.start(ui.ctx(), Operation::Prune);
+                }
+                ui.checkbox(&mut self.force_remove, "Force remove")
+                    .on_hover_text("Remove worktrees even if they have uncommitted changes.");
+            });
+            ui.separator();
+            self.create_ui(app, ui);
+            if let Some(error) = &self.error {
+                ui.separator();
+                ui.colored_label(Color32::RED, error);
+            }
+            ui.separator();
+            if ui.clicked("Close") {
+                closer.close();
+            }
+        });
+    }
+}
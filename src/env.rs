@@ -0,0 +1,319 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::appdata::AppConfig;
+
+/// The `.env` files that are loaded, in order. Later files override earlier files.
+pub const DOTENV_FILES: [&str; 2] = [".env", ".env.local"];
+
+/// Where the value of a variable comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnvSource {
+    /// Projector's own environment, which every launched process inherits.
+    Inherited,
+    /// One of [DOTENV_FILES].
+    DotEnv(&'static str),
+    /// Defined for the project in Projector.
+    Project,
+}
+
+impl EnvSource {
+    pub const fn text(self) -> &'static str {
+        match self {
+            EnvSource::Inherited => "inherited",
+            EnvSource::DotEnv(file) => file,
+            EnvSource::Project => "project",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+    pub source: EnvSource,
+}
+
+impl EnvVar {
+    /// Whether the value should be masked when it's shown.
+    #[inline]
+    pub fn is_secret(&self) -> bool {
+        is_secret_name(&self.name)
+    }
+}
+
+/// Names that usually hold credentials (`API_KEY`, `DB_PASSWORD`, `GITHUB_TOKEN`, ...).
+pub fn is_secret_name(name: &str) -> bool {
+    const PATTERNS: &[&str] = &["KEY", "SECRET", "TOKEN", "PASSWORD", "PASSWD", "PASS", "PWD", "CREDENTIAL", "AUTH", "PRIVATE", "DSN"];
+    let name = name.to_ascii_uppercase();
+    // `PWD` is the working directory, not a password.
+    name != "PWD" && name.split(['_', '-', '.']).any(|part| PATTERNS.contains(&part) || part.ends_with("KEY") || part.ends_with("TOKEN"))
+}
+
+/// Replaces every character of a secret value.
+#[inline]
+pub fn mask(value: &str) -> String {
+    "•".repeat(value.chars().count().clamp(4, 12))
+}
+
+/// Parses the contents of a `.env` file.
+///
+/// Supports `#` comments, an optional `export ` prefix, single quoted values (literal), double quoted values
+/// (with `\n`, `\t`, `\"`, and `\\` escapes, and which may span lines), and unquoted values (a ` #` starts a comment).
+/// Variables are not expanded. Lines that aren't assignments are skipped.
+pub fn parse_dotenv(text: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+            continue;
+        }
+        let value = value.trim_start();
+        let value = if let Some(rest) = value.strip_prefix('\'') {
+            match rest.split_once('\'') {
+                Some((value, _)) => value.to_owned(),
+                None => rest.to_owned(),
+            }
+        } else if let Some(rest) = value.strip_prefix('"') {
+            let mut raw = rest.to_owned();
+            // The value ends at the first unescaped quote, which may be on a later line.
+            let mut end = closing_quote(&raw);
+            while end.is_none() {
+                let Some(next) = lines.next() else {
+                    break;
+                };
+                raw.push('\n');
+                raw.push_str(next);
+                end = closing_quote(&raw);
+            }
+            unescape(&raw[..end.unwrap_or(raw.len())])
+        } else {
+            let value = match value.find(" #") {
+                Some(index) => &value[..index],
+                None => value,
+            };
+            value.trim_end().to_owned()
+        };
+        vars.push((name.to_owned(), value));
+    }
+    vars
+}
+
+fn closing_quote(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(index),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other @ ('"' | '\\')) => result.push(other),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// The environment variables of a project.
+///
+/// Precedence, from lowest to highest:
+/// 1. Projector's own environment (inherited).
+/// 2. `.env` in the project directory, if `load_dotenv` is set.
+/// 3. `.env.local` in the project directory, if `load_dotenv` is set.
+/// 4. Variables defined for the project in Projector.
+/// 5. Variables of a launch configuration step (only for that step).
+#[derive(Debug, Default, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct ProjectEnv {
+    pub vars: Vec<(String, String)>,
+    pub load_dotenv: bool,
+}
+
+impl ProjectEnv {
+    /// The variables that are set for processes launched for the project in `dir`, with the source of each.
+    /// Inherited variables are not included. Missing or unreadable `.env` files are skipped.
+    pub fn resolve<P: AsRef<Path>>(&self, dir: P) -> Vec<EnvVar> {
+        fn set(vars: &mut Vec<EnvVar>, name: &str, value: &str, source: EnvSource) {
+            match vars.iter_mut().find(|var| var.name == name) {
+                Some(var) => {
+                    var.value = value.to_owned();
+                    var.source = source;
+                }
+                None => vars.push(EnvVar { name: name.to_owned(), value: value.to_owned(), source }),
+            }
+        }
+        let dir = dir.as_ref();
+        let mut vars = Vec::new();
+        if self.load_dotenv {
+            for file in DOTENV_FILES {
+                let Ok(text) = std::fs::read_to_string(dir.join(file)) else {
+                    continue;
+                };
+                for (name, value) in parse_dotenv(&text) {
+                    set(&mut vars, &name, &value, EnvSource::DotEnv(file));
+                }
+            }
+        }
+        for (name, value) in self.vars.iter().filter(|(name, _)| !name.trim().is_empty()) {
+            set(&mut vars, name.trim(), value, EnvSource::Project);
+        }
+        vars
+    }
+
+    /// [ProjectEnv::resolve] as name/value pairs, for [std::process::Command::envs].
+    pub fn pairs<P: AsRef<Path>>(&self, dir: P) -> Vec<(String, String)> {
+        self.resolve(dir).into_iter().map(|var| (var.name, var.value)).collect()
+    }
+}
+
+/// The environments of every project, stored in `<program>/config/`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct ProjectEnvs {
+    envs: HashMap<PathBuf, ProjectEnv>,
+}
+
+impl ProjectEnvs {
+    /// The relative path of the project environments in `<program>/config/`
+    pub const CONFIG_REL_PATH: &str = "project_env";
+
+    pub fn load(config: &AppConfig) -> crate::error::Result<Self> {
+        config.load_or_default(Self::CONFIG_REL_PATH)
+    }
+
+    pub fn save(&self, config: &AppConfig) -> crate::error::Result<()> {
+        config.save(Self::CONFIG_REL_PATH, self)
+    }
+
+    pub fn get<P: AsRef<Path>>(&self, project: P) -> Option<&ProjectEnv> {
+        self.envs.get(project.as_ref())
+    }
+
+    pub fn set<P: Into<PathBuf>>(&mut self, project: P, env: ProjectEnv) {
+        let project = project.into();
+        if env == ProjectEnv::default() {
+            self.envs.remove(&project);
+        } else {
+            self.envs.insert(project, env);
+        }
+    }
+
+    /// The variables for a process launched in `path`. The environment of the nearest project that contains `path`
+    /// is used so that workspace members share the environment of their project. Linked worktrees are siblings of
+    /// their repository, so a worktree without an environment of its own uses the environment of its main worktree.
+    pub fn pairs_for<P: AsRef<Path>>(&self, path: P) -> Vec<(String, String)> {
+        let path = path.as_ref();
+        let nearest = |path: &Path| path.ancestors()
+            .find_map(|dir| Some((dir.to_path_buf(), self.envs.get(dir)?)));
+        nearest(path)
+            .or_else(|| {
+                let (main, worktree) = crate::git::main_worktree(path)?;
+                nearest(&main.join(path.strip_prefix(&worktree).ok()?))
+            })
+            .map(|(dir, env)| env.pairs(dir))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dotenv_test() {
+        let text = "\
+# comment
+export DATABASE_URL=postgres://localhost/db # trailing comment
+EMPTY=
+SINGLE='literal $HOME \\n'
+DOUBLE=\"line\\nnext \\\"quoted\\\"\"
+MULTI=\"first
+second\"
+not an assignment
+URL=http://example.com/#anchor
+";
+        assert_eq!(parse_dotenv(text), vec![
+            (String::from("DATABASE_URL"), String::from("postgres://localhost/db")),
+            (String::from("EMPTY"), String::new()),
+            (String::from("SINGLE"), String::from("literal $HOME \\n")),
+            (String::from("DOUBLE"), String::from("line\nnext \"quoted\"")),
+            (String::from("MULTI"), String::from("first\nsecond")),
+            (String::from("URL"), String::from("http://example.com/#anchor")),
+        ]);
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        std::fs::write(dir.path().join(".env"), "A=env\nB=env\nC=env\n").unwrap();
+        std::fs::write(dir.path().join(".env.local"), "B=local\nC=local\n").unwrap();
+        let env = ProjectEnv {
+            vars: vec![(String::from("C"), String::from("project"))],
+            load_dotenv: true,
+        };
+        let vars = env.resolve(dir.path());
+        let summary = vars.iter().map(|var| (var.name.as_str(), var.value.as_str(), var.source)).collect::<Vec<_>>();
+        assert_eq!(summary, vec![
+            ("A", "env", EnvSource::DotEnv(".env")),
+            ("B", "local", EnvSource::DotEnv(".env.local")),
+            ("C", "project", EnvSource::Project),
+        ]);
+
+        let mut envs = ProjectEnvs::default();
+        envs.set(dir.path(), env);
+        assert_eq!(envs.pairs_for(dir.path().join("crates").join("member")).len(), 3);
+
+        // A linked worktree is a sibling of its repository, but uses the repository's environment.
+        let root = tempfile::tempdir().expect("Failed to create temp dir.");
+        let repo = root.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        crate::git::init_repository(&repo, &crate::git::GitInitOptions {
+            default_branch: String::from("main"),
+            author_name: String::from("Test Author"),
+            author_email: String::from("test@example.com"),
+            commit_message: String::from("First"),
+            remote_url: None,
+        }).expect("Failed to initialize repository.");
+        let worktree = root.path().join("repo-feature");
+        crate::git::add_worktree(&repo, &worktree, "feature").expect("Failed to add worktree.");
+        let mut envs = ProjectEnvs::default();
+        envs.set(&repo, ProjectEnv {
+            vars: vec![(String::from("MODE"), String::from("dev"))],
+            load_dotenv: false,
+        });
+        assert_eq!(envs.pairs_for(&worktree), vec![(String::from("MODE"), String::from("dev"))]);
+        assert!(envs.pairs_for(root.path()).is_empty());
+
+        assert!(is_secret_name("API_KEY"));
+        assert!(is_secret_name("github_token"));
+        assert!(is_secret_name("DB_PASSWORD"));
+        assert!(!is_secret_name("PWD"));
+        assert!(!is_secret_name("KEYBOARD_LAYOUT"));
+    }
+}
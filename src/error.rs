@@ -15,6 +15,8 @@ pub enum Error {
     TomlDecodeError(#[from] toml::de::Error),
     #[error("Toml Encode Error: {0}")]
     TomlEncodeError(#[from] toml::ser::Error),
+    #[error("The settings file has version {0}, which is newer than this version of Projector supports.")]
+    UnsupportedSettingsVersion(u32),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
@@ -0,0 +1,71 @@
+/// Scores how well `query` matches `candidate`. Every character of `query` must appear in `candidate` in order,
+/// ignoring case. Returns `None` if `query` doesn't match. Higher scores are better matches.
+///
+/// Exact matches score highest, then prefixes. Matched characters that are consecutive or at the start of a word
+/// score more, and shorter candidates score more than longer ones.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query = query.to_lowercase().chars().collect::<Vec<_>>();
+    let candidate_chars = candidate.chars().collect::<Vec<_>>();
+    let lower = candidate_chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect::<Vec<_>>();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let mut score = 0i64;
+    let mut position = 0usize;
+    let mut previous = None;
+    for &query_char in query.iter() {
+        let index = position + lower[position..].iter().position(|&c| c == query_char)?;
+        score += 10;
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 15;
+        }
+        let word_start = index == 0 || {
+            let before = candidate_chars[index - 1];
+            !before.is_alphanumeric() || (before.is_lowercase() && candidate_chars[index].is_uppercase())
+        };
+        if word_start {
+            score += 20;
+        }
+        score -= (index - position) as i64;
+        previous = Some(index);
+        position = index + 1;
+    }
+    let query = query.into_iter().collect::<String>();
+    let lower = lower.into_iter().collect::<String>();
+    if lower == query {
+        score += 1000;
+    } else if lower.starts_with(&query) {
+        score += 500;
+    }
+    Some(score - candidate_chars.len() as i64)
+}
+
+/// The items that match `query`, best first. `key` is the text that an item is matched by.
+pub fn fuzzy_matches<T, I, F>(query: &str, items: I, key: F) -> Vec<(i64, T)>
+where
+    I: IntoIterator<Item = T>,
+    F: Fn(&T) -> &str,
+{
+    let mut matches = items.into_iter()
+        .filter_map(|item| Some((fuzzy_score(query, key(&item))?, item)))
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_test() {
+        assert!(fuzzy_score("prj", "projector").is_some());
+        assert!(fuzzy_score("pjx", "projector").is_none());
+        assert!(fuzzy_score("projector", "projector") > fuzzy_score("projector", "projector-old"));
+        assert!(fuzzy_score("proj", "projector") > fuzzy_score("proj", "my-projector"));
+        assert!(fuzzy_score("mp", "my-projector") > fuzzy_score("mp", "example"));
+        assert!(fuzzy_score("PROJ", "Projector").is_some());
+        let matches = fuzzy_matches("web", ["website", "my-web-app", "rust"], |name| name);
+        assert_eq!(matches.iter().map(|&(_, name)| name).collect::<Vec<_>>(), ["website", "my-web-app"]);
+    }
+}
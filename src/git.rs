@@ -0,0 +1,295 @@
+use std::path::{Path, PathBuf};
+
+use crate::util::execute::background_command;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GitError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("`git {args}` failed:\n{stderr}")]
+    CommandFailed {
+        args: String,
+        stderr: String,
+    },
+}
+
+/// Runs `git` in `dir` and returns stdout. Non-zero exit codes become [GitError::CommandFailed] with the captured stderr.
+pub fn git<P: AsRef<Path>>(dir: P, args: &[&str], envs: &[(&str, &str)]) -> Result<String, GitError> {
+    fn inner(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> Result<String, GitError> {
+        let output = background_command("git")
+            .current_dir(dir)
+            .args(args)
+            .envs(envs.iter().copied())
+            .output()?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(GitError::CommandFailed {
+                args: args.join(" "),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            })
+        }
+    }
+    inner(dir.as_ref(), args, envs)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitInitOptions {
+    /// Empty uses git's default.
+    pub default_branch: String,
+    /// Empty uses the author from the git config.
+    pub author_name: String,
+    /// Empty uses the author email from the git config.
+    pub author_email: String,
+    pub commit_message: String,
+    /// Added as `origin`. The remote is not contacted.
+    pub remote_url: Option<String>,
+}
+
+/// Initializes a repository in `dir`, commits everything that isn't ignored, and optionally adds a remote.
+pub fn init_repository<P: AsRef<Path>>(dir: P, options: &GitInitOptions) -> Result<(), GitError> {
+    fn inner(dir: &Path, options: &GitInitOptions) -> Result<(), GitError> {
+        git(dir, &["init", "--quiet"], &[])?;
+        let branch = options.default_branch.trim();
+        if !branch.is_empty() {
+            // `git init --initial-branch` requires git 2.28, while this works on every version.
+            let head = format!("refs/heads/{branch}");
+            git(dir, &["symbolic-ref", "HEAD", &head], &[])?;
+        }
+        // The generated .gitignore is in place before this, so ignored files are skipped.
+        git(dir, &["add", "--all"], &[])?;
+        let name = options.author_name.trim();
+        let email = options.author_email.trim();
+        let mut envs = Vec::new();
+        if !name.is_empty() {
+            envs.push(("GIT_AUTHOR_NAME", name));
+            envs.push(("GIT_COMMITTER_NAME", name));
+        }
+        if !email.is_empty() {
+            envs.push(("GIT_AUTHOR_EMAIL", email));
+            envs.push(("GIT_COMMITTER_EMAIL", email));
+        }
+        let message = match options.commit_message.trim() {
+            "" => "Initial commit",
+            message => message,
+        };
+        git(dir, &["commit", "--quiet", "--allow-empty", "-m", message], &envs)?;
+        if let Some(url) = options.remote_url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+            git(dir, &["remote", "add", "origin", url], &[])?;
+        }
+        Ok(())
+    }
+    inner(dir.as_ref(), options)
+}
+
+/// An entry from `git worktree list`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, bincode::Encode, bincode::Decode)]
+pub struct Worktree {
+    pub path: PathBuf,
+    /// The abbreviated commit hash that is checked out.
+    pub head: Option<String>,
+    /// `None` when `HEAD` is detached.
+    pub branch: Option<String>,
+    pub bare: bool,
+    pub locked: bool,
+    /// The worktree's directory no longer exists. `git worktree prune` removes it.
+    pub prunable: bool,
+}
+
+impl Worktree {
+    /// Parses the output of `git worktree list --porcelain`. The main worktree is first.
+    pub fn parse_list(output: &str) -> Vec<Worktree> {
+        let mut worktrees = Vec::new();
+        for block in output.split("\n\n") {
+            let mut worktree = Worktree::default();
+            for line in block.lines() {
+                let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+                match key {
+                    "worktree" => worktree.path = PathBuf::from(value),
+                    "HEAD" => worktree.head = Some(value.chars().take(7).collect()),
+                    "branch" => worktree.branch = Some(value.strip_prefix("refs/heads/").unwrap_or(value).to_owned()),
+                    "bare" => worktree.bare = true,
+                    "locked" => worktree.locked = true,
+                    "prunable" => worktree.prunable = true,
+                    _ => (),
+                }
+            }
+            if !worktree.path.as_os_str().is_empty() {
+                worktrees.push(worktree);
+            }
+        }
+        worktrees
+    }
+}
+
+pub fn list_worktrees<P: AsRef<Path>>(repo: P) -> Result<Vec<Worktree>, GitError> {
+    let output = git(repo, &["worktree", "list", "--porcelain"], &[])?;
+    Ok(Worktree::parse_list(&output))
+}
+
+/// The names of the local branches.
+pub fn local_branches<P: AsRef<Path>>(repo: P) -> Result<Vec<String>, GitError> {
+    let output = git(repo, &["for-each-ref", "--format=%(refname:short)", "refs/heads"], &[])?;
+    Ok(output.lines().map(str::to_owned).collect())
+}
+
+/// Checks out `branch` in a new worktree at `path`. The branch is created from `HEAD` if it doesn't exist.
+pub fn add_worktree<P0: AsRef<Path>, P1: AsRef<Path>>(repo: P0, path: P1, branch: &str) -> Result<(), GitError> {
+    fn inner(repo: &Path, path: &Path, branch: &str) -> Result<(), GitError> {
+        let path = path.display().to_string();
+        let exists = local_branches(repo)?.iter().any(|local| local == branch);
+        if exists {
+            git(repo, &["worktree", "add", &path, branch], &[])?;
+        } else {
+            git(repo, &["worktree", "add", "-b", branch, &path], &[])?;
+        }
+        Ok(())
+    }
+    inner(repo.as_ref(), path.as_ref(), branch)
+}
+
+/// Removes a worktree. The branch is kept.
+pub fn remove_worktree<P0: AsRef<Path>, P1: AsRef<Path>>(repo: P0, path: P1, force: bool) -> Result<(), GitError> {
+    let path = path.as_ref().display().to_string();
+    let mut args = vec!["worktree", "remove"];
+    if force {
+        args.push("--force");
+    }
+    args.push(&path);
+    git(repo, &args, &[])?;
+    Ok(())
+}
+
+/// Removes the administrative files of worktrees whose directories no longer exist.
+pub fn prune_worktrees<P: AsRef<Path>>(repo: P) -> Result<(), GitError> {
+    git(repo, &["worktree", "prune"], &[])?;
+    Ok(())
+}
+
+/// The main worktree of the linked worktree that contains `path`, along with the linked worktree's directory.
+/// Returns `None` if `path` isn't in a linked worktree. Git isn't run; the worktree's `.git` file is read instead.
+pub fn main_worktree<P: AsRef<Path>>(path: P) -> Option<(PathBuf, PathBuf)> {
+    fn inner(path: &Path) -> Option<(PathBuf, PathBuf)> {
+        let worktree = path.ancestors().find(|dir| dir.join(".git").exists())?;
+        // The `.git` of the main worktree is a directory, and the `.git` of a linked worktree is a file that points to
+        // the worktree's directory in the main repository's `.git/worktrees/`.
+        let dot_git = std::fs::read_to_string(worktree.join(".git")).ok()?;
+        let git_dir = worktree.join(dot_git.trim().strip_prefix("gitdir:")?.trim());
+        let common_dir = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+        let mut common_dir = git_dir.join(common_dir.trim());
+        // `commondir` is usually `../..`, which is resolved here so that the result can be compared with other paths.
+        common_dir = common_dir.components().fold(PathBuf::new(), |mut resolved, component| {
+            match component {
+                std::path::Component::ParentDir => _ = resolved.pop(),
+                std::path::Component::CurDir => (),
+                component => resolved.push(component),
+            }
+            resolved
+        });
+        // A bare repository has no main worktree.
+        if common_dir.file_name()? != ".git" {
+            return None;
+        }
+        Some((common_dir.parent()?.to_path_buf(), worktree.to_path_buf()))
+    }
+    inner(path.as_ref())
+}
+
+/// Resolves the directory for a new worktree from a pattern such as `{repo}-{branch}`.
+/// The result is relative to the repository's parent directory, so worktrees are siblings of the repository.
+pub fn worktree_directory<P: AsRef<Path>>(repo: P, pattern: &str, branch: &str) -> Result<PathBuf, strfmt::FmtError> {
+    fn inner(repo: &Path, pattern: &str, branch: &str) -> Result<PathBuf, strfmt::FmtError> {
+        let repo_name = repo.file_name()
+            .and_then(std::ffi::OsStr::to_str)
+            .unwrap_or("repo")
+            .to_owned();
+        // `feature/name` would otherwise create nested directories.
+        let branch = branch.replace(['/', '\\'], "-");
+        use strfmt::strfmt;
+        let relative = strfmt!(pattern, repo => repo_name, branch => branch)?;
+        let parent = repo.parent().unwrap_or(repo);
+        Ok(parent.join(relative))
+    }
+    inner(repo.as_ref(), pattern, branch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_repository_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        std::fs::write(dir.path().join(".gitignore"), "/target\n").unwrap();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target").join("out"), "ignored").unwrap();
+        init_repository(dir.path(), &GitInitOptions {
+            default_branch: String::from("trunk"),
+            author_name: String::from("Test Author"),
+            author_email: String::from("test@example.com"),
+            commit_message: String::from("First"),
+            remote_url: Some(String::from("git@github.com:example/project.git")),
+        }).expect("Failed to initialize repository.");
+        let branch = git(dir.path(), &["rev-parse", "--abbrev-ref", "HEAD"], &[]).unwrap();
+        assert_eq!(branch.trim(), "trunk");
+        let author = git(dir.path(), &["log", "-1", "--format=%an <%ae> %s"], &[]).unwrap();
+        assert_eq!(author.trim(), "Test Author <test@example.com> First");
+        let files = git(dir.path(), &["ls-files"], &[]).unwrap();
+        assert_eq!(files.lines().collect::<Vec<_>>(), vec![".gitignore", "main.rs"]);
+        let remote = git(dir.path(), &["remote", "get-url", "origin"], &[]).unwrap();
+        assert_eq!(remote.trim(), "git@github.com:example/project.git");
+        let err = git(dir.path(), &["checkout", "does-not-exist"], &[]).unwrap_err();
+        assert!(matches!(err, GitError::CommandFailed { stderr, .. } if !stderr.is_empty()));
+    }
+
+    #[test]
+    fn worktree_test() {
+        let output = "\
+worktree /code/projector
+HEAD 1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b
+branch refs/heads/main
+
+worktree /code/projector-feature-badges
+HEAD 0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b2a1f
+branch refs/heads/feature/badges
+locked
+
+worktree /code/gone
+HEAD 0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b2a1f
+detached
+prunable gitdir file points to non-existent location
+";
+        let worktrees = Worktree::parse_list(output);
+        assert_eq!(worktrees.len(), 3);
+        assert_eq!(worktrees[0].branch.as_deref(), Some("main"));
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feature/badges"));
+        assert!(worktrees[1].locked);
+        assert_eq!(worktrees[2].branch, None);
+        assert_eq!(worktrees[2].head.as_deref(), Some("0a9f8e7"));
+        assert!(worktrees[2].prunable);
+        let dir = worktree_directory("/code/projector", "{repo}-{branch}", "feature/badges").unwrap();
+        assert_eq!(dir, PathBuf::from("/code/projector-feature-badges"));
+
+        let root = tempfile::tempdir().expect("Failed to create temp dir.");
+        let repo = root.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        init_repository(&repo, &GitInitOptions {
+            default_branch: String::from("main"),
+            author_name: String::from("Test Author"),
+            author_email: String::from("test@example.com"),
+            commit_message: String::new(),
+            remote_url: None,
+        }).expect("Failed to initialize repository.");
+        let worktree_dir = worktree_directory(&repo, "{repo}-{branch}", "feature/x").unwrap();
+        add_worktree(&repo, &worktree_dir, "feature/x").expect("Failed to add worktree.");
+        let worktrees = list_worktrees(&repo).unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feature/x"));
+        assert!(local_branches(&repo).unwrap().contains(&String::from("feature/x")));
+        assert_eq!(main_worktree(worktree_dir.join("src")), Some((repo.clone(), worktree_dir.clone())));
+        assert_eq!(main_worktree(&repo), None);
+        remove_worktree(&repo, &worktree_dir, false).expect("Failed to remove worktree.");
+        assert_eq!(list_worktrees(&repo).unwrap().len(), 1);
+    }
+}
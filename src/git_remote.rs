@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use crate::git::{git, GitError};
+
+/// A remote from `git remote -v`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, bincode::Encode, bincode::Decode)]
+pub struct Remote {
+    pub name: String,
+    /// The fetch URL.
+    pub url: String,
+}
+
+impl Remote {
+    /// Parses the output of `git remote -v`. Push URLs are skipped.
+    pub fn parse_list(output: &str) -> Vec<Remote> {
+        output.lines()
+            .filter_map(|line| {
+                let (name, rest) = line.split_once('\t')?;
+                let url = rest.strip_suffix(" (fetch)")?;
+                Some(Remote { name: name.to_owned(), url: url.to_owned() })
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub fn web_url(&self) -> Option<WebUrl> {
+        WebUrl::from_remote_url(&self.url)
+    }
+}
+
+/// Reads the remotes of a repository. Remotes are not contacted.
+pub fn list_remotes<P: AsRef<Path>>(repo: P) -> Result<Vec<Remote>, GitError> {
+    let output = git(repo, &["remote", "-v"], &[])?;
+    Ok(Remote::parse_list(&output))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Host {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    /// Gitea, Forgejo, and any other host. These use Gitea's URL layout.
+    Gitea,
+}
+
+impl Host {
+    fn from_host_name(host: &str) -> Self {
+        let host = host.to_ascii_lowercase();
+        if host.contains("github") {
+            Host::GitHub
+        } else if host.contains("gitlab") {
+            Host::GitLab
+        } else if host.contains("bitbucket") {
+            Host::Bitbucket
+        } else {
+            Host::Gitea
+        }
+    }
+}
+
+/// The HTTPS web page of a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WebUrl {
+    pub host: Host,
+    /// `https://<host>/<owner>/<repo>`
+    pub base: String,
+}
+
+impl WebUrl {
+    /// Converts a remote URL (`git@host:owner/repo.git`, `ssh://git@host:22/owner/repo.git`,
+    /// `https://user@host/owner/repo.git`, `git://host/owner/repo`) to the repository's web page.
+    /// Returns `None` for local paths.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (host, path) = if let Some((scheme, rest)) = url.split_once("://") {
+            if !matches!(scheme, "ssh" | "git" | "http" | "https" | "git+ssh" | "ssh+git") {
+                return None;
+            }
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+            // SSH ports are not web ports.
+            let host = if scheme == "http" || scheme == "https" {
+                host
+            } else {
+                host.split_once(':').map_or(host, |(host, _)| host)
+            };
+            (host, path)
+        } else {
+            // scp-like syntax: `[user@]host:path`. A `/` before the `:` means it's a local path.
+            let (authority, path) = url.split_once(':')?;
+            if authority.contains('/') || authority.len() < 2 {
+                return None;
+            }
+            let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+            (host, path)
+        };
+        // GitHub's SSH-over-HTTPS host.
+        let host = if host.eq_ignore_ascii_case("ssh.github.com") { "github.com" } else { host };
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        if host.is_empty() || path.is_empty() {
+            return None;
+        }
+        Some(Self {
+            host: Host::from_host_name(host),
+            base: format!("https://{host}/{path}"),
+        })
+    }
+
+    /// The page for a branch.
+    pub fn branch_url(&self, branch: &str) -> String {
+        let branch = encode_branch(branch);
+        match self.host {
+            Host::GitHub => format!("{}/tree/{branch}", self.base),
+            Host::GitLab => format!("{}/-/tree/{branch}", self.base),
+            Host::Bitbucket => format!("{}/src/{branch}", self.base),
+            Host::Gitea => format!("{}/src/branch/{branch}", self.base),
+        }
+    }
+}
+
+/// Percent-encodes every byte of a branch name that isn't an unreserved URL character. `/` is kept.
+fn encode_branch(branch: &str) -> String {
+    let mut encoded = String::with_capacity(branch.len());
+    for byte in branch.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => encoded.push(byte as char),
+            byte => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_url_test() {
+        fn base(url: &str) -> Option<String> {
+            WebUrl::from_remote_url(url).map(|web| web.base)
+        }
+        assert_eq!(base("git@github.com:ErisianArchitect/projector.git").as_deref(), Some("https://github.com/ErisianArchitect/projector"));
+        assert_eq!(base("ssh://git@gitlab.com:2222/group/sub/project.git").as_deref(), Some("https://gitlab.com/group/sub/project"));
+        assert_eq!(base("https://user@bitbucket.org/team/repo.git").as_deref(), Some("https://bitbucket.org/team/repo"));
+        assert_eq!(base("ssh://git@ssh.github.com:443/owner/repo.git").as_deref(), Some("https://github.com/owner/repo"));
+        assert_eq!(base("https://git.example.com:3000/owner/repo/").as_deref(), Some("https://git.example.com:3000/owner/repo"));
+        assert_eq!(base("/srv/git/repo.git"), None);
+        assert_eq!(base("../repo"), None);
+        assert_eq!(base("C:/code/repo"), None);
+
+        let github = WebUrl::from_remote_url("git@github.com:owner/repo.git").unwrap();
+        assert_eq!(github.branch_url("feature/a#1"), "https://github.com/owner/repo/tree/feature/a%231");
+        assert_eq!(github.branch_url("x$(touch${IFS}pwned)`id`\"é"), "https://github.com/owner/repo/tree/x%24%28touch%24%7BIFS%7Dpwned%29%60id%60%22%C3%A9");
+        let gitlab = WebUrl::from_remote_url("git@gitlab.com:owner/repo.git").unwrap();
+        assert_eq!(gitlab.branch_url("main"), "https://gitlab.com/owner/repo/-/tree/main");
+        let bitbucket = WebUrl::from_remote_url("git@bitbucket.org:owner/repo.git").unwrap();
+        assert_eq!(bitbucket.branch_url("main"), "https://bitbucket.org/owner/repo/src/main");
+        let gitea = WebUrl::from_remote_url("git@codeberg.org:owner/repo.git").unwrap();
+        assert_eq!(gitea.branch_url("main"), "https://codeberg.org/owner/repo/src/branch/main");
+
+        let remotes = Remote::parse_list("origin\tgit@github.com:owner/repo.git (fetch)\norigin\tgit@github.com:owner/repo.git (push)\n");
+        assert_eq!(remotes, vec![Remote { name: String::from("origin"), url: String::from("git@github.com:owner/repo.git") }]);
+    }
+}
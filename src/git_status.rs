@@ -0,0 +1,153 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use eframe::egui::Context;
+
+use crate::{appdata::AppCache, util::background::BackgroundCache, git_remote::{list_remotes, Remote}, git::{git, list_worktrees, GitError, Worktree}};
+
+/// The status of a repository. Everything is computed from local refs, so remotes are never contacted.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, bincode::Encode, bincode::Decode)]
+pub struct GitStatus {
+    /// `None` when `HEAD` is detached.
+    pub branch: Option<String>,
+    /// The abbreviated commit hash of `HEAD`. `None` before the first commit.
+    pub head: Option<String>,
+    /// `true` when there are staged, unstaged, or untracked changes.
+    pub dirty: bool,
+    /// Ahead and behind counts against the upstream. `None` when there is no upstream.
+    pub ahead_behind: Option<(u32, u32)>,
+    /// Unix timestamp of the last commit.
+    pub last_commit_time: Option<i64>,
+    /// Linked worktrees. Only filled in for the main worktree.
+    pub worktrees: Vec<Worktree>,
+    pub remotes: Vec<Remote>,
+}
+
+impl GitStatus {
+    /// Parses the output of `git status --porcelain=v2 --branch`.
+    pub fn from_porcelain_v2(output: &str) -> Self {
+        let mut status = GitStatus::default();
+        for line in output.lines() {
+            if let Some(header) = line.strip_prefix("# ") {
+                let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+                match key {
+                    "branch.oid" if value != "(initial)" => {
+                        status.head = Some(value.chars().take(7).collect());
+                    }
+                    "branch.head" if value != "(detached)" => {
+                        status.branch = Some(value.to_owned());
+                    }
+                    "branch.ab" => {
+                        let mut counts = value.split(' ')
+                            .map(|count| count.trim_start_matches(['+', '-']).parse::<u32>().unwrap_or(0));
+                        status.ahead_behind = Some((counts.next().unwrap_or(0), counts.next().unwrap_or(0)));
+                    }
+                    _ => (),
+                }
+            } else if !line.is_empty() {
+                status.dirty = true;
+            }
+        }
+        status
+    }
+}
+
+#[inline]
+pub fn is_git_repository<P: AsRef<Path>>(dir: P) -> bool {
+    // `.git` is a file in worktrees and submodules.
+    dir.as_ref().join(".git").exists()
+}
+
+/// Reads the status of the repository at `dir`.
+pub fn git_status<P: AsRef<Path>>(dir: P) -> Result<GitStatus, GitError> {
+    fn inner(dir: &Path) -> Result<GitStatus, GitError> {
+        // `--no-optional-locks` keeps the refresh from competing with git commands that the user is running.
+        let output = git(dir, &["--no-optional-locks", "status", "--porcelain=v2", "--branch"], &[])?;
+        let mut status = GitStatus::from_porcelain_v2(&output);
+        if status.head.is_some() {
+            status.last_commit_time = git(dir, &["log", "-1", "--format=%ct"], &[])?.trim().parse().ok();
+        }
+        status.remotes = list_remotes(dir)?;
+        let mut worktrees = list_worktrees(dir)?;
+        // The main worktree is always listed first.
+        if !worktrees.is_empty() && same_file::is_same_file(&worktrees[0].path, dir).unwrap_or(false) {
+            worktrees.remove(0);
+            status.worktrees = worktrees;
+        }
+        Ok(status)
+    }
+    inner(dir.as_ref())
+}
+
+/// Computes [GitStatus] on a background thread so that the UI never waits on git.
+/// Statuses are persisted in [AppCache] so that the last known status is shown right away on startup.
+pub struct GitStatusCache {
+    cache: BackgroundCache<GitStatus>,
+}
+
+impl GitStatusCache {
+    /// The relative path of the cache file in `<program>/cache/`
+    const CACHE_REL_PATH: &str = "git_status";
+    /// How long a status is used before it is refreshed.
+    const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+    pub fn new(ctx: Context, cache: &AppCache) -> Self {
+        let loaded = cache.load::<_, HashMap<PathBuf, GitStatus>>(Self::CACHE_REL_PATH).unwrap_or_default();
+        Self {
+            cache: BackgroundCache::new("git-status", ctx, Self::REFRESH_INTERVAL, loaded, |path| {
+                if is_git_repository(path) {
+                    git_status(path).ok()
+                } else {
+                    None
+                }
+            }),
+        }
+    }
+
+    /// Returns the last known status and queues a refresh if it is out of date.
+    #[inline]
+    pub fn get<P: AsRef<Path>>(&self, dir: P) -> Option<GitStatus> {
+        self.cache.get(dir)
+    }
+
+    /// Marks a status as out of date so that it is refreshed the next time it is requested.
+    #[inline]
+    pub fn invalidate<P: AsRef<Path>>(&self, dir: P) {
+        self.cache.invalidate(dir)
+    }
+
+    pub fn save(&self, cache: &AppCache) -> crate::error::Result<()> {
+        cache.save(Self::CACHE_REL_PATH, &self.cache.snapshot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn porcelain_v2_test() {
+        let output = "\
+# branch.oid 1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b
+# branch.head feature/badges
+# branch.upstream origin/feature/badges
+# branch.ab +2 -1
+1 .M N... 100644 100644 100644 3f2a 3f2a src/app.rs
+";
+        let status = GitStatus::from_porcelain_v2(output);
+        assert_eq!(status, GitStatus {
+            branch: Some(String::from("feature/badges")),
+            head: Some(String::from("1a2b3c4")),
+            dirty: true,
+            ahead_behind: Some((2, 1)),
+            last_commit_time: None,
+            worktrees: Vec::new(),
+            remotes: Vec::new(),
+        });
+        let status = GitStatus::from_porcelain_v2("# branch.oid (initial)\n# branch.head (detached)\n");
+        assert_eq!(status, GitStatus::default());
+    }
+}
@@ -0,0 +1,391 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    dgui::recents::RecentEntry,
+    projects::{ProjectPath, ProjectType},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON Error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("SQLite Error: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+    #[error("XML Error: {0}")]
+    XmlError(#[from] roxmltree::Error),
+}
+
+/// An editor that recent projects are imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportSource {
+    VsCode,
+    VsCodeInsiders,
+    VsCodium,
+    /// Every installed JetBrains IDE.
+    JetBrains,
+}
+
+impl ImportSource {
+    pub const ALL: [ImportSource; 4] = [ImportSource::VsCode, ImportSource::VsCodeInsiders, ImportSource::VsCodium, ImportSource::JetBrains];
+
+    pub const fn text(self) -> &'static str {
+        match self {
+            ImportSource::VsCode => "VS Code",
+            ImportSource::VsCodeInsiders => "VS Code Insiders",
+            ImportSource::VsCodium => "VSCodium",
+            ImportSource::JetBrains => "JetBrains IDEs",
+        }
+    }
+
+    /// The name of the editor's directory in the config directory.
+    const fn config_dir_name(self) -> &'static str {
+        match self {
+            ImportSource::VsCode => "Code",
+            ImportSource::VsCodeInsiders => "Code - Insiders",
+            ImportSource::VsCodium => "VSCodium",
+            ImportSource::JetBrains => "JetBrains",
+        }
+    }
+}
+
+/// A project that was found in an editor's recently opened projects.
+#[derive(Debug, Clone)]
+pub struct ImportedRecent {
+    pub source: ImportSource,
+    pub entry: RecentEntry,
+}
+
+/// Decodes `%XX` escapes.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Converts a `file://` URI to a path. Returns `None` for other schemes, such as remote folders.
+pub fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = percent_decode(uri.strip_prefix("file://")?);
+    // `file:///c:/Users` on Windows.
+    let is_drive = |path: &str| path.len() >= 3 && path.as_bytes()[0] == b'/' && path.as_bytes()[2] == b':';
+    if cfg!(target_os = "windows") && is_drive(&path) {
+        return Some(PathBuf::from(&path[1..]));
+    }
+    Some(PathBuf::from(path))
+}
+
+/// Collects the folder URIs in a VS Code JSON value.
+fn collect_folder_uris(value: &serde_json::Value, uris: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("folderUri" | "folder", serde_json::Value::String(uri)) => uris.push(uri.clone()),
+                    // `{ "file:///path": "__default__profile__" }`
+                    ("workspaces", serde_json::Value::Object(workspaces)) => uris.extend(workspaces.keys().cloned()),
+                    _ => collect_folder_uris(value, uris),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter().for_each(|value| collect_folder_uris(value, uris)),
+        _ => (),
+    }
+}
+
+fn uris_to_paths(uris: &[String]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for path in uris.iter().filter_map(|uri| file_uri_to_path(uri)) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Reads the folders from VS Code's `storage.json`, most recent first.
+///
+/// Recently opened folders are stored under `openedPathsList` in older versions. Newer versions keep the
+/// folders of open and recent windows in `windowsState`, `backupWorkspaces`, and `profileAssociations`.
+pub fn parse_vscode_storage(json: &str) -> Result<Vec<PathBuf>, ImportError> {
+    let storage: serde_json::Value = serde_json::from_str(json)?;
+    let mut uris = Vec::new();
+    // The recently opened list is in order of recency, so it goes first.
+    if let Some(opened) = storage.get("openedPathsList") {
+        collect_folder_uris(opened, &mut uris);
+    }
+    collect_folder_uris(&storage, &mut uris);
+    Ok(uris_to_paths(&uris))
+}
+
+/// Reads the folders from the `history.recentlyOpenedPathsList` value of VS Code's `state.vscdb`, most recent first.
+pub fn parse_vscode_recently_opened(json: &str) -> Result<Vec<PathBuf>, ImportError> {
+    let list: serde_json::Value = serde_json::from_str(json)?;
+    let mut uris = Vec::new();
+    collect_folder_uris(&list, &mut uris);
+    Ok(uris_to_paths(&uris))
+}
+
+/// Reads the recently opened list from VS Code's state database.
+fn read_vscode_state(path: &Path) -> Result<Vec<PathBuf>, ImportError> {
+    use rusqlite::{types::ValueRef, OpenFlags, OptionalExtension};
+    let connection = rusqlite::Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)?;
+    let value = connection
+        .query_row("SELECT value FROM ItemTable WHERE key = 'history.recentlyOpenedPathsList'", [], |row| {
+            Ok(match row.get_ref(0)? {
+                ValueRef::Text(bytes) | ValueRef::Blob(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+                _ => String::new(),
+            })
+        })
+        .optional()?;
+    match value {
+        Some(json) if !json.is_empty() => parse_vscode_recently_opened(&json),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// A project path and the time that it was last opened, if it is recorded.
+pub type OpenedProject = (PathBuf, Option<DateTime<Utc>>);
+
+/// Resolves the path macros of JetBrains config files. Returns `None` for macros that can't be resolved.
+fn expand_jetbrains_path(path: &str, home: &Path) -> Option<PathBuf> {
+    let path = match path.strip_prefix("$USER_HOME$") {
+        Some(rest) => format!("{}{rest}", home.display()),
+        None => path.to_owned(),
+    };
+    (!path.contains('$')).then(|| PathBuf::from(path))
+}
+
+/// Reads the projects of a JetBrains `recentProjects.xml`, with the time that they were last opened where it is recorded.
+///
+/// Current versions list the projects in the `additionalInfo` map with an `activationTimestamp` in milliseconds.
+/// Older versions only have a `recentPaths` list.
+pub fn parse_jetbrains_recent_projects(xml: &str, home: &Path) -> Result<Vec<OpenedProject>, ImportError> {
+    let document = roxmltree::Document::parse(xml)?;
+    let option_named = |node: &roxmltree::Node, name: &str| node.has_tag_name("option") && node.attribute("name") == Some(name);
+    let mut projects: Vec<OpenedProject> = Vec::new();
+    for option in document.descendants().filter(|node| option_named(node, "additionalInfo")) {
+        for entry in option.descendants().filter(|node| node.has_tag_name("entry")) {
+            let Some(path) = entry.attribute("key").and_then(|key| expand_jetbrains_path(key, home)) else {
+                continue;
+            };
+            let timestamp = ["activationTimestamp", "projectOpenTimestamp"].iter()
+                .find_map(|name| entry.descendants().find(|node| option_named(node, name)))
+                .and_then(|node| node.attribute("value")?.parse::<i64>().ok())
+                .and_then(DateTime::from_timestamp_millis);
+            projects.push((path, timestamp));
+        }
+    }
+    for option in document.descendants().filter(|node| option_named(node, "recentPaths")) {
+        for path in option.descendants().filter_map(|node| (node.has_tag_name("option") && node.has_attribute("value")).then(|| node.attribute("value")).flatten()) {
+            let Some(path) = expand_jetbrains_path(path, home) else {
+                continue;
+            };
+            if !projects.iter().any(|(other, _)| other == &path) {
+                projects.push((path, None));
+            }
+        }
+    }
+    Ok(projects)
+}
+
+/// The files that an editor's recent projects are read from.
+fn source_files(source: ImportSource) -> Vec<PathBuf> {
+    let Some(dirs) = directories::BaseDirs::new() else {
+        return Vec::new();
+    };
+    let dir = dirs.config_dir().join(source.config_dir_name());
+    match source {
+        ImportSource::JetBrains => {
+            // Every IDE and version has its own directory, such as `RustRover2024.2`.
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                return Vec::new();
+            };
+            entries.flatten()
+                .map(|entry| entry.path().join("options").join("recentProjects.xml"))
+                .filter(|path| path.is_file())
+                .collect()
+        }
+        _ => [
+            dir.join("User").join("globalStorage").join("state.vscdb"),
+            dir.join("User").join("globalStorage").join("storage.json"),
+            dir.join("storage.json"),
+        ].into_iter().filter(|path| path.is_file()).collect(),
+    }
+}
+
+/// Reads the projects of a file. Projects without a timestamp get the time that the file was last modified,
+/// which is when the editor last updated its list.
+fn read_source_file(path: &Path) -> Result<Vec<(PathBuf, DateTime<Utc>)>, ImportError> {
+    let modified = std::fs::metadata(path)?.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now());
+    let projects = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("vscdb") => read_vscode_state(path)?.into_iter().map(|path| (path, None)).collect(),
+        Some("xml") => {
+            let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf()).unwrap_or_default();
+            parse_jetbrains_recent_projects(&std::fs::read_to_string(path)?, &home)?
+        }
+        _ => parse_vscode_storage(&std::fs::read_to_string(path)?)?.into_iter().map(|path| (path, None)).collect::<Vec<_>>(),
+    };
+    Ok(projects.into_iter().map(|(path, time)| (path, time.unwrap_or(modified))).collect())
+}
+
+#[inline]
+pub(crate) fn is_same_project(lhs: &Path, rhs: &Path) -> bool {
+    lhs == rhs || same_file::is_same_file(lhs, rhs).unwrap_or(false)
+}
+
+/// Adds `imported` to `recents`. A project that is already in `recents` keeps the later of the two times.
+fn merge_imported(recents: &mut Vec<ImportedRecent>, imported: ImportedRecent) {
+    match recents.iter_mut().find(|recent| is_same_project(recent.entry.path(), imported.entry.path())) {
+        Some(recent) if recent.entry.last_open_time() < imported.entry.last_open_time() => *recent = imported,
+        Some(_) => (),
+        None => recents.push(imported),
+    }
+}
+
+/// Imports the recently opened projects of an editor. Projects that don't exist are skipped.
+pub fn import_from(source: ImportSource) -> Result<Vec<ImportedRecent>, ImportError> {
+    let mut recents = Vec::new();
+    for file in source_files(source) {
+        for (path, time) in read_source_file(&file)? {
+            if !path.is_dir() {
+                continue;
+            }
+            let project_type = ProjectType::detect(&path).unwrap_or(ProjectType::Other);
+            merge_imported(&mut recents, ImportedRecent {
+                source,
+                entry: RecentEntry::new(ProjectPath::from_type(project_type, path), time),
+            });
+        }
+    }
+    Ok(recents)
+}
+
+/// Imports the recently opened projects of every installed editor, most recent first. A project that was
+/// opened in more than one editor is imported once, with the time that it was last opened.
+pub fn import_recents() -> Vec<ImportedRecent> {
+    let mut recents = Vec::new();
+    for source in ImportSource::ALL {
+        match import_from(source) {
+            Ok(imported) => imported.into_iter().for_each(|imported| merge_imported(&mut recents, imported)),
+            Err(err) => eprintln!("Failed to import recent projects from {}: {err}", source.text()),
+        }
+    }
+    // Stable, so projects from the same list keep their order.
+    recents.sort_by_key(|recent| std::cmp::Reverse(recent.entry.last_open_time()));
+    recents
+}
+
+/// Removes the imported projects that are already in `existing`.
+pub fn without_existing<'a, I: IntoIterator<Item = &'a Path>>(imported: Vec<ImportedRecent>, existing: I) -> Vec<ImportedRecent> {
+    let existing = existing.into_iter().collect::<Vec<_>>();
+    imported.into_iter()
+        .filter(|recent| !existing.iter().any(|path| is_same_project(path, recent.entry.path())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_test() {
+        assert_eq!(percent_decode("my%20project%zz%"), "my project%zz%");
+        assert_eq!(file_uri_to_path("file:///home/user/my%20project"), Some(PathBuf::from("/home/user/my project")));
+        assert_eq!(file_uri_to_path("vscode-remote://ssh-remote%2Bhost/home/user"), None);
+
+        let storage = r#"{
+            "windowsState": {
+                "lastActiveWindow": { "folder": "file:///code/current" },
+                "openedWindows": [{ "folder": "file:///code/other" }]
+            },
+            "openedPathsList": {
+                "entries": [
+                    { "folderUri": "file:///code/recent" },
+                    { "fileUri": "file:///code/file.txt" },
+                    { "folderUri": "file:///code/current" }
+                ]
+            },
+            "profileAssociations": {
+                "workspaces": { "file:///code/associated": "__default__profile__" }
+            }
+        }"#;
+        let mut paths = parse_vscode_storage(storage).unwrap();
+        assert_eq!(paths.drain(..2).collect::<Vec<_>>(), vec![PathBuf::from("/code/recent"), PathBuf::from("/code/current")]);
+        paths.sort();
+        assert_eq!(paths, vec![PathBuf::from("/code/associated"), PathBuf::from("/code/other")]);
+        assert!(parse_vscode_storage("not json").is_err());
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let db = dir.path().join("state.vscdb");
+        let connection = rusqlite::Connection::open(&db).unwrap();
+        connection.execute("CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)", []).unwrap();
+        connection.execute(
+            "INSERT INTO ItemTable (key, value) VALUES ('history.recentlyOpenedPathsList', ?1)",
+            [r#"{"entries":[{"folderUri":"file:///code/b"},{"fileUri":"file:///code/b/main.rs"},{"workspace":{"id":"1","configPath":"file:///code/x.code-workspace"}},{"folderUri":"file:///code/a"}]}"#],
+        ).unwrap();
+        drop(connection);
+        assert_eq!(read_vscode_state(&db).unwrap(), vec![PathBuf::from("/code/b"), PathBuf::from("/code/a")]);
+
+        let xml = r#"<application>
+  <component name="RecentProjectsManager">
+    <option name="additionalInfo">
+      <map>
+        <entry key="$USER_HOME$/code/app">
+          <value>
+            <RecentProjectMetaInfo frameTitle="app">
+              <option name="activationTimestamp" value="1700000000000" />
+              <option name="projectOpenTimestamp" value="1600000000000" />
+            </RecentProjectMetaInfo>
+          </value>
+        </entry>
+        <entry key="$APPLICATION_CONFIG_DIR$/scratches">
+          <value><RecentProjectMetaInfo /></value>
+        </entry>
+      </map>
+    </option>
+    <option name="recentPaths">
+      <list>
+        <option value="$USER_HOME$/code/app" />
+        <option value="/code/old" />
+      </list>
+    </option>
+  </component>
+</application>"#;
+        assert_eq!(parse_jetbrains_recent_projects(xml, Path::new("/home/user")).unwrap(), vec![
+            (PathBuf::from("/home/user/code/app"), DateTime::from_timestamp_millis(1700000000000)),
+            (PathBuf::from("/code/old"), None),
+        ]);
+
+        let project = dir.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        let older = ImportedRecent { source: ImportSource::VsCode, entry: RecentEntry::new(ProjectPath::other(&project), DateTime::from_timestamp(100, 0).unwrap()) };
+        let newer = ImportedRecent { source: ImportSource::JetBrains, entry: RecentEntry::new(ProjectPath::other(project.join(".")), DateTime::from_timestamp(200, 0).unwrap()) };
+        let mut recents = Vec::new();
+        merge_imported(&mut recents, older);
+        merge_imported(&mut recents, newer);
+        assert_eq!(recents.len(), 1);
+        assert_eq!(recents[0].source, ImportSource::JetBrains);
+        assert!(without_existing(recents.clone(), [project.as_path()]).is_empty());
+        assert_eq!(without_existing(recents, [dir.path()]).len(), 1);
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    fs::{File, TryLockError},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use eframe::egui::Context;
+
+use crate::projects::ProjectType;
+
+/// The relative path of the lock file in `<program>/config/` that the running instance holds.
+pub const LOCK_REL_PATH: &str = ".lock";
+/// The flag that the Restart button launches the new instance with. The new instance waits for the lock instead of
+/// forwarding its arguments to the instance that is closing.
+pub const RESTART_FLAG: &str = "--restart";
+/// The relative path of the socket in `<program>/config/` that the running instance listens on. Where there are no
+/// Unix sockets, the instance listens on a loopback port, and this file holds the port and the token that connections
+/// must send first, so that other users and programs can't open projects.
+pub const SOCKET_REL_PATH: &str = ".socket";
+
+#[derive(Debug, thiserror::Error)]
+pub enum InstanceError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Projector is already running, but it couldn't be reached: {0}")]
+    ForwardFailed(std::io::Error),
+}
+
+/// A request that a later launch forwards to the running instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// Show and focus the window.
+    Show,
+    /// Add the project at the path to the recent projects, then show the window. Without a type, the type is
+    /// detected.
+    Open(PathBuf, Option<ProjectType>),
+    /// Remove the project at the path from the recent projects.
+    Remove(PathBuf),
+}
+
+impl Message {
+    /// The messages of the launch arguments. A launch without arguments shows the window, and a launch with
+    /// directories opens them.
+    pub fn from_args(args: &[String]) -> Vec<Message> {
+        if args.is_empty() {
+            return vec![Message::Show];
+        }
+        args.iter()
+            .map(|arg| Message::Open(std::path::absolute(arg).unwrap_or_else(|_| PathBuf::from(arg)), None))
+            .collect()
+    }
+
+    /// Messages are sent one per line. The type of an opened project comes after its path, and is empty when it
+    /// should be detected.
+    fn encode(&self) -> String {
+        match self {
+            Message::Show => String::from("show\n"),
+            Message::Open(path, project_type) => {
+                format!("open\t{}\t{}\n", path.display(), project_type.map_or("", ProjectType::text))
+            }
+            Message::Remove(path) => format!("remove\t{}\n", path.display()),
+        }
+    }
+
+    fn decode(line: &str) -> Option<Self> {
+        match line.split_once('\t') {
+            None if line == "show" => Some(Message::Show),
+            Some(("open", rest)) => {
+                let (path, project_type) = rest.rsplit_once('\t')?;
+                let project_type = match project_type {
+                    "" => None,
+                    text => Some(ProjectType::from_text(text)?),
+                };
+                (!path.is_empty()).then(|| Message::Open(PathBuf::from(path), project_type))
+            }
+            Some(("remove", path)) if !path.is_empty() => Some(Message::Remove(PathBuf::from(path))),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the arguments are meant for the GUI rather than the command line. They're directories to open, or none.
+/// A command name is never a directory, even if the working directory has a subdirectory with that name.
+pub fn is_gui_launch(args: &[String]) -> bool {
+    if args.first().is_some_and(|arg| crate::cli::COMMANDS.contains(&arg.as_str())) {
+        return false;
+    }
+    args.iter().all(|arg| Path::new(arg).is_dir())
+}
+
+/// Takes the lock of the running instance in `config_dir`. Returns `None` if another instance holds it.
+/// The lock is released when the file is dropped.
+pub fn try_lock(config_dir: &Path) -> Result<Option<File>, InstanceError> {
+    let lock = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(config_dir.join(LOCK_REL_PATH))?;
+    match lock.try_lock() {
+        Ok(()) => Ok(Some(lock)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(err)) => Err(err.into()),
+    }
+}
+
+/// Sends `messages` to the instance that holds the lock in `config_dir`.
+#[inline]
+pub fn send(config_dir: &Path, messages: &[Message]) -> Result<(), InstanceError> {
+    forward(&config_dir.join(SOCKET_REL_PATH), messages)
+}
+
+/// The outcome of [Instance::acquire].
+pub enum Acquired {
+    /// No other instance is running. This one holds the lock until it's dropped.
+    Primary(Instance),
+    /// Another instance is running and received the messages.
+    Forwarded,
+}
+
+#[cfg(unix)]
+type Listener = std::os::unix::net::UnixListener;
+#[cfg(not(unix))]
+type Listener = std::net::TcpListener;
+
+#[cfg(unix)]
+type Stream = std::os::unix::net::UnixStream;
+#[cfg(not(unix))]
+type Stream = std::net::TcpStream;
+
+#[cfg(unix)]
+fn bind(socket_path: &Path) -> std::io::Result<(Listener, Option<String>)> {
+    // The lock is held, so a socket that's left over is from an instance that exited without removing it.
+    _ = std::fs::remove_file(socket_path);
+    Ok((Listener::bind(socket_path)?, None))
+}
+
+#[cfg(not(unix))]
+fn bind(socket_path: &Path) -> std::io::Result<(Listener, Option<String>)> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(std::io::Error::other)?;
+    let token = bytes.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    let listener = Listener::bind((std::net::Ipv4Addr::LOCALHOST, 0))?;
+    std::fs::write(socket_path, format!("{}\n{token}", listener.local_addr()?.port()))?;
+    Ok((listener, Some(token)))
+}
+
+#[cfg(unix)]
+fn connect(socket_path: &Path) -> std::io::Result<Stream> {
+    Stream::connect(socket_path)
+}
+
+#[cfg(not(unix))]
+fn connect(socket_path: &Path) -> std::io::Result<Stream> {
+    let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "The socket file is malformed.");
+    let contents = std::fs::read_to_string(socket_path)?;
+    let (port, token) = contents.trim().split_once('\n').ok_or_else(invalid)?;
+    let port = port.trim().parse::<u16>().map_err(|_| invalid())?;
+    let mut stream = Stream::connect((std::net::Ipv4Addr::LOCALHOST, port))?;
+    stream.write_all(format!("{}\n", token.trim()).as_bytes())?;
+    Ok(stream)
+}
+
+/// The lock of the running instance and the socket that later launches forward their arguments to.
+pub struct Instance {
+    _lock: File,
+    listener: Listener,
+    /// The first line that connections must send, if the socket can be reached by other users.
+    token: Option<String>,
+    socket_path: PathBuf,
+}
+
+impl Instance {
+    /// Takes the lock in `config_dir`. If another instance holds it, `messages` are forwarded to that instance.
+    pub fn acquire(config_dir: &Path, messages: &[Message]) -> Result<Acquired, InstanceError> {
+        let Some(lock) = try_lock(config_dir)? else {
+            send(config_dir, messages)?;
+            return Ok(Acquired::Forwarded);
+        };
+        Self::with_lock(config_dir, lock).map(Acquired::Primary)
+    }
+
+    /// Waits until the instance that holds the lock in `config_dir` exits, then takes the lock.
+    pub fn acquire_after_restart(config_dir: &Path) -> Result<Instance, InstanceError> {
+        let lock = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(config_dir.join(LOCK_REL_PATH))?;
+        lock.lock()?;
+        Self::with_lock(config_dir, lock)
+    }
+
+    fn with_lock(config_dir: &Path, lock: File) -> Result<Instance, InstanceError> {
+        let socket_path = config_dir.join(SOCKET_REL_PATH);
+        let (listener, token) = bind(&socket_path)?;
+        Ok(Instance {
+            _lock: lock,
+            listener,
+            token,
+            socket_path,
+        })
+    }
+
+    /// Receives forwarded messages on a background thread. `initial` are received first.
+    pub fn listen(self, ctx: Context, initial: Vec<Message>) -> InstanceListener {
+        let (sender, receiver) = mpsc::channel();
+        for message in initial {
+            _ = sender.send(message);
+        }
+        match self.listener.try_clone() {
+            Ok(listener) => {
+                let token = self.token.clone();
+                std::thread::spawn(move || {
+                    for stream in listener.incoming() {
+                        let Ok(stream) = stream else {
+                            continue;
+                        };
+                        let mut lines = BufReader::new(stream).lines();
+                        if let Some(token) = &token {
+                            if !lines.next().is_some_and(|line| line.is_ok_and(|line| &line == token)) {
+                                continue;
+                            }
+                        }
+                        for line in lines {
+                            let Ok(line) = line else {
+                                break;
+                            };
+                            if let Some(message) = Message::decode(&line) {
+                                if sender.send(message).is_err() {
+                                    return;
+                                }
+                                ctx.request_repaint();
+                            }
+                        }
+                    }
+                });
+            }
+            Err(err) => eprintln!("Failed to listen for other launches of Projector: {err}"),
+        }
+        InstanceListener {
+            _instance: self,
+            receiver,
+        }
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+/// The messages that later launches forwarded to the running instance.
+pub struct InstanceListener {
+    _instance: Instance,
+    receiver: mpsc::Receiver<Message>,
+}
+
+impl InstanceListener {
+    /// The next message that was received since the last call.
+    pub fn poll(&self) -> Option<Message> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+fn forward(socket_path: &Path, messages: &[Message]) -> Result<(), InstanceError> {
+    // The running instance may have taken the lock but not bound the socket yet.
+    let mut attempts = 0;
+    let mut stream = loop {
+        match connect(socket_path) {
+            Ok(stream) => break stream,
+            Err(_) if attempts < 10 => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(err) => return Err(InstanceError::ForwardFailed(err)),
+        }
+    };
+    for message in messages {
+        stream.write_all(message.encode().as_bytes()).map_err(InstanceError::ForwardFailed)?;
+    }
+    stream.flush().map_err(InstanceError::ForwardFailed)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_test() {
+        let messages = [
+            Message::Show,
+            Message::Open(PathBuf::from("/home/user/my project"), None),
+            Message::Open(PathBuf::from("/home/user/tab\tproject"), Some(ProjectType::Python)),
+            Message::Remove(PathBuf::from("/home/user/my project")),
+        ];
+        for message in messages {
+            assert_eq!(Message::decode(message.encode().strip_suffix('\n').unwrap()), Some(message));
+        }
+        assert_eq!(Message::decode("open\t"), None);
+        assert_eq!(Message::decode("open\t\t"), None);
+        assert_eq!(Message::decode("open\t/home/user/project\tcobol"), None);
+        assert_eq!(Message::decode("remove\t"), None);
+        assert_eq!(Message::decode("quit"), None);
+        assert_eq!(Message::from_args(&[]), vec![Message::Show]);
+    }
+
+    #[test]
+    fn is_gui_launch_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let scan = dir.path().join("scan");
+        std::fs::create_dir(&scan).expect("Failed to create directory.");
+        assert!(is_gui_launch(&[]));
+        assert!(is_gui_launch(&[scan.display().to_string()]));
+        assert!(!is_gui_launch(&[dir.path().display().to_string(), String::from("not a directory")]));
+        // Command names are checked before directories.
+        assert!(!is_gui_launch(&[String::from("scan")]));
+    }
+
+    #[test]
+    fn forward_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let Ok(Acquired::Primary(instance)) = Instance::acquire(dir.path(), &[]) else {
+            panic!("The first instance should hold the lock.");
+        };
+        let ctx = Context::default();
+        let listener = instance.listen(ctx, vec![Message::Show]);
+        let open = Message::Open(dir.path().to_path_buf(), None);
+        assert!(matches!(Instance::acquire(dir.path(), std::slice::from_ref(&open)), Ok(Acquired::Forwarded)));
+        assert_eq!(listener.poll(), Some(Message::Show));
+        let mut received = None;
+        for _ in 0..50 {
+            received = listener.poll();
+            if received.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(received, Some(open));
+        drop(listener);
+        assert!(!dir.path().join(SOCKET_REL_PATH).exists());
+        assert!(matches!(Instance::acquire(dir.path(), &[]), Ok(Acquired::Primary(_))));
+    }
+
+    #[test]
+    fn restart_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let Ok(Acquired::Primary(instance)) = Instance::acquire(dir.path(), &[]) else {
+            panic!("The first instance should hold the lock.");
+        };
+        let listener = instance.listen(Context::default(), Vec::new());
+        let config_dir = dir.path().to_path_buf();
+        let restarted = std::thread::spawn(move || Instance::acquire_after_restart(&config_dir));
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!restarted.is_finished(), "The new instance should wait until the old one exits.");
+        drop(listener);
+        let restarted = restarted.join().expect("Failed to join thread.").expect("The new instance should take the lock.");
+        let listener = restarted.listen(Context::default(), Vec::new());
+        let open = Message::Open(dir.path().to_path_buf(), None);
+        assert!(matches!(Instance::acquire(dir.path(), std::slice::from_ref(&open)), Ok(Acquired::Forwarded)));
+        let mut received = None;
+        for _ in 0..50 {
+            received = listener.poll();
+            if received.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(received, Some(open));
+    }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn token_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let Ok(Acquired::Primary(instance)) = Instance::acquire(dir.path(), &[]) else {
+            panic!("The first instance should hold the lock.");
+        };
+        let listener = instance.listen(Context::default(), Vec::new());
+        let contents = std::fs::read_to_string(dir.path().join(SOCKET_REL_PATH)).expect("Failed to read the socket file.");
+        let port = contents.lines().next().and_then(|port| port.parse::<u16>().ok()).expect("The socket file should start with the port.");
+        // A connection that doesn't send the token is ignored.
+        let mut stream = Stream::connect((std::net::Ipv4Addr::LOCALHOST, port)).expect("Failed to connect.");
+        stream.write_all(b"wrong\nshow\n").expect("Failed to write.");
+        drop(stream);
+        let open = Message::Open(dir.path().to_path_buf(), None);
+        assert!(matches!(Instance::acquire(dir.path(), std::slice::from_ref(&open)), Ok(Acquired::Forwarded)));
+        let mut received = None;
+        for _ in 0..50 {
+            received = listener.poll();
+            if received.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(received, Some(open));
+    }
+}
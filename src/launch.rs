@@ -0,0 +1,329 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+    sync::mpsc,
+};
+
+use eframe::egui::Context;
+
+use crate::{
+    appdata::AppConfig,
+    settings::{General, TerminalMode},
+    tmux::{prepare_terminal, TmuxError},
+    util::execute::{program_command, quote_path, quote_url, shell_command},
+    venv::{detect_venv, placeholders, Venv},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LaunchError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+    #[error("Invalid command template: {0}")]
+    InvalidTemplate(String),
+    #[error("`{command}` failed with an exit status of {}", code.map_or_else(|| String::from("(terminated)"), |code| code.to_string()))]
+    Failed {
+        command: String,
+        code: Option<i32>,
+    },
+    #[error("The working directory does not exist: {0}")]
+    MissingDirectory(PathBuf),
+    #[error("tmux Error: {0}")]
+    TmuxError(#[from] TmuxError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub enum StepAction {
+    /// Runs the editor command from Settings > General.
+    OpenEditor,
+    /// Runs the terminal command from Settings > General in the step's working directory.
+    OpenTerminal,
+    RevealInFileExplorer,
+    /// Opens a URL with the browser command from Settings > General.
+    OpenUrl(String),
+    /// A shell command.
+    Command(String),
+}
+
+impl StepAction {
+    pub const fn text(&self) -> &'static str {
+        match self {
+            StepAction::OpenEditor => "Open Editor",
+            StepAction::OpenTerminal => "Open Terminal",
+            StepAction::RevealInFileExplorer => "Reveal in File Explorer",
+            StepAction::OpenUrl(_) => "Open URL",
+            StepAction::Command(_) => "Run Command",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub enum OnFailure {
+    /// Later steps are not run.
+    #[default]
+    Stop,
+    Continue,
+}
+
+impl OnFailure {
+    pub const fn text(self) -> &'static str {
+        match self {
+            OnFailure::Stop => "Stop",
+            OnFailure::Continue => "Continue",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct LaunchStep {
+    pub action: StepAction,
+    /// Added to the environment of the step's process.
+    pub env: Vec<(String, String)>,
+    /// Relative to the project directory. Empty for the project directory.
+    pub working_dir: String,
+    /// Wait for the step to exit before starting the next step. A step can only fail if it's waited on.
+    pub wait: bool,
+    pub on_failure: OnFailure,
+}
+
+impl LaunchStep {
+    pub fn new(action: StepAction) -> Self {
+        Self {
+            action,
+            env: Vec::new(),
+            working_dir: String::new(),
+            wait: false,
+            on_failure: OnFailure::Stop,
+        }
+    }
+
+    /// The directory that the step runs in.
+    #[inline]
+    pub fn working_dir(&self, project: &Path) -> PathBuf {
+        project.join(self.working_dir.trim())
+    }
+
+    /// The shell command that the step runs. `venv` fills the `{venv}` and `{python}` placeholders.
+    pub fn command(&self, general: &General, project: &Path, venv: Option<&Venv>) -> Result<String, LaunchError> {
+        use strfmt::strfmt;
+        let working_dir = self.working_dir(project);
+        let (venv_str, python_str) = placeholders(venv);
+        let (template, result) = match &self.action {
+            StepAction::OpenEditor => (&general.editor_command, strfmt!(&general.editor_command, path => quote_path(project), venv => venv_str, python => python_str)),
+            StepAction::OpenTerminal => (&general.shell_command, strfmt!(&general.shell_command, path => quote_path(&working_dir), venv => venv_str, python => python_str)),
+            StepAction::RevealInFileExplorer => (&general.explorer_command, strfmt!(&general.explorer_command, path => quote_path(&working_dir))),
+            StepAction::OpenUrl(url) => (&general.browser_command, strfmt!(&general.browser_command, url => quote_url(url))),
+            StepAction::Command(command) => return Ok(command.clone()),
+        };
+        result.map_err(|_| LaunchError::InvalidTemplate(template.clone()))
+    }
+}
+
+/// A named, ordered list of steps that is run for a project.
+#[derive(Debug, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct LaunchConfig {
+    pub name: String,
+    pub steps: Vec<LaunchStep>,
+}
+
+/// A step that failed while running a [LaunchConfig].
+#[derive(Debug)]
+pub struct StepFailure {
+    /// The index of the step.
+    pub step: usize,
+    pub error: LaunchError,
+}
+
+fn run_step(general: &General, project: &Path, venv: Option<&Venv>, env: &[(String, String)], step: &LaunchStep) -> Result<(), LaunchError> {
+    let working_dir = step.working_dir(project);
+    if !working_dir.is_dir() {
+        return Err(LaunchError::MissingDirectory(working_dir));
+    }
+    let command_text = match (&step.action, general.terminal_mode) {
+        // The session's panes get the environment that the terminal would have had.
+        (StepAction::OpenTerminal, TerminalMode::Tmux) => {
+            let mut session_env = venv.map(Venv::activation_env).unwrap_or_default();
+            session_env.extend(env.iter().chain(step.env.iter()).cloned());
+            prepare_terminal(general, &working_dir, &session_env)?
+        }
+        _ => step.command(general, project, venv)?,
+    };
+    // URLs can contain characters that the shell interprets, so the browser isn't started through it.
+    let command = match step.action {
+        StepAction::OpenUrl(_) => program_command(&command_text),
+        _ => shell_command(&command_text),
+    };
+    let mut command: Command = command.ok_or_else(|| LaunchError::InvalidCommand(command_text.clone()))?;
+    command.current_dir(&working_dir);
+    // Terminals start with the virtual environment activated.
+    if let (StepAction::OpenTerminal, Some(venv)) = (&step.action, venv) {
+        command.envs(venv.activation_env());
+    }
+    // Step variables override the project's variables.
+    command.envs(env.iter().chain(step.env.iter()).map(|(key, value)| (key, value)));
+    if step.wait {
+        let status: ExitStatus = command.status()?;
+        if !status.success() {
+            return Err(LaunchError::Failed { command: command_text, code: status.code() });
+        }
+    } else {
+        let mut child = command.spawn()?;
+        // Reap the process when it exits.
+        std::thread::spawn(move || child.wait());
+    }
+    Ok(())
+}
+
+/// Runs the steps of `config` in order. Blocks while waiting on steps.
+/// `env` is the project's environment (see [crate::env::ProjectEnv]).
+pub fn run_launch(general: &General, project: &Path, env: &[(String, String)], config: &LaunchConfig) -> Vec<StepFailure> {
+    let mut failures = Vec::new();
+    let venv = detect_venv(project);
+    for (index, step) in config.steps.iter().enumerate() {
+        if let Err(error) = run_step(general, project, venv.as_ref(), env, step) {
+            failures.push(StepFailure { step: index, error });
+            if step.on_failure == OnFailure::Stop {
+                break;
+            }
+        }
+    }
+    failures
+}
+
+/// Describes the failures of a launch for the user.
+pub fn failure_report(config: &LaunchConfig, failures: &[StepFailure]) -> String {
+    let mut report = format!("Launch configuration `{}` failed:", config.name);
+    for failure in failures {
+        let action = config.steps.get(failure.step).map_or("", |step| step.action.text());
+        report.push_str(&format!("\nStep {} ({action}): {}", failure.step + 1, failure.error));
+    }
+    report
+}
+
+/// The launch configurations of every project, stored in `<program>/config/`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct LaunchConfigs {
+    configs: HashMap<PathBuf, Vec<LaunchConfig>>,
+}
+
+impl LaunchConfigs {
+    /// The relative path of the launch configurations in `<program>/config/`
+    pub const CONFIG_REL_PATH: &str = "launch_configs";
+
+    pub fn load(config: &AppConfig) -> crate::error::Result<Self> {
+        config.load_or_default(Self::CONFIG_REL_PATH)
+    }
+
+    pub fn save(&self, config: &AppConfig) -> crate::error::Result<()> {
+        config.save(Self::CONFIG_REL_PATH, self)
+    }
+
+    pub fn get<P: AsRef<Path>>(&self, project: P) -> &[LaunchConfig] {
+        self.configs.get(project.as_ref()).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn set<P: Into<PathBuf>>(&mut self, project: P, configs: Vec<LaunchConfig>) {
+        let project = project.into();
+        if configs.is_empty() {
+            self.configs.remove(&project);
+        } else {
+            self.configs.insert(project, configs);
+        }
+    }
+}
+
+/// Runs launch configurations on background threads so that steps that are waited on don't block the UI.
+pub struct Launcher {
+    ctx: Context,
+    sender: mpsc::Sender<String>,
+    receiver: mpsc::Receiver<String>,
+}
+
+impl Launcher {
+    pub fn new(ctx: Context) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self { ctx, sender, receiver }
+    }
+
+    pub fn launch(&self, general: &General, project: &Path, env: &[(String, String)], config: &LaunchConfig) {
+        let general = general.clone();
+        let env = env.to_vec();
+        let project = project.to_owned();
+        let config = config.clone();
+        let sender = self.sender.clone();
+        let ctx = self.ctx.clone();
+        std::thread::spawn(move || {
+            let failures = run_launch(&general, &project, &env, &config);
+            if !failures.is_empty() {
+                _ = sender.send(failure_report(&config, &failures));
+                ctx.request_repaint();
+            }
+        });
+    }
+
+    /// The report of a launch that failed since the last call.
+    pub fn poll_failure(&self) -> Option<String> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn run_launch_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let general = General::default();
+        let step = |command: &str, on_failure: OnFailure| LaunchStep {
+            action: StepAction::Command(command.to_owned()),
+            env: vec![(String::from("LAUNCH_VALUE"), String::from("hello"))],
+            working_dir: String::from("sub"),
+            wait: true,
+            on_failure,
+        };
+        let config = LaunchConfig {
+            name: String::from("test"),
+            steps: vec![
+                step("echo $LAUNCH_VALUE $PROJECT_VALUE > out.txt", OnFailure::Stop),
+                step("exit 3", OnFailure::Continue),
+                step("echo next >> out.txt", OnFailure::Stop),
+                step("exit 4", OnFailure::Stop),
+                step("echo unreachable >> out.txt", OnFailure::Stop),
+            ],
+        };
+        let failures = run_launch(&general, dir.path(), &[(String::from("PROJECT_VALUE"), String::from("project"))], &config);
+        assert_eq!(failures.iter().map(|failure| failure.step).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(matches!(failures[0].error, LaunchError::Failed { code: Some(3), .. }));
+        assert_eq!(std::fs::read_to_string(dir.path().join("sub").join("out.txt")).unwrap(), "hello project\nnext\n");
+
+        let mut configs = LaunchConfigs::default();
+        configs.set(dir.path(), vec![config.clone()]);
+        assert_eq!(configs.get(dir.path()), &[config]);
+        configs.set(dir.path(), Vec::new());
+        assert!(configs.get(dir.path()).is_empty());
+
+        // A missing file is empty, but a file that can't be read is an error rather than empty configurations.
+        let config_dir = AppConfig::new(dir.path());
+        assert_eq!(LaunchConfigs::load(&config_dir).unwrap(), LaunchConfigs::default());
+        std::fs::write(config_dir.relative_path(LaunchConfigs::CONFIG_REL_PATH), [0xff; 8]).unwrap();
+        assert!(LaunchConfigs::load(&config_dir).is_err());
+    }
+
+    #[test]
+    fn open_url_command_test() {
+        let general = General {
+            browser_command: String::from("xdg-open {url}"),
+            ..General::default()
+        };
+        let step = LaunchStep::new(StepAction::OpenUrl(String::from(r#"https://example.com/"$(touch pwned)`id`\"#)));
+        assert_eq!(
+            step.command(&general, Path::new("."), None).unwrap(),
+            r#"xdg-open "https://example.com/%22%24(touch%20pwned)%60id%60%5C""#,
+        );
+    }
+}
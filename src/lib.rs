@@ -11,6 +11,26 @@ pub mod util;
 pub mod fp;
 pub mod project_wizard;
 pub mod charcons;
+pub mod license_headers;
+pub mod scaffold;
+pub mod templates;
+pub mod validation;
+pub mod git;
+pub mod git_status;
+pub mod git_remote;
+pub mod cargo_workspace;
+pub mod tasks;
+pub mod launch;
+pub mod cli;
+pub mod env;
+pub mod venv;
+pub mod toolchain;
+pub mod tmux;
+pub mod tools;
+pub mod import;
+pub mod fuzzy;
+pub mod shell;
+pub mod instance;
 
 pub const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 pub const IS_DEBUG: bool = cfg!(debug_assertions);
\ No newline at end of file
@@ -0,0 +1,397 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Datelike;
+
+/// Every header is expected to contain this tag. It is how existing headers are found so that they can be updated.
+pub const SPDX_TAG: &str = "SPDX-License-Identifier:";
+
+#[derive(Debug, thiserror::Error)]
+pub enum HeaderError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Format Error: {0}")]
+    FormatError(#[from] strfmt::FmtError),
+    #[error("Walk Error: {0}")]
+    WalkError(#[from] ignore::Error),
+    #[error("Header template must contain `{SPDX_TAG}`")]
+    MissingTag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommentSyntax {
+    Line(&'static str),
+    Block {
+        open: &'static str,
+        prefix: &'static str,
+        close: &'static str,
+    },
+}
+
+impl CommentSyntax {
+    const C_BLOCK: Self = Self::Block { open: "/*", prefix: " * ", close: " */" };
+    const MARKUP_BLOCK: Self = Self::Block { open: "<!--", prefix: "  ", close: "-->" };
+    const PHP_BLOCK: Self = Self::Block { open: "<?php /*", prefix: " * ", close: " */ ?>" };
+    const SERVER_PAGE_BLOCK: Self = Self::Block { open: "<%--", prefix: "  ", close: "--%>" };
+
+    /// Returns the comment syntax for a file extension, or `None` if the format has no comments (`json`) or isn't text (`wasm`).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        Some(match ext.to_ascii_lowercase().as_str() {
+            "rs" | "js" | "mjs" | "cjs" | "ts" | "jsx" | "tsx" | "sass" | "c" | "h" | "cpp" | "hpp" | "cs" | "java" | "go" => Self::Line("//"),
+            "py" | "pyw" | "pyi" | "yaml" | "yml" | "toml" | "env" | "cgi" | "sh" | "rb" | "pl" => Self::Line("#"),
+            "css" | "scss" | "less" => Self::C_BLOCK,
+            "html" | "htm" | "xhtml" | "xml" | "asp" | "svg" | "vue" => Self::MARKUP_BLOCK,
+            "php" => Self::PHP_BLOCK,
+            "aspx" | "jsp" => Self::SERVER_PAGE_BLOCK,
+            _ => return None,
+        })
+    }
+
+    #[inline]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref().extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .and_then(Self::from_extension)
+    }
+
+    /// Wraps each line of `text` in this comment syntax.
+    pub fn render(self, text: &str, newline: &str) -> String {
+        let mut out = String::new();
+        match self {
+            CommentSyntax::Line(prefix) => {
+                for line in text.lines() {
+                    out.push_str(prefix);
+                    if !line.is_empty() {
+                        out.push(' ');
+                        out.push_str(line);
+                    }
+                    out.push_str(newline);
+                }
+            }
+            CommentSyntax::Block { open, prefix, close } => {
+                out.push_str(open);
+                out.push_str(newline);
+                for line in text.lines() {
+                    out.push_str(prefix.trim_end());
+                    if !line.is_empty() {
+                        out.push_str(&prefix[prefix.trim_end().len()..]);
+                        out.push_str(line);
+                    }
+                    out.push_str(newline);
+                }
+                out.push_str(close);
+                out.push_str(newline);
+            }
+        }
+        out
+    }
+}
+
+/// Settings used to produce the header text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderTemplate {
+    pub template: String,
+    pub spdx: String,
+    pub author: String,
+}
+
+impl HeaderTemplate {
+    /// Formats the template. Supported placeholders are `{spdx}`, `{author}`, and `{year}`.
+    pub fn format(&self) -> Result<String, HeaderError> {
+        let spdx = self.spdx.clone();
+        let author = self.author.clone();
+        let year = chrono::Local::now().year().to_string();
+        use strfmt::strfmt;
+        let text = strfmt!(&self.template, spdx => spdx, author => author, year => year)?;
+        if !text.contains(SPDX_TAG) {
+            return Err(HeaderError::MissingTag);
+        }
+        Ok(text)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+}
+
+/// A pending change to a single file. Nothing is written until [HeaderChange::write] is called.
+#[derive(Debug, Clone)]
+pub struct HeaderChange {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+    /// The 1-based line the change starts on.
+    pub line: usize,
+    pub removed: String,
+    pub inserted: String,
+    /// The content of the file when the change was planned.
+    pub original: String,
+    pub content: String,
+}
+
+impl HeaderChange {
+    /// A unified-diff style preview of the change.
+    pub fn diff(&self) -> String {
+        let removed_count = self.removed.lines().count();
+        let inserted_count = self.inserted.lines().count();
+        let mut out = format!("@@ -{},{} +{},{} @@\n", self.line, removed_count, self.line, inserted_count);
+        for line in self.removed.lines() {
+            out.push('-');
+            out.push_str(line);
+            out.push('\n');
+        }
+        for line in self.inserted.lines() {
+            out.push('+');
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Writes the change. Files that were edited since the change was planned are left alone.
+    pub fn write(&self) -> std::io::Result<()> {
+        if std::fs::read_to_string(&self.path)? != self.original {
+            return Err(std::io::Error::other("The file changed since the preview. Preview the headers again."));
+        }
+        std::fs::write(&self.path, &self.content)
+    }
+}
+
+/// Returns true if the line must stay above the header (shebangs, encoding cookies, xml declarations).
+/// Rust inner attributes (`#![...]`) aren't shebangs.
+fn is_preamble(line: &str, index: usize) -> bool {
+    let trimmed = line.trim_start();
+    (index == 0 && (trimmed.starts_with("#!/") || trimmed.starts_with("#! ") || trimmed.starts_with("<?xml")))
+        || (index < 2 && trimmed.starts_with('#') && trimmed.contains("coding") && (trimmed.contains("coding:") || trimmed.contains("coding=")))
+}
+
+/// Finds the byte range of an existing header comment that begins at `start`. A line comment header ends at the
+/// first blank or non-comment line, which is how headers are inserted.
+fn find_existing(content: &str, start: usize, syntax: CommentSyntax) -> Option<std::ops::Range<usize>> {
+    let mut end = start;
+    let mut lines = content[start..].split_inclusive('\n');
+    match syntax {
+        CommentSyntax::Line(prefix) => {
+            for line in lines {
+                // Doc comments such as `//!` and `///` aren't part of the header.
+                let Some(rest) = line.trim_start().strip_prefix(prefix) else {
+                    break;
+                };
+                if !rest.is_empty() && !rest.starts_with([' ', '\t', '\r', '\n']) {
+                    break;
+                }
+                end += line.len();
+            }
+        }
+        CommentSyntax::Block { open, close, .. } => {
+            let first = lines.next()?;
+            if first.trim() != open {
+                return None;
+            }
+            end += first.len();
+            let mut closed = false;
+            for line in lines {
+                end += line.len();
+                if line.trim() == close.trim() {
+                    closed = true;
+                    break;
+                }
+            }
+            if !closed {
+                return None;
+            }
+        }
+    }
+    if content[start..end].contains(SPDX_TAG) {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+/// Inserts or updates the header in `content`. Returns `None` if the header is already up to date.
+pub fn apply_header(path: &Path, content: &str, header: &str, syntax: CommentSyntax) -> Option<HeaderChange> {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let rendered = syntax.render(header, newline);
+    let mut start = 0;
+    let mut line = 1;
+    for (index, text) in content.split_inclusive('\n').enumerate() {
+        if !is_preamble(text, index) {
+            break;
+        }
+        start += text.len();
+        line += 1;
+    }
+    let (kind, range, inserted) = if let Some(range) = find_existing(content, start, syntax) {
+        (ChangeKind::Update, range, rendered)
+    } else {
+        let mut inserted = rendered;
+        if start < content.len() {
+            inserted.push_str(newline);
+        }
+        (ChangeKind::Insert, start..start, inserted)
+    };
+    let removed = &content[range.clone()];
+    if removed == inserted {
+        return None;
+    }
+    let mut new_content = String::with_capacity(content.len() + inserted.len());
+    new_content.push_str(&content[..range.start]);
+    new_content.push_str(&inserted);
+    new_content.push_str(&content[range.end..]);
+    Some(HeaderChange {
+        path: path.to_owned(),
+        kind,
+        line,
+        removed: removed.to_owned(),
+        inserted,
+        original: content.to_owned(),
+        content: new_content,
+    })
+}
+
+/// The changes that [plan_headers] found.
+#[derive(Debug, Default)]
+pub struct HeaderPlan {
+    pub changes: Vec<HeaderChange>,
+    /// The files that couldn't be read. They're left out of the plan rather than failing it.
+    pub skipped: Vec<(PathBuf, std::io::Error)>,
+}
+
+/// Walks `root` (respecting `.gitignore`, `.ignore`, and hidden files) and collects the changes needed
+/// to bring every file with one of the given `extensions` up to date. This does not write anything.
+pub fn plan_headers<P: AsRef<Path>, S: AsRef<str>>(root: P, extensions: &[S], template: &HeaderTemplate) -> Result<HeaderPlan, HeaderError> {
+    let header = template.format()?;
+    let mut plan = HeaderPlan::default();
+    let walker = ignore::WalkBuilder::new(root.as_ref())
+        .require_git(false)
+        .build();
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().map(|ty| ty.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let path = entry.path();
+        let Some(ext) = path.extension().and_then(std::ffi::OsStr::to_str) else {
+            continue;
+        };
+        if !extensions.iter().any(|include| include.as_ref().eq_ignore_ascii_case(ext)) {
+            continue;
+        }
+        let Some(syntax) = CommentSyntax::from_extension(ext) else {
+            continue;
+        };
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            // Not UTF-8, so not something we should be touching.
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidData => continue,
+            Err(err) => {
+                plan.skipped.push((path.to_owned(), err));
+                continue;
+            }
+        };
+        if let Some(change) = apply_header(path, &content, &header, syntax) {
+            plan.changes.push(change);
+        }
+    }
+    Ok(plan)
+}
+
+/// Writes all changes, returning the paths that failed along with their errors.
+pub fn write_changes(changes: &[HeaderChange]) -> Vec<(PathBuf, std::io::Error)> {
+    changes.iter().filter_map(|change| {
+        change.write().err().map(|err| (change.path.clone(), err))
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "SPDX-License-Identifier: MIT\nCopyright (c) 2025 Test";
+
+    #[test]
+    fn insert_and_update_test() {
+        let path = Path::new("main.rs");
+        let syntax = CommentSyntax::from_path(path).unwrap();
+        let content = "fn main() {}\n";
+        let change = apply_header(path, content, HEADER, syntax).expect("Expected an insertion.");
+        assert_eq!(change.kind, ChangeKind::Insert);
+        assert_eq!(change.content, "// SPDX-License-Identifier: MIT\n// Copyright (c) 2025 Test\n\nfn main() {}\n");
+        assert!(apply_header(path, &change.content, HEADER, syntax).is_none());
+
+        let updated = apply_header(path, &change.content, "SPDX-License-Identifier: Apache-2.0\nCopyright (c) 2025 Test", syntax).expect("Expected an update.");
+        assert_eq!(updated.kind, ChangeKind::Update);
+        assert_eq!(updated.content, "// SPDX-License-Identifier: Apache-2.0\n// Copyright (c) 2025 Test\n\nfn main() {}\n");
+        println!("{}", updated.diff());
+    }
+
+    #[test]
+    fn preamble_test() {
+        let path = Path::new("script.py");
+        let syntax = CommentSyntax::from_path(path).unwrap();
+        let content = "#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\nprint('hi')\n";
+        let change = apply_header(path, content, HEADER, syntax).unwrap();
+        assert_eq!(change.line, 3);
+        assert!(change.content.starts_with("#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\n# SPDX-License-Identifier: MIT\n"));
+
+        let path = Path::new("style.css");
+        let syntax = CommentSyntax::from_path(path).unwrap();
+        let change = apply_header(path, "body {}\r\n", HEADER, syntax).unwrap();
+        assert_eq!(change.content, "/*\r\n * SPDX-License-Identifier: MIT\r\n * Copyright (c) 2025 Test\r\n */\r\n\r\nbody {}\r\n");
+        assert!(apply_header(path, &change.content, HEADER, syntax).is_none());
+        assert!(CommentSyntax::from_extension("json").is_none());
+    }
+
+    #[test]
+    fn keeps_following_comments_test() {
+        let path = Path::new("lib.rs");
+        let syntax = CommentSyntax::from_path(path).unwrap();
+        let content = "#![allow(unused)]\n";
+        let change = apply_header(path, content, HEADER, syntax).unwrap();
+        assert!(change.content.starts_with("// SPDX-License-Identifier: MIT\n"));
+
+        let content = "// SPDX-License-Identifier: MIT\n// Copyright (c) 2024 Test\n//! Crate docs.\n";
+        let change = apply_header(path, content, HEADER, syntax).unwrap();
+        assert_eq!(change.content, "// SPDX-License-Identifier: MIT\n// Copyright (c) 2025 Test\n//! Crate docs.\n");
+        let content = "// SPDX-License-Identifier: MIT\n// Copyright (c) 2024 Test\n\n// Parses things.\nfn main() {}\n";
+        let change = apply_header(path, content, HEADER, syntax).unwrap();
+        assert_eq!(change.content, "// SPDX-License-Identifier: MIT\n// Copyright (c) 2025 Test\n\n// Parses things.\nfn main() {}\n");
+    }
+
+    #[test]
+    fn resized_template_test() {
+        let path = Path::new("main.rs");
+        let syntax = CommentSyntax::from_path(path).unwrap();
+        let content = "// SPDX-License-Identifier: MIT\n// Copyright (c) 2024 Test\n//\n// All rights reserved.\n\n// Parses things.\nfn main() {}\n";
+        let shorter = apply_header(path, content, HEADER, syntax).unwrap();
+        assert_eq!(shorter.content, "// SPDX-License-Identifier: MIT\n// Copyright (c) 2025 Test\n\n// Parses things.\nfn main() {}\n");
+
+        let longer = apply_header(path, &shorter.content, "SPDX-License-Identifier: MIT\nCopyright (c) 2025 Test\nSee LICENSE for details.", syntax).unwrap();
+        assert_eq!(longer.content, "// SPDX-License-Identifier: MIT\n// Copyright (c) 2025 Test\n// See LICENSE for details.\n\n// Parses things.\nfn main() {}\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plan_skips_unreadable_test() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        let unreadable = dir.path().join("b.rs");
+        std::fs::write(&unreadable, "fn b() {}\n").unwrap();
+        std::fs::set_permissions(&unreadable, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // Permissions don't apply to root.
+        if std::fs::read(&unreadable).is_ok() {
+            return;
+        }
+        let template = HeaderTemplate {
+            template: String::from("SPDX-License-Identifier: {spdx}"),
+            spdx: String::from("MIT"),
+            author: String::new(),
+        };
+        let plan = plan_headers(dir.path(), &["rs"], &template).unwrap();
+        assert_eq!(plan.changes.len(), 1);
+        assert_eq!(plan.skipped.len(), 1);
+        assert_eq!(plan.skipped[0].0, unreadable);
+    }
+}
@@ -4,9 +4,67 @@ use eframe::{
     NativeOptions,
     egui::ViewportBuilder,
 };
-use projector::app::*;
+use projector::{
+    app::*,
+    appdata::AppData,
+    instance::{is_gui_launch, Acquired, Instance, InstanceError, Message, RESTART_FLAG},
+};
+
+/// Programs that use the Windows GUI subsystem have no console, so commands attach to the console of the shell
+/// that ran them.
+#[cfg(windows)]
+fn attach_console() {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
 
 fn main() -> Result<(), eframe::Error> {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let restart = args.first().is_some_and(|arg| arg == RESTART_FLAG);
+    if restart {
+        args.remove(0);
+    }
+    if !is_gui_launch(&args) {
+        #[cfg(windows)]
+        attach_console();
+        std::process::exit(projector::cli::run(&args));
+    }
+    // Only one instance runs at a time, so that instances don't overwrite each other's settings. A later launch
+    // forwards its arguments to the running instance.
+    let messages = Message::from_args(&args);
+    let instance = match AppData::from("com", "erisianarchitect", "projector") {
+        Ok(app_data) => {
+            _ = app_data.ensure_dirs();
+            let acquired = if restart {
+                Instance::acquire_after_restart(app_data.config().path()).map(Acquired::Primary)
+            } else {
+                Instance::acquire(app_data.config().path(), &messages)
+            };
+            match acquired {
+                Ok(Acquired::Primary(instance)) => Some(instance),
+                Ok(Acquired::Forwarded) => return Ok(()),
+                // A second window would save its recent projects over the first one's.
+                Err(err @ InstanceError::ForwardFailed(_)) => {
+                    eprintln!("{err}");
+                    return Ok(());
+                }
+                Err(err) => {
+                    eprintln!("Failed to check for a running instance of Projector. {err}");
+                    None
+                }
+            }
+        }
+        Err(err) => {
+            eprintln!("Failed to find the config directory. {err}");
+            None
+        }
+    };
     let title = format!("Projector v{}{}", projector::VERSION, if projector::IS_DEBUG {
         " [DEBUG]"
     } else {
@@ -24,6 +82,16 @@ fn main() -> Result<(), eframe::Error> {
                 .with_title(title),
             ..Default::default()
         },
-        Box::new(|cc| Ok(ProjectorApp::boxed_new(cc)))
+        Box::new(move |cc| {
+            let app = ProjectorApp::boxed_new(cc);
+            Ok(match instance {
+                // The first launch's own directories are opened like forwarded ones.
+                Some(instance) => {
+                    let initial = if args.is_empty() { Vec::new() } else { messages };
+                    app.with_instance(instance.listen(cc.egui_ctx.clone(), initial))
+                }
+                None => app,
+            })
+        })
     )
 }
\ No newline at end of file
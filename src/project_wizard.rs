@@ -1,20 +1,295 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
 use eframe::{
     egui::*,
 };
 
-use crate::{ext::UiExt, settings::{Closer, DialogCloser, Settings}};
+use crate::{ext::UiExt, git::{init_repository, GitError}, projects::{ProjectPath, ProjectType}, scaffold::{ScaffoldError, ScaffoldOptions}, settings::{Closer, Settings}, templates::{Template, TemplateError, DESCRIPTION_VARIABLE, NAME_VARIABLE}, validation::{suggest_names, validate_name, NameError}, widgets::{expand_path, PathInput}};
 
-pub struct ProjectWizard {
+#[derive(Debug, thiserror::Error)]
+pub enum WizardError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("{0}")]
+    ScaffoldError(#[from] ScaffoldError),
+    #[error("{0}")]
+    TemplateError(#[from] TemplateError),
+    #[error("Invalid project name: {0}")]
+    InvalidName(#[from] NameError),
+    #[error("No location was chosen for the project.")]
+    NoLocation,
+    #[error("{0} already exists.")]
+    AlreadyExists(PathBuf),
+}
 
+/// A project that was created by the wizard.
+pub struct CreatedProject {
+    pub project: ProjectPath,
+    /// The project is still created when git initialization fails.
+    pub git_error: Option<GitError>,
+}
+
+pub struct ProjectWizard {
+    name: String,
+    description: String,
+    project_type: ProjectType,
+    location: String,
+    scaffold: ScaffoldOptions,
+    init_git: bool,
+    remote_url: String,
+    templates: Vec<Template>,
+    /// Index into `templates`. `None` creates an empty project.
+    template_index: Option<usize>,
+    /// Values for template variables other than `name` and `description`.
+    variable_values: HashMap<String, String>,
+    error: Option<String>,
 }
 
 impl ProjectWizard {
+    pub fn new(settings: &Settings, project_type: ProjectType, templates: Vec<Template>) -> Self {
+        let location = settings.projects.enabled_project_directories(project_type)
+            .next()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        Self {
+            name: String::new(),
+            description: String::new(),
+            project_type,
+            location,
+            scaffold: ScaffoldOptions::default(),
+            init_git: settings.git.init_repository,
+            remote_url: String::new(),
+            templates,
+            template_index: None,
+            variable_values: HashMap::new(),
+            error: None,
+        }
+    }
+
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn with_location<S: Into<String>>(mut self, location: S) -> Self {
+        self.location = location.into();
+        self
+    }
+
+    pub fn set_project_type(&mut self, project_type: ProjectType) {
+        self.project_type = project_type;
+    }
+
+    pub fn with_init_git(mut self, init_git: bool) -> Self {
+        self.init_git = init_git;
+        self
+    }
+
+    /// Selects the template with the name `name` (case-insensitive). Returns `false` if there is no such template.
+    pub fn select_template_named(&mut self, name: &str) -> bool {
+        let Some(index) = self.templates.iter().position(|template| template.manifest.name.eq_ignore_ascii_case(name)) else {
+            return false;
+        };
+        self.select_template(Some(index));
+        true
+    }
+
+    /// Sets the value of a variable of the selected template.
+    pub fn set_variable<K: Into<String>, V: Into<String>>(&mut self, name: K, value: V) {
+        self.variable_values.insert(name.into(), value.into());
+    }
+
+    #[inline]
+    fn selected_template(&self) -> Option<&Template> {
+        self.template_index.and_then(|index| self.templates.get(index))
+    }
+
+    fn select_template(&mut self, index: Option<usize>) {
+        self.template_index = index;
+        self.variable_values = self.selected_template()
+            .map(Template::default_values)
+            .unwrap_or_default();
+        if let Some(template) = self.selected_template() {
+            self.project_type = template.manifest.project_type;
+        }
+    }
+
+    /// The directory that the project will be created in. `~` and environment variables in the location are expanded.
+    pub fn project_directory(&self) -> PathBuf {
+        let location = expand_path(&self.location).unwrap_or_else(|_| PathBuf::from(&self.location));
+        location.join(self.name.trim())
+    }
+
+    /// Creates the project directory, generates the scaffold files, and initializes the git repository.
+    pub fn create(&self, settings: &Settings) -> Result<CreatedProject, WizardError> {
+        let name = self.name.trim();
+        if let Some(err) = validate_name(name, self.project_type).into_iter().next() {
+            return Err(WizardError::InvalidName(err));
+        }
+        if self.location.trim().is_empty() {
+            return Err(WizardError::NoLocation);
+        }
+        let directory = self.project_directory();
+        if directory.exists() {
+            return Err(WizardError::AlreadyExists(directory));
+        }
+        std::fs::create_dir_all(&directory)?;
+        // A project that couldn't be created is removed, so that creating it again doesn't fail with `AlreadyExists`.
+        if let Err(err) = self.populate(&directory, name, settings) {
+            if let Err(remove_err) = std::fs::remove_dir_all(&directory) {
+                eprintln!("Failed to remove partially created project {}: {remove_err}", directory.display());
+            }
+            return Err(err);
+        }
+        let git_error = if self.init_git {
+            let remote_url = Some(self.remote_url.trim().to_owned()).filter(|url| !url.is_empty());
+            init_repository(&directory, &settings.git.init_options(remote_url)).err()
+        } else {
+            None
+        };
+        Ok(CreatedProject {
+            project: ProjectPath::from_type(self.project_type, directory),
+            git_error,
+        })
+    }
+
+    /// Writes the template and scaffold files into the new project directory.
+    fn populate(&self, directory: &Path, name: &str, settings: &Settings) -> Result<(), WizardError> {
+        if let Some(template) = self.selected_template() {
+            let mut values = self.variable_values.clone();
+            values.insert(String::from(NAME_VARIABLE), name.to_owned());
+            values.insert(String::from(DESCRIPTION_VARIABLE), self.description.trim().to_owned());
+            template.instantiate(directory, &values)?;
+        }
+        // Files that came from the template are not overwritten.
+        crate::scaffold::write_scaffold(
+            directory,
+            self.project_type,
+            &settings.templates,
+            name,
+            self.description.trim(),
+            self.scaffold,
+        )?;
+        Ok(())
+    }
+
+    fn form_ui(&mut self, settings: &Settings, ui: &mut Ui) {
+        const LABEL_WIDTH: f32 = 180.0;
+        ui.setting_ui(LABEL_WIDTH, "Name", "The name of the project. This is also the name of the project directory.", Color32::TRANSPARENT, |ui| {
+            let errors = validate_name(self.name.trim(), self.project_type);
+            let invalid = !self.name.is_empty() && !errors.is_empty();
+            TextEdit::singleline(&mut self.name)
+                .desired_width(ui.available_width())
+                .text_color_opt(invalid.then_some(Color32::RED))
+                .show(ui);
+            if invalid {
+                for error in &errors {
+                    ui.colored_label(Color32::RED, error.to_string());
+                }
+                let suggestions = suggest_names(self.name.trim(), self.project_type);
+                if !suggestions.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label("Try:");
+                        for suggestion in suggestions {
+                            if ui.link(&suggestion).clicked() {
+                                self.name = suggestion;
+                            }
+                        }
+                    });
+                }
+            }
+        });
+        ui.setting_ui(LABEL_WIDTH, "Description", "A short description. This is used to fill in the README.", Color32::TRANSPARENT, |ui| {
+            TextEdit::multiline(&mut self.description)
+                .desired_width(ui.available_width())
+                .desired_rows(3)
+                .show(ui);
+        });
+        ui.setting_ui(LABEL_WIDTH, "Template", "The template the project is created from.\nUse \"Save as Template\" on a recent project to add templates.", Color32::TRANSPARENT, |ui| {
+            let mut template_index = self.template_index;
+            ComboBox::new("project_wizard_template_combo", "")
+                .selected_text(self.selected_template().map(|template| template.manifest.name.as_str()).unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut template_index, None, "None");
+                    for (index, template) in self.templates.iter().enumerate() {
+                        ui.selectable_value(&mut template_index, Some(index), &template.manifest.name)
+                            .on_hover_text(&template.manifest.description);
+                    }
+                });
+            if template_index != self.template_index {
+                self.select_template(template_index);
+            }
+            if let Some(template) = self.selected_template() {
+                let variables = template.manifest.variables.iter()
+                    .filter(|variable| variable.name != NAME_VARIABLE && variable.name != DESCRIPTION_VARIABLE)
+                    .map(|variable| variable.name.clone())
+                    .collect::<Vec<_>>();
+                for variable in variables {
+                    ui.horizontal(|ui| {
+                        ui.label(&variable);
+                        let value = self.variable_values.entry(variable).or_default();
+                        TextEdit::singleline(value)
+                            .desired_width(ui.available_width())
+                            .show(ui);
+                    });
+                }
+            }
+        });
+        ui.setting_ui(LABEL_WIDTH, "Project Type", "The type of project to create.", Color32::TRANSPARENT, |ui| {
+            let previous_type = self.project_type;
+            ComboBox::new("project_wizard_type_combo", "")
+                .selected_text(self.project_type.text())
+                .show_ui(ui, |ui| {
+                    for project_type in ProjectType::ALL {
+                        ui.selectable_value(&mut self.project_type, project_type, project_type.text());
+                    }
+                });
+            if previous_type != self.project_type {
+                if let Some(dir) = settings.projects.enabled_project_directories(self.project_type).next() {
+                    self.location = dir.display().to_string();
+                }
+            }
+        });
+        ui.setting_ui(LABEL_WIDTH, "Location", "The directory that the project directory is created in.", Color32::TRANSPARENT, |ui| {
+            ui.add(PathInput::new(&mut self.location).directory().must_exist(true).writable(true));
+        });
+        ui.setting_ui(LABEL_WIDTH, "Generate", "Files that are generated from the templates in Settings > Templates.", Color32::TRANSPARENT, |ui| {
+            ui.checkbox(&mut self.scaffold.gitignore, crate::scaffold::GITIGNORE_FILE);
+            ui.checkbox(&mut self.scaffold.readme, crate::scaffold::README_FILE);
+        });
+        ui.setting_ui(LABEL_WIDTH, "Git", "Initialize a git repository and commit the generated files.\nThe branch, author, and message are set in Settings > Git.", Color32::TRANSPARENT, |ui| {
+            ui.checkbox(&mut self.init_git, "Initialize git repository");
+            ui.add_enabled_ui(self.init_git, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Remote URL");
+                    TextEdit::singleline(&mut self.remote_url)
+                        .desired_width(ui.available_width())
+                        .hint_text("Optional. Added as origin without contacting it.")
+                        .show(ui);
+                });
+            });
+        });
+        if !self.name.trim().is_empty() && !self.location.trim().is_empty() {
+            ui.label(format!("Project will be created at {}", self.project_directory().display()));
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(Color32::RED, error);
+        }
+    }
+
+    /// Returns the project if it was created this frame.
     pub fn show(
         &mut self,
         closer: Closer<'_>,
         settings: &Settings,
         ui: &mut Ui,
-    ) {
+    ) -> Option<CreatedProject> {
+        let mut created = None;
         Modal::new(Id::new("project_wizard_modal"))
             .area(
                 Area::new(Id::new("project_wizard_modal_area"))
@@ -27,53 +302,43 @@ impl ProjectWizard {
                 Frame::NONE
                     .fill(ui.style().visuals.window_fill)
             )
-            .show(ui.ctx(), move |ui| {
+            .show(ui.ctx(), |ui| {
                 ui.set_size(vec2(700.0, 700.0));
                 ui.bottom_up(Align::Min, |ui| {
                     ui.with_inner_margin(Margin::same(8), |ui| {
-                        // let inner_rect = ui.response().rect + Margin::same(8);
-                        // ui.painter().rect_filled(inner_rect, CornerRadius::ZERO, Color32::LIGHT_GRAY);
                         menu::bar(ui, |ui| {
-                            ui.right_to_left(Align::Center, |ui| {
-                                ui.label("💾🕖 7:28 PM 6/27/2025");
-                                ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-                                    if ui.button("Close").clicked() {
-                                        closer.close();
-                                    }
-                                    if ui.button("Save").clicked() {
-
-                                    }
-                                    if ui.button("Save and Close").clicked() {
-
-                                    }
-                                    if ui.button("Discard Changes").clicked() {
-
-                                    }
-                                    ui.separator();
-                                    ui.label("Modified");
+                            ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+                                let esc_pressed = ui.input_mut(|i| {
+                                    i.consume_key(Modifiers::NONE, Key::Escape)
                                 });
+                                if esc_pressed || ui.button("Close").clicked() {
+                                    closer.close();
+                                }
+                                if ui.button("Create").clicked() {
+                                    match self.create(settings) {
+                                        Ok(project) => {
+                                            created = Some(project);
+                                            closer.close();
+                                        }
+                                        Err(err) => {
+                                            self.error = Some(err.to_string());
+                                        }
+                                    }
+                                }
                             });
                         });
                     });
                     ui.vertical(|ui| {
                         ui.with_inner_margin(Margin::same(8), |ui| {
-                            ui.setting_ui(
-                                180.0,
-                                "Test",
-                                "This is a test",
-                                Color32::TRANSPARENT,
-                                |ui| {
-                                    if ui.clicked("Click me!") {
-                                        println!("Clicked.");
-                                    }
-                                }
-                            );
-                            if ui.clicked("Close") {
-                                closer.close();
-                            }
+                            ScrollArea::vertical()
+                                .auto_shrink(Vec2b::FALSE)
+                                .show(ui, |ui| {
+                                    self.form_ui(settings, ui);
+                                });
                         });
                     });
                 });
             });
+        created
     }
-}
\ No newline at end of file
+}
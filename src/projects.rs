@@ -28,6 +28,89 @@ pub enum ProjectType {
     Other,
 }
 
+impl ProjectType {
+    pub const ALL: [ProjectType; 4] = [ProjectType::Rust, ProjectType::Python, ProjectType::Web, ProjectType::Other];
+
+    /// The name of the project type, which is also the name of its tab.
+    #[inline]
+    pub const fn text(self) -> &'static str {
+        crate::app::MainTab::Project(self).text()
+    }
+
+    /// Case-insensitive inverse of [ProjectType::text].
+    pub fn from_text(text: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|project_type| project_type.text().eq_ignore_ascii_case(text))
+    }
+
+    /// Detects the type of the project in `dir` from its manifest files. Returns `None` for a directory that
+    /// doesn't look like a project.
+    pub fn detect<P: AsRef<Path>>(dir: P) -> Option<Self> {
+        fn inner(dir: &Path) -> Option<ProjectType> {
+            const MARKERS: [(ProjectType, &[&str]); 4] = [
+                (ProjectType::Rust, &["Cargo.toml"]),
+                (ProjectType::Python, &["pyproject.toml", "setup.py", "setup.cfg", "requirements.txt", "Pipfile"]),
+                (ProjectType::Web, &["package.json", "index.html", "deno.json"]),
+                (ProjectType::Other, &[".git"]),
+            ];
+            MARKERS.iter()
+                .find(|(_, files)| files.iter().any(|file| dir.join(file).exists()))
+                .map(|&(project_type, _)| project_type)
+        }
+        inner(dir.as_ref())
+    }
+}
+
+/// The project that a dropped or opened path belongs to. A directory is its own project. A file belongs to
+/// the nearest ancestor that is recognized as a project, or to its directory if there is none.
+pub fn containing_project<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
+    fn inner(path: &Path) -> Option<PathBuf> {
+        if path.is_dir() {
+            return Some(path.to_path_buf());
+        }
+        let parent = path.parent()?;
+        let project = parent.ancestors().find(|dir| ProjectType::detect(dir).is_some()).unwrap_or(parent);
+        Some(project.to_path_buf())
+    }
+    inner(path.as_ref())
+}
+
+/// Finds the projects that are directly inside of `root`. Hidden directories are skipped.
+pub fn discover_projects<P: AsRef<Path>>(root: P) -> Vec<ProjectPath> {
+    fn inner(root: &Path) -> Vec<ProjectPath> {
+        let Ok(entries) = populate_entries(root) else {
+            return Vec::new();
+        };
+        let mut projects = entries.into_iter()
+            .filter(|path| path.is_dir())
+            .filter(|path| !path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with('.')))
+            .filter_map(|path| Some(ProjectPath::from_type(ProjectType::detect(&path)?, path)))
+            .collect::<Vec<_>>();
+        projects.sort();
+        projects
+    }
+    inner(root.as_ref())
+}
+
+/// Finds the projects in project directories. A project that isn't recognized as another type belongs to the type
+/// of its project directory. Directories are scanned in order, and a project that is found in more than one of them
+/// is only returned for the first. Returns each project with the directory that it was found in.
+pub fn scan_directories<I: IntoIterator<Item = (ProjectType, PathBuf)>>(directories: I) -> Vec<(ProjectPath, PathBuf)> {
+    let mut found = HashSet::new();
+    let mut projects = Vec::new();
+    for (project_type, root) in directories {
+        for mut project in discover_projects(&root) {
+            if !found.insert(project.path().to_path_buf()) {
+                continue;
+            }
+            if project.project_type() == ProjectType::Other {
+                project.remap(|path| ProjectPath::from_type(project_type, path));
+            }
+            projects.push((project, root.clone()));
+        }
+    }
+    projects
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, bincode::Decode, bincode::Encode)]
 pub enum ProjectPath {
     Rust(PathBuf),
@@ -61,6 +144,17 @@ impl ProjectPath {
         Self::Other(path.into())
     }
 
+    #[must_use]
+    #[inline]
+    pub fn from_type<P: Into<PathBuf>>(project_type: ProjectType, path: P) -> Self {
+        match project_type {
+            ProjectType::Rust => Self::Rust(path.into()),
+            ProjectType::Python => Self::Python(path.into()),
+            ProjectType::Web => Self::Web(path.into()),
+            ProjectType::Other => Self::Other(path.into()),
+        }
+    }
+
     #[must_use]
     #[inline]
     pub const fn project_type(&self) -> ProjectType {
@@ -228,6 +322,36 @@ mod tests {
         print_path(&proj_path);
     }
 
+    #[test]
+    fn discover_projects_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let root = dir.path();
+        for (name, marker) in [("app", "Cargo.toml"), ("script", "pyproject.toml"), ("site", "package.json"), (".hidden", "Cargo.toml")] {
+            std::fs::create_dir(root.join(name)).unwrap();
+            std::fs::write(root.join(name).join(marker), "").unwrap();
+        }
+        std::fs::create_dir_all(root.join("notes").join(".git")).unwrap();
+        std::fs::create_dir(root.join("empty")).unwrap();
+        std::fs::write(root.join("file.txt"), "").unwrap();
+        std::fs::create_dir_all(root.join("app").join("src")).unwrap();
+        std::fs::write(root.join("app").join("src").join("main.rs"), "").unwrap();
+        assert_eq!(containing_project(root.join("app").join("src").join("main.rs")), Some(root.join("app")));
+        assert_eq!(containing_project(root.join("app").join("src")), Some(root.join("app").join("src")));
+        let scanned = scan_directories([(ProjectType::Web, root.to_path_buf()), (ProjectType::Rust, root.to_path_buf())]);
+        assert!(scanned.iter().all(|(_, found_in)| found_in == root));
+        assert_eq!(scanned.len(), discover_projects(root).len());
+        assert_eq!(ProjectType::detect(root.join("app")), Some(ProjectType::Rust));
+        assert_eq!(ProjectType::detect(root.join("empty")), None);
+        let mut projects = discover_projects(root);
+        projects.sort_by(|lhs, rhs| lhs.path().cmp(rhs.path()));
+        assert_eq!(projects, vec![
+            ProjectPath::rust(root.join("app")),
+            ProjectPath::other(root.join("notes")),
+            ProjectPath::python(root.join("script")),
+            ProjectPath::web(root.join("site")),
+        ]);
+    }
+
     #[test]
     fn populate_directory() {
         let directory = rfd::FileDialog::new().pick_folder().expect("You didn't pick a folder, dumbass.");
@@ -0,0 +1,217 @@
+use std::path::{Path, PathBuf};
+
+use crate::{projects::ProjectType, settings::Templates};
+
+pub const GITIGNORE_FILE: &str = ".gitignore";
+pub const README_FILE: &str = "README.md";
+
+pub const RUST_GITIGNORE: &str = "\
+/target/
+**/*.rs.bk
+*.pdb
+";
+
+pub const PYTHON_GITIGNORE: &str = "\
+__pycache__/
+*.py[cod]
+*.egg-info/
+.venv/
+venv/
+env/
+build/
+dist/
+.pytest_cache/
+.mypy_cache/
+";
+
+pub const WEB_GITIGNORE: &str = "\
+node_modules/
+dist/
+build/
+.cache/
+*.log
+.env.local
+";
+
+pub const OTHER_GITIGNORE: &str = "\
+.DS_Store
+Thumbs.db
+";
+
+pub const RUST_README: &str = "\
+# {name}
+
+{description}
+
+## Building
+
+```sh
+cargo build --release
+```
+";
+
+pub const PYTHON_README: &str = "\
+# {name}
+
+{description}
+
+## Setup
+
+```sh
+python -m venv .venv
+```
+";
+
+pub const WEB_README: &str = "\
+# {name}
+
+{description}
+
+## Setup
+
+```sh
+npm install
+```
+";
+
+pub const OTHER_README: &str = "\
+# {name}
+
+{description}
+";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScaffoldError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// The `.gitignore` and README templates for a single project type.
+#[derive(Debug, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct ScaffoldTemplate {
+    pub gitignore: String,
+    pub readme: String,
+}
+
+impl ScaffoldTemplate {
+    #[inline]
+    pub fn new<S0: Into<String>, S1: Into<String>>(gitignore: S0, readme: S1) -> Self {
+        Self {
+            gitignore: gitignore.into(),
+            readme: readme.into(),
+        }
+    }
+
+    /// The built-in template for a project type.
+    pub fn default_for(project_type: ProjectType) -> Self {
+        match project_type {
+            ProjectType::Rust => Self::new(RUST_GITIGNORE, RUST_README),
+            ProjectType::Python => Self::new(PYTHON_GITIGNORE, PYTHON_README),
+            ProjectType::Web => Self::new(WEB_GITIGNORE, WEB_README),
+            ProjectType::Other => Self::new(OTHER_GITIGNORE, OTHER_README),
+        }
+    }
+
+    /// Formats the README template. Supported placeholders are `{name}` and `{description}`.
+    /// Any other braces are kept as they are, so READMEs can contain code blocks.
+    pub fn render_readme(&self, name: &str, description: &str) -> String {
+        let mut readme = String::with_capacity(self.readme.len());
+        let mut rest = self.readme.as_str();
+        while let Some(start) = rest.find('{') {
+            readme.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if let Some(after) = rest.strip_prefix("{name}") {
+                readme.push_str(name);
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("{description}") {
+                readme.push_str(description);
+                rest = after;
+            } else {
+                readme.push('{');
+                rest = &rest[1..];
+            }
+        }
+        readme.push_str(rest);
+        readme
+    }
+}
+
+/// Which files to generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScaffoldOptions {
+    pub gitignore: bool,
+    pub readme: bool,
+    /// Replace files that already exist.
+    pub overwrite: bool,
+}
+
+impl Default for ScaffoldOptions {
+    fn default() -> Self {
+        Self {
+            gitignore: true,
+            readme: true,
+            overwrite: false,
+        }
+    }
+}
+
+/// Writes the `.gitignore` and README for a project directory. Returns the paths that were written.
+/// Existing files are skipped unless `options.overwrite` is set.
+pub fn write_scaffold<P: AsRef<Path>>(
+    directory: P,
+    project_type: ProjectType,
+    templates: &Templates,
+    name: &str,
+    description: &str,
+    options: ScaffoldOptions,
+) -> Result<Vec<PathBuf>, ScaffoldError> {
+    fn write_file(path: PathBuf, contents: &str, overwrite: bool, written: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        if !overwrite && path.exists() {
+            return Ok(());
+        }
+        std::fs::write(&path, contents)?;
+        written.push(path);
+        Ok(())
+    }
+    let directory = directory.as_ref();
+    let template = templates.get(project_type);
+    let mut written = Vec::new();
+    if options.gitignore {
+        write_file(directory.join(GITIGNORE_FILE), &template.gitignore, options.overwrite, &mut written)?;
+    }
+    if options.readme {
+        let readme = template.render_readme(name, description);
+        write_file(directory.join(README_FILE), &readme, options.overwrite, &mut written)?;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffold_test() -> Result<(), ScaffoldError> {
+        let dir = tempfile::tempdir()?;
+        let templates = Templates::default();
+        let written = write_scaffold(dir.path(), ProjectType::Rust, &templates, "projector", "A project opener.", ScaffoldOptions::default())?;
+        assert_eq!(written.len(), 2);
+        let readme = std::fs::read_to_string(dir.path().join(README_FILE))?;
+        assert!(readme.starts_with("# projector\n\nA project opener.\n"));
+        let gitignore = std::fs::read_to_string(dir.path().join(GITIGNORE_FILE))?;
+        assert!(gitignore.contains("/target/"));
+        // Existing files are left alone.
+        let written = write_scaffold(dir.path(), ProjectType::Python, &templates, "other", "", ScaffoldOptions::default())?;
+        assert!(written.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn render_readme_test() {
+        let template = ScaffoldTemplate::new("", "# {name}\n\n```rust\nfn main() { println!(\"{}\", 1); }\n```\n{description}{");
+        assert_eq!(
+            template.render_readme("demo", "Uses {name} literally."),
+            "# demo\n\n```rust\nfn main() { println!(\"{}\", 1); }\n```\nUses {name} literally.{",
+        );
+    }
+}
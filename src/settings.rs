@@ -6,7 +6,7 @@ use eframe::{
 use crate::{
     app::{
         MainTab, ModalUi,
-    }, appdata::AppData, dgui::tabs::{Tab, TabSizeMode, Tabs}, ext::{Replace, UiExt}, projects::ProjectType, util::{
+    }, appdata::AppData, dgui::{dirs::Dirs, dirs_edit::DirsEdit, tabs::{Tab, TabSizeMode, Tabs}}, ext::{Replace, UiExt}, projects::ProjectType, scaffold::ScaffoldTemplate, tools::{self, CommandPreset}, util::{
         alt::Alternator, marker::*, time::RepeatTimer
     }
 };
@@ -40,6 +40,44 @@ impl IncludePathTypes {
     }
 }
 
+/// How "Open Terminal Here" opens a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, bincode::Encode, bincode::Decode)]
+pub enum TerminalMode {
+    /// Runs the shell command.
+    Command,
+    /// Attaches a terminal to the project's tmux session, creating the session if it isn't running.
+    Tmux,
+}
+
+impl TerminalMode {
+    #[inline]
+    pub const fn text(self) -> &'static str {
+        match self {
+            TerminalMode::Command => "Shell Command",
+            TerminalMode::Tmux => "tmux Session",
+        }
+    }
+}
+
+/// How directories and files are picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, bincode::Encode, bincode::Decode)]
+pub enum FileDialogMode {
+    /// The operating system's file dialog.
+    Native,
+    /// Projector's directory browser. Works where the native dialog isn't available.
+    BuiltIn,
+}
+
+impl FileDialogMode {
+    #[inline]
+    pub const fn text(self) -> &'static str {
+        match self {
+            FileDialogMode::Native => "Native",
+            FileDialogMode::BuiltIn => "Built-in",
+        }
+    }
+}
+
 macro_rules! settings_structs {
     (
         $(
@@ -79,19 +117,37 @@ settings_structs!{
         pub open_after_create: bool = true,
         pub close_after_open: bool = false,
         pub default_projects_tab: MainTab = MainTab::Main,
-        pub editor_command: String = String::from("code {path}"),
-        pub shell_command: String = String::from(if cfg!(target_os = "windows") {
+        pub editor_command: String = tools::default_editor_command().unwrap_or_else(|| String::from("code {path}")),
+        pub shell_command: String = tools::default_terminal_command().unwrap_or_else(|| String::from(if cfg!(target_os = "windows") {
             "wt.exe --startingDirectory {path}"
         } else if cfg!(target_os = "linux") {
             "konsole --workdir {path}"
         } else {
             ""
-        }),
+        })),
+        pub terminal_mode: TerminalMode = TerminalMode::Command,
+        pub tmux_terminal_command: String = tools::default_tmux_terminal_command().unwrap_or_else(|| String::from(if cfg!(target_os = "linux") {
+            "konsole --workdir {path} -e {tmux}"
+        } else if cfg!(target_os = "macos") {
+            "osascript -e 'tell application \"Terminal\" to do script \"{tmux}\"'"
+        } else {
+            ""
+        })),
+        pub tmux_session_name: String = String::from("{name}-{hash}"),
+        pub tmux_layout: String = String::from("main:"),
         pub explorer_command: String = String::from(if cfg!(target_os = "windows") {
             "explorer.exe {path}"
         } else {
             ""
         }),
+        pub browser_command: String = String::from(if cfg!(target_os = "windows") {
+            "explorer.exe {url}"
+        } else if cfg!(target_os = "macos") {
+            "open {url}"
+        } else {
+            "xdg-open {url}"
+        }),
+        pub file_dialog: FileDialogMode = FileDialogMode::Native,
         pub dummy_string: String = String::from("dummy"),
         pub dummy_toggle: bool = false,
         pub clicker_counter: u64 = 0,
@@ -102,6 +158,7 @@ settings_structs!{
     pub struct Rust {
         pub editor_command: String = String::new(),
         pub project_directories: Vec<PathBuf> = Vec::new(),
+        pub disabled_directories: Vec<PathBuf> = Vec::new(),
         pub include_path_types: IncludePathTypes = IncludePathTypes::Directories,
         pub restrict_extensions: bool = false,
         pub include_extensions: Vec<String> = vec![
@@ -113,6 +170,7 @@ settings_structs!{
     pub struct Python {
         pub editor_command: String = String::new(),
         pub project_directories: Vec<PathBuf> = Vec::new(),
+        pub disabled_directories: Vec<PathBuf> = Vec::new(),
         pub include_path_types: IncludePathTypes = IncludePathTypes::FilesAndDirectories,
         pub include_extensions: Vec<String> = vec![
             String::from("py"),
@@ -125,6 +183,7 @@ settings_structs!{
     pub struct Web {
         pub editor_command: String = String::new(),
         pub project_directories: Vec<PathBuf> = Vec::new(),
+        pub disabled_directories: Vec<PathBuf> = Vec::new(),
         pub include_path_types: IncludePathTypes = IncludePathTypes::FilesAndDirectories,
         pub include_extensions: Vec<String> = vec![
             String::from("html"),
@@ -161,8 +220,32 @@ settings_structs!{
         pub web: Web = Web::default(),
     }
 
+    #[derive(Debug, Clone, PartialEq, bincode::Encode, bincode::Decode)]
+    pub struct Licenses {
+        pub spdx_identifier: String = String::from("MIT"),
+        pub copyright_holder: String = String::new(),
+        pub header_template: String = String::from("SPDX-License-Identifier: {spdx}\nCopyright (c) {year} {author}"),
+    }
+
+    #[derive(Debug, Clone, PartialEq, bincode::Encode, bincode::Decode)]
+    pub struct Templates {
+        pub rust: ScaffoldTemplate = ScaffoldTemplate::default_for(ProjectType::Rust),
+        pub python: ScaffoldTemplate = ScaffoldTemplate::default_for(ProjectType::Python),
+        pub web: ScaffoldTemplate = ScaffoldTemplate::default_for(ProjectType::Web),
+        pub other: ScaffoldTemplate = ScaffoldTemplate::default_for(ProjectType::Other),
+    }
+
+    #[derive(Debug, Clone, PartialEq, bincode::Encode, bincode::Decode)]
+    pub struct Git {
+        pub init_repository: bool = true,
+        pub default_branch: String = String::from("main"),
+        pub author_name: String = String::new(),
+        pub author_email: String = String::new(),
+        pub initial_commit_message: String = String::from("Initial commit"),
+        pub worktree_directory: String = String::from("{repo}-{branch}"),
+    }
+
     // plugins
-    // templates,
     // style
     #[derive(Debug, Clone, PartialEq, bincode::Encode, bincode::Decode)]
     pub struct Style {
@@ -173,13 +256,141 @@ settings_structs!{
     pub struct Settings {
         pub general: General = General::default(),
         pub projects: Projects = Projects::default(),
+        pub licenses: Licenses = Licenses::default(),
+        pub templates: Templates = Templates::default(),
+        pub git: Git = Git::default(),
         // plugins
-        // templates
         // style
         pub style: Style = Style::default(),
     }
 }
 
+impl Projects {
+    /// The directories that are searched for projects of a type. [ProjectType::Other] has none.
+    #[inline]
+    pub fn project_directories(&self, project_type: ProjectType) -> &[PathBuf] {
+        match project_type {
+            ProjectType::Rust => &self.rust.project_directories,
+            ProjectType::Python => &self.python.project_directories,
+            ProjectType::Web => &self.web.project_directories,
+            ProjectType::Other => &[],
+        }
+    }
+
+    /// Mutable [Projects::project_directories]. [ProjectType::Other] has none.
+    pub fn project_directories_mut(&mut self, project_type: ProjectType) -> Option<&mut Vec<PathBuf>> {
+        match project_type {
+            ProjectType::Rust => Some(&mut self.rust.project_directories),
+            ProjectType::Python => Some(&mut self.python.project_directories),
+            ProjectType::Web => Some(&mut self.web.project_directories),
+            ProjectType::Other => None,
+        }
+    }
+
+    /// The project directories that are disabled. They are kept in [Projects::project_directories] but aren't scanned.
+    #[inline]
+    pub fn disabled_directories(&self, project_type: ProjectType) -> &[PathBuf] {
+        match project_type {
+            ProjectType::Rust => &self.rust.disabled_directories,
+            ProjectType::Python => &self.python.disabled_directories,
+            ProjectType::Web => &self.web.disabled_directories,
+            ProjectType::Other => &[],
+        }
+    }
+
+    /// The project directories that are scanned, in order of priority.
+    pub fn enabled_project_directories(&self, project_type: ProjectType) -> impl Iterator<Item = &PathBuf> {
+        let disabled = self.disabled_directories(project_type);
+        self.project_directories(project_type).iter().filter(move |dir| !disabled.contains(dir))
+    }
+
+    /// The enabled project directories of every type, in the order that they're scanned.
+    pub fn scan_roots(&self) -> Vec<(ProjectType, PathBuf)> {
+        [ProjectType::Rust, ProjectType::Python, ProjectType::Web].into_iter()
+            .flat_map(|project_type| self.enabled_project_directories(project_type).map(move |dir| (project_type, dir.clone())))
+            .collect()
+    }
+
+    /// The project directories of a type as a [Dirs]. [ProjectType::Other] has none.
+    pub fn project_dirs(&self, project_type: ProjectType) -> Dirs {
+        Dirs::from_parts(self.project_directories(project_type), self.disabled_directories(project_type))
+    }
+
+    /// Replaces the project directories of a type. Does nothing for [ProjectType::Other].
+    pub fn set_project_dirs(&mut self, project_type: ProjectType, dirs: &Dirs) {
+        let (directories, disabled) = dirs.to_parts();
+        match project_type {
+            ProjectType::Rust => (self.rust.project_directories, self.rust.disabled_directories) = (directories, disabled),
+            ProjectType::Python => (self.python.project_directories, self.python.disabled_directories) = (directories, disabled),
+            ProjectType::Web => (self.web.project_directories, self.web.disabled_directories) = (directories, disabled),
+            ProjectType::Other => (),
+        }
+    }
+
+    /// The extensions that are included for a project type. [ProjectType::Other] includes the extensions of every type.
+    pub fn include_extensions(&self, project_type: ProjectType) -> Vec<&str> {
+        let mut extensions: Vec<&str> = match project_type {
+            ProjectType::Rust => self.rust.include_extensions.iter().map(String::as_str).collect(),
+            ProjectType::Python => self.python.include_extensions.iter().map(String::as_str).collect(),
+            ProjectType::Web => self.web.include_extensions.iter().map(String::as_str).collect(),
+            ProjectType::Other => self.rust.include_extensions.iter()
+                .chain(self.python.include_extensions.iter())
+                .chain(self.web.include_extensions.iter())
+                .map(String::as_str)
+                .collect(),
+        };
+        extensions.sort_unstable();
+        extensions.dedup();
+        extensions
+    }
+}
+
+impl Licenses {
+    #[inline]
+    pub fn header_template(&self) -> crate::license_headers::HeaderTemplate {
+        crate::license_headers::HeaderTemplate {
+            template: self.header_template.clone(),
+            spdx: self.spdx_identifier.clone(),
+            author: self.copyright_holder.clone(),
+        }
+    }
+}
+
+impl Git {
+    #[inline]
+    pub fn init_options(&self, remote_url: Option<String>) -> crate::git::GitInitOptions {
+        crate::git::GitInitOptions {
+            default_branch: self.default_branch.clone(),
+            author_name: self.author_name.clone(),
+            author_email: self.author_email.clone(),
+            commit_message: self.initial_commit_message.clone(),
+            remote_url,
+        }
+    }
+}
+
+impl Templates {
+    #[inline]
+    pub fn get(&self, project_type: ProjectType) -> &ScaffoldTemplate {
+        match project_type {
+            ProjectType::Rust => &self.rust,
+            ProjectType::Python => &self.python,
+            ProjectType::Web => &self.web,
+            ProjectType::Other => &self.other,
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, project_type: ProjectType) -> &mut ScaffoldTemplate {
+        match project_type {
+            ProjectType::Rust => &mut self.rust,
+            ProjectType::Python => &mut self.python,
+            ProjectType::Web => &mut self.web,
+            ProjectType::Other => &mut self.other,
+        }
+    }
+}
+
 impl Settings {
     #[inline]
     pub fn create_settings_modal(&self) -> ModalUi {
@@ -189,6 +400,126 @@ impl Settings {
     pub fn apply_settings(&mut self, settings: &Settings) {
         self.clone_from(settings);
     }
+
+    /// Encodes the settings in the current version of the settings file format.
+    pub fn encode_versioned<W: std::io::Write>(&self, writer: &mut W) -> crate::error::Result<()> {
+        bincode::encode_into_std_write((SETTINGS_MAGIC, SETTINGS_VERSION, self), writer, bincode::config::standard())?;
+        Ok(())
+    }
+
+    /// Decodes a settings file, migrating older versions of the format.
+    pub fn decode_versioned(bytes: &[u8]) -> crate::error::Result<Settings> {
+        let config = bincode::config::standard();
+        let Some(rest) = bytes.strip_prefix(&SETTINGS_MAGIC) else {
+            let (settings, _) = bincode::decode_from_slice::<v0::Settings, _>(bytes, config)?;
+            return Ok(settings.into());
+        };
+        let (version, read) = bincode::decode_from_slice::<u32, _>(rest, config)?;
+        match version {
+            SETTINGS_VERSION => Ok(bincode::decode_from_slice(&rest[read..], config)?.0),
+            version => Err(crate::error::Error::UnsupportedSettingsVersion(version)),
+        }
+    }
+}
+
+/// Settings files start with this tag, followed by the version of the format. Files without it are version 0.
+const SETTINGS_MAGIC: [u8; 4] = *b"PJST";
+/// The version of the settings file format. Bump it whenever the layout of [Settings] changes, and migrate the
+/// previous layout in [Settings::decode_versioned].
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// The unversioned layout of the settings, from before the settings file had a version.
+mod v0 {
+    use std::path::PathBuf;
+
+    use crate::{app::MainTab, dgui::tabs::TabSizeMode};
+
+    use super::IncludePathTypes;
+
+    #[derive(bincode::Decode)]
+    pub struct General {
+        open_after_create: bool,
+        close_after_open: bool,
+        default_projects_tab: MainTab,
+        editor_command: String,
+        shell_command: String,
+        explorer_command: String,
+        dummy_string: String,
+        dummy_toggle: bool,
+        clicker_counter: u64,
+        dummy_number: u64,
+    }
+
+    #[derive(bincode::Decode)]
+    pub struct Rust {
+        editor_command: String,
+        project_directories: Vec<PathBuf>,
+        include_path_types: IncludePathTypes,
+        restrict_extensions: bool,
+        include_extensions: Vec<String>,
+    }
+
+    #[derive(bincode::Decode)]
+    pub struct Language {
+        editor_command: String,
+        project_directories: Vec<PathBuf>,
+        include_path_types: IncludePathTypes,
+        include_extensions: Vec<String>,
+    }
+
+    #[derive(bincode::Decode)]
+    pub struct Projects {
+        rust: Rust,
+        python: Language,
+        web: Language,
+    }
+
+    #[derive(bincode::Decode)]
+    pub struct Style {
+        tab_size_mode: TabSizeMode,
+    }
+
+    #[derive(bincode::Decode)]
+    pub struct Settings {
+        general: General,
+        projects: Projects,
+        style: Style,
+    }
+
+    impl From<Settings> for super::Settings {
+        fn from(old: Settings) -> Self {
+            let mut settings = super::Settings::default();
+            let general = &mut settings.general;
+            general.open_after_create = old.general.open_after_create;
+            general.close_after_open = old.general.close_after_open;
+            general.default_projects_tab = old.general.default_projects_tab;
+            general.editor_command = old.general.editor_command;
+            general.shell_command = old.general.shell_command;
+            general.explorer_command = old.general.explorer_command;
+            general.dummy_string = old.general.dummy_string;
+            general.dummy_toggle = old.general.dummy_toggle;
+            general.clicker_counter = old.general.clicker_counter;
+            general.dummy_number = old.general.dummy_number;
+            let rust = &mut settings.projects.rust;
+            rust.editor_command = old.projects.rust.editor_command;
+            rust.project_directories = old.projects.rust.project_directories;
+            rust.include_path_types = old.projects.rust.include_path_types;
+            rust.restrict_extensions = old.projects.rust.restrict_extensions;
+            rust.include_extensions = old.projects.rust.include_extensions;
+            let python = &mut settings.projects.python;
+            python.editor_command = old.projects.python.editor_command;
+            python.project_directories = old.projects.python.project_directories;
+            python.include_path_types = old.projects.python.include_path_types;
+            python.include_extensions = old.projects.python.include_extensions;
+            let web = &mut settings.projects.web;
+            web.editor_command = old.projects.web.editor_command;
+            web.project_directories = old.projects.web.project_directories;
+            web.include_path_types = old.projects.web.include_path_types;
+            web.include_extensions = old.projects.web.include_extensions;
+            settings.style.tab_size_mode = old.style.tab_size_mode;
+            settings
+        }
+    }
 }
 
 #[repr(u8)]
@@ -198,7 +529,8 @@ pub enum SettingsTab {
     Projects = 1,
     Licenses = 2,
     Templates = 3,
-    Style = 4,
+    Git = 4,
+    Style = 5,
 }
 
 impl SettingsTab {
@@ -309,6 +641,9 @@ pub struct SettingsDialog {
     pub request_close: bool,
     pub general_gui: GeneralGui,
     pub projects_gui: ProjectsGui,
+    pub licenses_gui: LicensesGui,
+    pub templates_gui: TemplatesGui,
+    pub git_gui: GitGui,
     pub style_gui: StyleGui,
     pub counter: u64,
     pub timer: RepeatTimer,
@@ -337,6 +672,15 @@ impl SettingsDialog {
             },
             projects_gui: ProjectsGui {
                 tab_index: 0,
+            },
+            licenses_gui: LicensesGui {
+
+            },
+            templates_gui: TemplatesGui {
+                tab_index: 0,
+            },
+            git_gui: GitGui {
+
             },
             style_gui: StyleGui {
 
@@ -534,6 +878,7 @@ impl SettingsDialog {
                             Tab::new("Projects", SettingsTab::Projects),
                             Tab::new("Licenses", SettingsTab::Licenses),
                             Tab::new("Templates", SettingsTab::Templates),
+                            Tab::new("Git", SettingsTab::Git),
                             Tab::new("Style", SettingsTab::Style),
                             // Tab::new("Other", SettingsTab::Extended("other")),
                             // Tab::new("End", SettingsTab::Extended("end")),
@@ -549,8 +894,15 @@ impl SettingsDialog {
                                     SettingsTab::Projects => {
                                         self.projects_gui.ui(changed, &mut self.settings_copy.projects, ui);
                                     }
-                                    SettingsTab::Licenses => {}
-                                    SettingsTab::Templates => {}
+                                    SettingsTab::Licenses => {
+                                        self.licenses_gui.ui(changed, &mut self.settings_copy.licenses, ui);
+                                    }
+                                    SettingsTab::Templates => {
+                                        self.templates_gui.ui(changed, &mut self.settings_copy.templates, ui);
+                                    }
+                                    SettingsTab::Git => {
+                                        self.git_gui.ui(changed, &mut self.settings_copy.git, ui);
+                                    }
                                     SettingsTab::Style => {
                                         Frame::NONE
                                             .inner_margin(Margin::same(8))
@@ -586,12 +938,49 @@ pub struct ProjectsGui {
     tab_index: usize,
 }
 
+pub struct LicensesGui {
+
+}
+
+pub struct TemplatesGui {
+    tab_index: usize,
+}
+
+pub struct GitGui {
+
+}
+
 pub struct StyleGui {
 
 }
 
 const LABEL_WIDTH: f32 = 180.0;
 
+/// A command field with a dropdown of presets for the installed programs.
+pub(crate) fn command_presets_ui(ui: &mut Ui, id_salt: &str, command: &mut String, presets: &[CommandPreset]) -> Response {
+    const PRESETS_WIDTH: f32 = 100.0;
+    let edit = TextEdit::singleline(command)
+        .desired_width(ui.available_width() - PRESETS_WIDTH - ui.spacing().item_spacing.x * 2.0 - ui.spacing().icon_width);
+    let mut resp = ui.add(edit);
+    ComboBox::new(id_salt, "")
+        .selected_text("Presets")
+        .width(PRESETS_WIDTH)
+        .show_ui(ui, |ui| {
+            if presets.is_empty() {
+                ui.label("No installed programs were found.");
+            }
+            for preset in presets {
+                let select_resp = ui.selectable_label(*command == preset.command, &preset.name)
+                    .on_hover_text(&preset.command);
+                if select_resp.clicked() {
+                    command.clone_from(&preset.command);
+                    resp.mark_changed();
+                }
+            }
+        });
+    resp
+}
+
 impl GeneralGui {
     pub fn ui(
         &mut self,
@@ -652,23 +1041,84 @@ impl GeneralGui {
                 ui.setting_ui(
                     LABEL_WIDTH,
                     "Editor Command",
-                    "The command that is executed to open a project path.\nUse `{path}` (without backticks) as a placeholder for the formatter.\nUse `{{` and `}}` to escape `{` and `}`.\nYou do not need to put quotes around `{path}`.",
+                    "The command that is executed to open a project path.\nUse `{path}` (without backticks) as a placeholder for the formatter.\n`{venv}` and `{python}` are the project's Python virtual environment and its interpreter.\nUse `{{` and `}}` to escape `{` and `}`.\nYou do not need to put quotes around `{path}`.",
                     alt.next(),
                     |ui| {
-                        let edit = TextEdit::singleline(&mut general.editor_command)
-                            .desired_width(ui.available_width());
-                        record_change(&ui.add(edit));
+                        record_change(&command_presets_ui(ui, "editor_presets_combo", &mut general.editor_command, &tools::editor_presets(tools::detected_tools())));
                     }
                 );
                 ui.setting_ui(
                     LABEL_WIDTH,
                     "Open Shell Command",
-                    "The command to open an external shell.\nUse `{path}` (without backticks) as a placeholder for the formatter.\nUse `{{` and `}}` to escape `{` and `}`.\nYou do not need to put quotes around `{path}`.",
+                    "The command to open an external shell.\nUse `{path}` (without backticks) as a placeholder for the formatter.\n`{venv}` and `{python}` are the project's Python virtual environment and its interpreter.\nUse `{{` and `}}` to escape `{` and `}`.\nYou do not need to put quotes around `{path}`.",
                     alt.next(),
                     |ui| {
-                        let edit = TextEdit::singleline(&mut general.shell_command)
-                            .desired_width(ui.available_width());
-                        record_change(&ui.add(edit));
+                        record_change(&command_presets_ui(ui, "shell_presets_combo", &mut general.shell_command, &tools::terminal_presets(tools::detected_tools())));
+                    }
+                );
+                ui.setting_ui(
+                    LABEL_WIDTH,
+                    "Terminal Mode",
+                    "How \"Open Terminal Here\" opens a terminal.\nShell Command runs the Open Shell Command.\ntmux Session attaches a terminal to the project's tmux session, creating the session if it isn't running.",
+                    alt.next(),
+                    |ui| {
+                        record_change(&ComboBox::new("terminal_mode_combo", "")
+                            .selected_text(general.terminal_mode.text())
+                            .show_ui(ui, |ui| {
+                                for mode in [TerminalMode::Command, TerminalMode::Tmux] {
+                                    record_change(&ui.selectable_value(&mut general.terminal_mode, mode, mode.text()));
+                                }
+                            }).response);
+                    }
+                );
+                if general.terminal_mode == TerminalMode::Tmux {
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "tmux Terminal Command",
+                        "The command that opens a terminal attached to a tmux session.\nUse `{tmux}` (without backticks) as a placeholder for the attach command, and `{path}` for the project path.\nUse `{{` and `}}` to escape `{` and `}`.",
+                        alt.next(),
+                        |ui| {
+                            record_change(&command_presets_ui(ui, "tmux_terminal_presets_combo", &mut general.tmux_terminal_command, &tools::tmux_terminal_presets(tools::detected_tools())));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "tmux Session Name",
+                        "The name of a project's tmux session.\nUse `{name}` (without backticks) as a placeholder for the project's directory name, and `{hash}` for a short hash of its path.\nProjects with the same directory name share a session unless `{hash}` is used.\n`.` and `:` are replaced with `_`.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut general.tmux_session_name)
+                                .desired_width(ui.available_width());
+                            record_change(&ui.add(edit));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "tmux Layout",
+                        "The windows and panes of new sessions, one window per line:\n`<name> [<layout>]: <command> | <command>`\nEach command runs in its own pane. Leave a command empty for a shell.\n`<layout>` is optional and is a tmux layout such as `tiled` or `main-vertical`.\nExample: `dev [even-horizontal]: cargo watch -x check | cargo test`",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::multiline(&mut general.tmux_layout)
+                                .desired_width(ui.available_width())
+                                .desired_rows(3)
+                                .font(TextStyle::Monospace);
+                            record_change(&ui.add(edit));
+                        }
+                    );
+                }
+                ui.setting_ui(
+                    LABEL_WIDTH,
+                    "File Dialog",
+                    "How directories and files are picked.\nNative uses the operating system's file dialog.\nBuilt-in uses Projector's directory browser, which works on systems without a desktop portal or GTK.",
+                    alt.next(),
+                    |ui| {
+                        record_change(&ComboBox::new("file_dialog_combo", "")
+                            .selected_text(general.file_dialog.text())
+                            .show_ui(ui, |ui| {
+                                for mode in [FileDialogMode::Native, FileDialogMode::BuiltIn] {
+                                    record_change(&ui.selectable_value(&mut general.file_dialog, mode, mode.text()));
+                                }
+                            }).response);
                     }
                 );
                 ui.setting_ui(
@@ -682,6 +1132,17 @@ impl GeneralGui {
                         record_change(&ui.add(edit));
                     }
                 );
+                ui.setting_ui(
+                    LABEL_WIDTH,
+                    "Browser Command",
+                    "The command that is executed to open a web page, such as a project's remote repository.\nUse `{url}` (without backticks) as a placeholder for the formatter.\nUse `{{` and `}}` to escape `{` and `}`.\nYou do not need to put quotes around `{url}`.\nThe program is started directly rather than through the shell.",
+                    alt.next(),
+                    |ui| {
+                        let edit = TextEdit::singleline(&mut general.browser_command)
+                            .desired_width(ui.available_width());
+                        record_change(&ui.add(edit));
+                    }
+                );
                 ui.setting_ui(
                     LABEL_WIDTH,
                     "Click Counter",
@@ -785,31 +1246,7 @@ impl ProjectsGui {
                                         });
                                     }
                                 );
-                                ui.setting_ui(
-                                    LABEL_WIDTH,
-                                    "Project Directories",
-                                    "The directories that will be searched for sub-directories/files to add to the project browser.",
-                                    alt.next(),
-                                    |ui| {
-                                        let _u = ScrollArea::new(Vec2b::new(false, true))
-                                        .auto_shrink(Vec2b::new(false, true))
-                                        // .max_width(200.0)
-                                        .show(ui, |ui| {
-                                            let dirs = projects.rust.project_directories.as_slice();
-                                            for dir in dirs {
-                                                ui.label(format!("{}", dir.display())).on_hover_cursor(CursorIcon::Default);
-                                            }
-                                        });
-                                        ui.horizontal(|ui| {
-                                            if ui.button("Add Path").clicked() {
-                                                if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                                                    projects.rust.project_directories.push(path);
-                                                    changed.mark();
-                                                }
-                                            }
-                                        });
-                                    }
-                                );
+                                Self::project_directories_ui(changed, projects, ProjectType::Rust, alt.next(), ui);
                                 ui.setting_ui(
                                     LABEL_WIDTH,
                                     "Include Path Types",
@@ -884,12 +1321,225 @@ impl ProjectsGui {
                             });
                         });
                     }
-                    ProjectType::Python => {}
-                    ProjectType::Web => {}
+                    ProjectType::Python | ProjectType::Web => {
+                        ScrollArea::vertical()
+                        .auto_shrink(Vec2b::FALSE)
+                        .show(ui, |ui| {
+                            Frame::NONE
+                            .inner_margin(Margin::same(8))
+                            .show(ui, |ui| {
+                                let mut alt = Alternator::new(Color32::TRANSPARENT, ui.style().visuals.faint_bg_color);
+                                Self::project_directories_ui(changed, projects, project_type, alt.next(), ui);
+                            });
+                        });
+                    }
                     ProjectType::Other => {}
                 }
             });
     }
+
+    fn project_directories_ui(changed: MarkOnly, projects: &mut Projects, project_type: ProjectType, color: Color32, ui: &mut Ui) {
+        ui.setting_ui(
+            LABEL_WIDTH,
+            "Project Directories",
+            "The directories that will be searched for sub-directories/files to add to the project browser.\nDirectories are searched from top to bottom. Drag a directory to change its priority.\nUncheck a directory to stop searching it without removing it.",
+            color,
+            |ui| {
+                let mut dirs = projects.project_dirs(project_type);
+                if changed.record_change(ui.add(DirsEdit::new(&mut dirs, project_type))).changed() {
+                    projects.set_project_dirs(project_type, &dirs);
+                }
+            }
+        );
+    }
+}
+
+impl LicensesGui {
+    pub fn ui(&mut self, changed: MarkOnly, licenses: &mut Licenses, ui: &mut Ui) {
+        ScrollArea::vertical()
+            .auto_shrink(Vec2b::FALSE)
+            .show(ui, |ui| {
+                Frame::NONE
+                .inner_margin(Margin::same(8))
+                .show(ui, |ui| {
+                    let mut alt = Alternator::new(Color32::TRANSPARENT, ui.style().visuals.faint_bg_color);
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "SPDX Identifier",
+                        "The SPDX license identifier, such as `MIT` or `MIT OR Apache-2.0`.\nAvailable in the header template as `{spdx}`.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut licenses.spdx_identifier)
+                                .desired_width(ui.available_width());
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Copyright Holder",
+                        "The name that appears in the copyright notice.\nAvailable in the header template as `{author}`.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut licenses.copyright_holder)
+                                .desired_width(ui.available_width());
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Header Template",
+                        "The header inserted at the top of each source file. Comment syntax is added automatically.\nPlaceholders: `{spdx}`, `{author}`, `{year}`.\nThe template must contain `SPDX-License-Identifier:` so that existing headers can be found and updated.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::multiline(&mut licenses.header_template)
+                                .desired_width(ui.available_width())
+                                .desired_rows(4)
+                                .code_editor();
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                });
+            });
+    }
+}
+
+impl TemplatesGui {
+    pub fn ui(&mut self, changed: MarkOnly, templates: &mut Templates, ui: &mut Ui) {
+        const TABS: &[Tab<'static, ProjectType>] = &[
+            Tab::new("Rust", ProjectType::Rust),
+            Tab::new("Python", ProjectType::Python),
+            Tab::new("Web", ProjectType::Web),
+            Tab::new("Other", ProjectType::Other),
+        ];
+        Tabs::new(&mut self.tab_index, TABS)
+            .with_text_align(Align::Center)
+            .with_size_mode(TabSizeMode::Grow)
+            .show(ui, |_tab_index, project_type, ui| {
+                ScrollArea::vertical()
+                .auto_shrink(Vec2b::FALSE)
+                .show(ui, |ui| {
+                    Frame::NONE
+                    .inner_margin(Margin::same(8))
+                    .show(ui, |ui| {
+                        let mut alt = Alternator::new(Color32::TRANSPARENT, ui.style().visuals.faint_bg_color);
+                        let template = templates.get_mut(project_type);
+                        let default = ScaffoldTemplate::default_for(project_type);
+                        ui.setting_ui(
+                            LABEL_WIDTH,
+                            ".gitignore",
+                            "The .gitignore that is generated when a project of this type is created or adopted.\nThis is written as-is.",
+                            alt.next(),
+                            |ui| {
+                                let edit = TextEdit::multiline(&mut template.gitignore)
+                                    .desired_width(ui.available_width())
+                                    .desired_rows(8)
+                                    .code_editor();
+                                changed.record_change(ui.add(edit));
+                                if ui.add_enabled(template.gitignore != default.gitignore, Button::new("Reset to Default")).clicked() {
+                                    template.gitignore = default.gitignore.clone();
+                                    changed.mark();
+                                }
+                            }
+                        );
+                        ui.setting_ui(
+                            LABEL_WIDTH,
+                            "README",
+                            "The README.md skeleton that is generated when a project of this type is created or adopted.\nUse `{name}` and `{description}` (without backticks) as placeholders.\nOther braces are left as they are.",
+                            alt.next(),
+                            |ui| {
+                                let edit = TextEdit::multiline(&mut template.readme)
+                                    .desired_width(ui.available_width())
+                                    .desired_rows(8)
+                                    .code_editor();
+                                changed.record_change(ui.add(edit));
+                                if ui.add_enabled(template.readme != default.readme, Button::new("Reset to Default")).clicked() {
+                                    template.readme = default.readme;
+                                    changed.mark();
+                                }
+                            }
+                        );
+                    });
+                });
+            });
+    }
+}
+
+impl GitGui {
+    pub fn ui(&mut self, changed: MarkOnly, git: &mut Git, ui: &mut Ui) {
+        ScrollArea::vertical()
+            .auto_shrink(Vec2b::FALSE)
+            .show(ui, |ui| {
+                Frame::NONE
+                .inner_margin(Margin::same(8))
+                .show(ui, |ui| {
+                    let mut alt = Alternator::new(Color32::TRANSPARENT, ui.style().visuals.faint_bg_color);
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Initialize Repository",
+                        "Whether \"Initialize git repository\" is checked by default in Create Project.",
+                        alt.next(),
+                        |ui| {
+                            changed.record_change(ui.checkbox(&mut git.init_repository, ""));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Default Branch",
+                        "The name of the initial branch. Leave empty to use git's default.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut git.default_branch)
+                                .desired_width(ui.available_width());
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Author Name",
+                        "The author of the initial commit. Leave empty to use `user.name` from your git config.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut git.author_name)
+                                .desired_width(ui.available_width());
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Author Email",
+                        "The author email of the initial commit. Leave empty to use `user.email` from your git config.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut git.author_email)
+                                .desired_width(ui.available_width());
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Initial Commit Message",
+                        "The message of the initial commit.",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut git.initial_commit_message)
+                                .desired_width(ui.available_width());
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                    ui.setting_ui(
+                        LABEL_WIDTH,
+                        "Worktree Directory",
+                        "Where new worktrees are created, relative to the repository's parent directory.\nPlaceholders: `{repo}` (the repository directory name), `{branch}` (`/` is replaced with `-`).",
+                        alt.next(),
+                        |ui| {
+                            let edit = TextEdit::singleline(&mut git.worktree_directory)
+                                .desired_width(ui.available_width());
+                            changed.record_change(ui.add(edit));
+                        }
+                    );
+                });
+            });
+    }
 }
 
 impl StyleGui {
@@ -921,4 +1571,43 @@ impl StyleGui {
                 });
             });
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_version_test() {
+        let mut settings = Settings::default();
+        settings.general.editor_command = String::from("hx {path}");
+        settings.projects.rust.disabled_directories.push(PathBuf::from("/old"));
+        let mut bytes = Vec::new();
+        settings.encode_versioned(&mut bytes).unwrap();
+        assert_eq!(Settings::decode_versioned(&bytes).unwrap(), settings);
+
+        let mut newer = Vec::new();
+        bincode::encode_into_std_write((SETTINGS_MAGIC, SETTINGS_VERSION + 1), &mut newer, bincode::config::standard()).unwrap();
+        assert!(matches!(Settings::decode_versioned(&newer), Err(crate::error::Error::UnsupportedSettingsVersion(_))));
+
+        // Structs are encoded like tuples of their fields, so this is a settings file from before versioning.
+        let language = |dir: &str, ext: &str| (String::new(), vec![PathBuf::from(dir)], IncludePathTypes::FilesAndDirectories, vec![String::from(ext)]);
+        let v0 = (
+            (false, true, MainTab::Text, String::from("vim {path}"), String::from("xterm"), String::from("nautilus {path}"), String::from("dummy"), false, 3u64, 4u64),
+            (
+                (String::from("rustrover {path}"), vec![PathBuf::from("/code/rust")], IncludePathTypes::Directories, true, vec![String::from("rs")]),
+                language("/code/python", "py"),
+                language("/code/web", "html"),
+            ),
+            (TabSizeMode::Shrink,),
+        );
+        let bytes = bincode::encode_to_vec(v0, bincode::config::standard()).unwrap();
+        let migrated = Settings::decode_versioned(&bytes).unwrap();
+        assert_eq!(migrated.general.editor_command, "vim {path}");
+        assert_eq!(migrated.general.default_projects_tab, MainTab::Text);
+        assert_eq!(migrated.projects.rust.editor_command, "rustrover {path}");
+        assert!(migrated.projects.rust.restrict_extensions);
+        assert_eq!(migrated.projects.web.project_directories, vec![PathBuf::from("/code/web")]);
+        assert_eq!(migrated.style.tab_size_mode, TabSizeMode::Shrink);
+        assert_eq!(migrated.licenses, Licenses::default());
+    }
+}
@@ -0,0 +1,170 @@
+/// The commands of `projector`, completed in every shell.
+const COMMANDS: &str = "list open add remove scan new launch path shell help";
+
+/// A shell that `projector shell` prints an integration script for. The script defines a function that changes
+/// directory to a project, and completions for the function and for `projector`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 3] = [Shell::Bash, Shell::Zsh, Shell::Fish];
+
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shell| shell.name().eq_ignore_ascii_case(name))
+    }
+
+    /// How the script is loaded from the shell's startup file.
+    pub const fn install_hint(self) -> &'static str {
+        match self {
+            Shell::Bash => "eval \"$(projector shell bash)\"  # in ~/.bashrc",
+            Shell::Zsh => "eval \"$(projector shell zsh)\"  # in ~/.zshrc, after compinit",
+            Shell::Fish => "projector shell fish | source  # in ~/.config/fish/config.fish",
+        }
+    }
+
+    /// Quotes `text` as a single word.
+    fn quote(self, text: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("'{}'", text.replace('\'', r"'\''")),
+            Shell::Fish => format!("'{}'", text.replace('\\', r"\\").replace('\'', r"\'")),
+        }
+    }
+
+    /// The script that defines `function`, which changes directory to the project that matches its arguments, and
+    /// the completions of `function` and `projector`. `program` is the path of the `projector` executable.
+    pub fn init_script(self, program: &str, function: &str) -> String {
+        let program = self.quote(program);
+        let template = match self {
+            Shell::Bash => BASH_SCRIPT,
+            Shell::Zsh => ZSH_SCRIPT,
+            Shell::Fish => FISH_SCRIPT,
+        };
+        template
+            .replace("__PROGRAM__", &program)
+            .replace("__FUNCTION__", function)
+            .replace("__COMMANDS__", COMMANDS)
+    }
+}
+
+const BASH_SCRIPT: &str = r#"__FUNCTION__() {
+    local dir
+    dir="$(__PROGRAM__ path "$@")" || return
+    cd -- "$dir"
+}
+
+_projector_names() {
+    __PROGRAM__ complete 2>/dev/null
+}
+
+___FUNCTION___complete() {
+    local IFS=$'\n'
+    COMPREPLY=($(compgen -W "$(_projector_names)" -- "${COMP_WORDS[COMP_CWORD]}"))
+}
+complete -F ___FUNCTION___complete __FUNCTION__
+
+_projector_complete() {
+    local IFS=$'\n'
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    if [ "$COMP_CWORD" -eq 1 ]; then
+        COMPREPLY=($(compgen -W "$(printf '%s\n' __COMMANDS__)" -- "$cur"))
+        return
+    fi
+    case "${COMP_WORDS[1]}" in
+        open|remove|launch|path)
+            [ "$COMP_CWORD" -eq 2 ] && COMPREPLY=($(compgen -W "$(_projector_names)" -- "$cur")) ;;
+        add)
+            COMPREPLY=($(compgen -d -- "$cur")) ;;
+        shell)
+            COMPREPLY=($(compgen -W "$(printf '%s\n' bash zsh fish)" -- "$cur")) ;;
+    esac
+}
+complete -F _projector_complete projector
+"#;
+
+const ZSH_SCRIPT: &str = r#"__FUNCTION__() {
+    local dir
+    dir="$(__PROGRAM__ path "$@")" || return
+    cd -- "$dir"
+}
+
+_projector_names() {
+    local -a names
+    names=("${(@f)$(__PROGRAM__ complete 2>/dev/null)}")
+    compadd -a names
+}
+
+___FUNCTION__() {
+    (( CURRENT == 2 )) && _projector_names
+}
+
+_projector() {
+    if (( CURRENT == 2 )); then
+        compadd __COMMANDS__
+        return
+    fi
+    case "$words[2]" in
+        open|remove|launch|path) (( CURRENT == 3 )) && _projector_names ;;
+        add) _files -/ ;;
+        shell) compadd bash zsh fish ;;
+    esac
+}
+
+if (( $+functions[compdef] )); then
+    compdef ___FUNCTION__ __FUNCTION__
+    compdef _projector projector
+fi
+"#;
+
+// The program is kept in a variable, because the completions are single-quoted and the quoted program can't be nested
+// in them.
+const FISH_SCRIPT: &str = r#"set -g __projector_program __PROGRAM__
+
+function __FUNCTION__ --description 'Change directory to a Projector project'
+    set -l dir ($__projector_program path $argv); or return
+    cd $dir
+end
+
+complete -c __FUNCTION__ -f -a '($__projector_program complete 2>/dev/null)'
+complete -c projector -f -n '__fish_use_subcommand' -a '__COMMANDS__'
+complete -c projector -f -n '__fish_seen_subcommand_from open remove launch path' -a '($__projector_program complete 2>/dev/null)'
+complete -c projector -f -n '__fish_seen_subcommand_from shell' -a 'bash zsh fish'
+complete -c projector -n '__fish_seen_subcommand_from add' -a '(__fish_complete_directories)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_test() {
+        assert_eq!(Shell::from_name("ZSH"), Some(Shell::Zsh));
+        assert_eq!(Shell::from_name("tcsh"), None);
+        assert_eq!(Shell::Bash.quote("it's"), r"'it'\''s'");
+        assert_eq!(Shell::Fish.quote("it's"), r"'it\'s'");
+        for shell in Shell::ALL {
+            let script = shell.init_script("/opt/projector", "pj");
+            assert!(!script.contains("__PROGRAM__") && !script.contains("__FUNCTION__") && !script.contains("__COMMANDS__"));
+        }
+        for shell in [Shell::Bash, Shell::Zsh] {
+            assert!(shell.init_script("/opt/projector", "pj").contains("'/opt/projector' path"));
+        }
+        let fish = Shell::Fish.init_script("/opt/it's/projector", "pj");
+        assert!(fish.starts_with(r"set -g __projector_program '/opt/it\'s/projector'"));
+        assert!(fish.contains("-a '($__projector_program complete 2>/dev/null)'"));
+        assert_eq!(fish.matches("projector'").count(), 1);
+        assert!(Shell::Bash.init_script("projector", "pj").contains("complete -F _pj_complete pj"));
+    }
+}
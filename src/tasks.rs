@@ -0,0 +1,630 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Child, Stdio},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use eframe::egui::Context;
+
+use crate::{appdata::AppCache, util::execute::shell_command};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TaskError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Toml Error: {0}")]
+    TomlError(#[from] toml::de::Error),
+    #[error("Json Error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TaskSource {
+    Cargo,
+    /// `[alias]` in `.cargo/config.toml`.
+    CargoAlias,
+    /// `scripts` in `package.json`.
+    Npm,
+    Make,
+    Just,
+    /// Scripts in `pyproject.toml`.
+    Python,
+}
+
+impl TaskSource {
+    pub const fn text(self) -> &'static str {
+        match self {
+            TaskSource::Cargo => "cargo",
+            TaskSource::CargoAlias => "cargo alias",
+            TaskSource::Npm => "package.json",
+            TaskSource::Make => "make",
+            TaskSource::Just => "just",
+            TaskSource::Python => "pyproject",
+        }
+    }
+}
+
+/// A runnable task that was detected in a project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Task {
+    pub source: TaskSource,
+    pub name: String,
+    /// The shell command that runs the task in the project directory.
+    pub command: String,
+    /// What the task runs, when it is known (the script body, the alias expansion, the recipe comment).
+    pub description: Option<String>,
+}
+
+impl Task {
+    fn new<N: Into<String>, C: Into<String>>(source: TaskSource, name: N, command: C, description: Option<String>) -> Self {
+        Self {
+            source,
+            name: name.into(),
+            command: command.into(),
+            description,
+        }
+    }
+
+    /// Identifies the task within its project.
+    #[inline]
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.source.text(), self.name)
+    }
+}
+
+/// The shell command that runs the task `name` with `runner`. Names come from the project's files, so they're quoted.
+/// Returns `None` if the name can't be quoted.
+fn task_command(runner: &str, name: &str) -> Option<String> {
+    shlex::try_quote(name).ok().map(|name| format!("{runner}{name}"))
+}
+
+const CARGO_COMMANDS: &[&str] = &["build", "check", "run", "test", "clippy", "doc", "fmt"];
+
+fn cargo_tasks(dir: &Path, tasks: &mut Vec<Task>) -> Result<(), TaskError> {
+    if !dir.join("Cargo.toml").is_file() {
+        return Ok(());
+    }
+    tasks.extend(CARGO_COMMANDS.iter().map(|command| Task::new(TaskSource::Cargo, *command, format!("cargo {command}"), None)));
+    for config in [dir.join(".cargo").join("config.toml"), dir.join(".cargo").join("config")] {
+        if !config.is_file() {
+            continue;
+        }
+        let config: toml::Table = std::fs::read_to_string(config)?.parse()?;
+        let Some(aliases) = config.get("alias").and_then(toml::Value::as_table) else {
+            break;
+        };
+        for (name, value) in aliases {
+            let expansion = match value {
+                toml::Value::String(expansion) => expansion.clone(),
+                toml::Value::Array(args) => args.iter().filter_map(toml::Value::as_str).collect::<Vec<_>>().join(" "),
+                _ => continue,
+            };
+            if let Some(command) = task_command("cargo ", name) {
+                tasks.push(Task::new(TaskSource::CargoAlias, name, command, Some(expansion)));
+            }
+        }
+        // Cargo only reads `config` when `config.toml` doesn't exist.
+        break;
+    }
+    Ok(())
+}
+
+fn npm_tasks(dir: &Path, tasks: &mut Vec<Task>) -> Result<(), TaskError> {
+    let manifest = dir.join("package.json");
+    if !manifest.is_file() {
+        return Ok(());
+    }
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(manifest)?)?;
+    let runner = if dir.join("pnpm-lock.yaml").is_file() {
+        "pnpm run "
+    } else if dir.join("yarn.lock").is_file() {
+        "yarn run "
+    } else if dir.join("bun.lockb").is_file() || dir.join("bun.lock").is_file() {
+        "bun run "
+    } else {
+        "npm run "
+    };
+    if let Some(scripts) = manifest.get("scripts").and_then(serde_json::Value::as_object) {
+        for (name, script) in scripts {
+            if let Some(command) = task_command(runner, name) {
+                tasks.push(Task::new(TaskSource::Npm, name, command, script.as_str().map(str::to_owned)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses the explicit targets of a Makefile. Special targets (`.PHONY`), pattern rules, and variable assignments are skipped.
+pub fn parse_make_targets(makefile: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    for line in makefile.lines() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `a := b`, `a ::= b`, and `a = b:c` are assignments.
+        if rest.starts_with('=') || rest.starts_with(":=") || names.contains('=') {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            if !name.contains(['%', '$']) && !targets.iter().any(|target| target == name) {
+                targets.push(name.to_owned());
+            }
+        }
+    }
+    targets
+}
+
+fn make_tasks(dir: &Path, tasks: &mut Vec<Task>) -> Result<(), TaskError> {
+    let Some(makefile) = ["GNUmakefile", "makefile", "Makefile"].iter().map(|name| dir.join(name)).find(|path| path.is_file()) else {
+        return Ok(());
+    };
+    for target in parse_make_targets(&std::fs::read_to_string(makefile)?) {
+        if let Some(command) = task_command("make ", &target) {
+            tasks.push(Task::new(TaskSource::Make, &target, command, None));
+        }
+    }
+    Ok(())
+}
+
+/// Parses the recipes of a justfile, with the comment above each recipe as its description.
+/// Private recipes (starting with `_`) are skipped.
+pub fn parse_just_recipes(justfile: &str) -> Vec<(String, Option<String>)> {
+    const KEYWORDS: &[&str] = &["set", "alias", "export", "import", "mod"];
+    let mut recipes = Vec::new();
+    let mut comment = None;
+    let mut private = false;
+    for line in justfile.lines() {
+        if line.starts_with([' ', '\t']) || line.trim().is_empty() {
+            comment = None;
+            private = false;
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim().to_owned());
+            continue;
+        }
+        // Attributes such as `[private]` apply to the next recipe.
+        if line.starts_with('[') {
+            private |= line.contains("private");
+            continue;
+        }
+        let header = line.trim_start_matches('@');
+        let name = header.split(|c: char| c.is_whitespace() || c == ':').next().unwrap_or("");
+        let is_recipe = header.split_once(':').is_some_and(|(_, rest)| !rest.starts_with('='));
+        if is_recipe && !name.is_empty() && !KEYWORDS.contains(&name) && !private && !name.starts_with('_') {
+            recipes.push((name.to_owned(), comment.take()));
+        }
+        comment = None;
+        private = false;
+    }
+    recipes
+}
+
+fn just_tasks(dir: &Path, tasks: &mut Vec<Task>) -> Result<(), TaskError> {
+    let Some(justfile) = ["justfile", "Justfile", ".justfile"].iter().map(|name| dir.join(name)).find(|path| path.is_file()) else {
+        return Ok(());
+    };
+    for (recipe, description) in parse_just_recipes(&std::fs::read_to_string(justfile)?) {
+        if let Some(command) = task_command("just ", &recipe) {
+            tasks.push(Task::new(TaskSource::Just, &recipe, command, description));
+        }
+    }
+    Ok(())
+}
+
+fn python_tasks(dir: &Path, tasks: &mut Vec<Task>) -> Result<(), TaskError> {
+    let pyproject = dir.join("pyproject.toml");
+    if !pyproject.is_file() {
+        return Ok(());
+    }
+    let pyproject: toml::Table = std::fs::read_to_string(pyproject)?.parse()?;
+    let table = |path: &[&str]| -> Option<&toml::Table> {
+        path.iter().try_fold(&pyproject, |table, key| table.get(*key)?.as_table())
+    };
+    let describe = |value: &toml::Value| -> Option<String> {
+        match value {
+            toml::Value::String(script) => Some(script.clone()),
+            toml::Value::Table(table) => ["cmd", "shell", "call", "script"].iter()
+                .find_map(|key| table.get(*key)?.as_str().map(str::to_owned)),
+            toml::Value::Array(steps) => Some(steps.iter().filter_map(toml::Value::as_str).collect::<Vec<_>>().join(" && ")),
+            _ => None,
+        }
+    };
+    // Console entry points are installed into the environment, so they are run through the project's tool when there is one.
+    let entry_runner = if table(&["tool", "poetry"]).is_some() {
+        "poetry run "
+    } else if dir.join("uv.lock").is_file() {
+        "uv run "
+    } else {
+        ""
+    };
+    let sources: [(&[&str], &str); 5] = [
+        (&["project", "scripts"], entry_runner),
+        (&["tool", "poetry", "scripts"], "poetry run "),
+        (&["tool", "pdm", "scripts"], "pdm run "),
+        (&["tool", "hatch", "envs", "default", "scripts"], "hatch run "),
+        (&["tool", "poe", "tasks"], "poe "),
+    ];
+    for (path, runner) in sources {
+        let Some(scripts) = table(path) else {
+            continue;
+        };
+        for (name, value) in scripts {
+            if tasks.iter().any(|task| task.source == TaskSource::Python && &task.name == name) {
+                continue;
+            }
+            if let Some(command) = task_command(runner, name) {
+                tasks.push(Task::new(TaskSource::Python, name, command, describe(value)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The tasks that were detected in a project.
+#[derive(Debug, Default)]
+pub struct DetectedTasks {
+    pub tasks: Vec<Task>,
+    /// The sources that couldn't be read. The tasks of the other sources are still detected.
+    pub errors: Vec<(TaskSource, TaskError)>,
+}
+
+/// Detects the tasks of the project in `dir`.
+pub fn detect_tasks<P: AsRef<Path>>(dir: P) -> DetectedTasks {
+    type Detector = fn(&Path, &mut Vec<Task>) -> Result<(), TaskError>;
+    const DETECTORS: [(TaskSource, Detector); 5] = [
+        (TaskSource::CargoAlias, cargo_tasks),
+        (TaskSource::Npm, npm_tasks),
+        (TaskSource::Make, make_tasks),
+        (TaskSource::Just, just_tasks),
+        (TaskSource::Python, python_tasks),
+    ];
+    let dir = dir.as_ref();
+    let mut detected = DetectedTasks::default();
+    for (source, detector) in DETECTORS {
+        if let Err(err) = detector(dir, &mut detected.tasks) {
+            detected.errors.push((source, err));
+        }
+    }
+    detected
+}
+
+/// The result of the last run of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct TaskStatus {
+    /// `None` when the process was terminated by a signal (or killed).
+    pub exit_code: Option<i32>,
+    /// Unix timestamp.
+    pub finished: i64,
+}
+
+impl TaskStatus {
+    #[inline]
+    pub const fn success(self) -> bool {
+        matches!(self.exit_code, Some(0))
+    }
+}
+
+/// The last lines that a task wrote. Earlier lines are dropped so that long-running tasks don't grow without bound.
+#[derive(Debug, Default)]
+struct TaskOutput {
+    lines: VecDeque<String>,
+    dropped: usize,
+}
+
+impl TaskOutput {
+    const MAX_LINES: usize = 5000;
+
+    fn push<S: Into<String>>(&mut self, line: S) {
+        if self.lines.len() == Self::MAX_LINES {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line.into());
+    }
+
+    fn text(&self) -> String {
+        let mut text = if self.dropped > 0 {
+            format!("[{} earlier lines were dropped]\n", self.dropped)
+        } else {
+            String::new()
+        };
+        self.lines.iter().for_each(|line| text.push_str(line));
+        text
+    }
+}
+
+/// A task that is running or has finished this session.
+pub struct TaskRun {
+    pub task: Task,
+    output: Mutex<TaskOutput>,
+    child: Mutex<Option<Child>>,
+    status: Mutex<Option<TaskStatus>>,
+}
+
+impl TaskRun {
+    /// The last [TaskOutput::MAX_LINES] lines that the task has written to stdout and stderr so far.
+    pub fn output(&self) -> String {
+        self.output.lock().unwrap().text()
+    }
+
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.status.lock().unwrap().is_none()
+    }
+
+    /// Kills the task along with the processes it started. The task runs in a shell, so killing only the shell
+    /// would leave the task's own processes running.
+    pub fn kill(&self) {
+        if !self.is_running() {
+            return;
+        }
+        let mut child = self.child.lock().unwrap();
+        let Some(child) = child.as_mut() else {
+            return;
+        };
+        // Once the task has been waited on, its process ID can belong to another process.
+        if !matches!(child.try_wait(), Ok(None)) {
+            return;
+        }
+        kill_process_group(child);
+    }
+}
+
+/// Kills the process group of `child`, which leads its own group (see [TaskRunner::run]).
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) {
+    extern "C" {
+        fn kill(pid: i32, signal: i32) -> i32;
+    }
+    const SIGKILL: i32 = 9;
+    let Ok(pid) = i32::try_from(child.id()) else {
+        return;
+    };
+    unsafe {
+        kill(-pid, SIGKILL);
+    }
+    // The process may have exited in the meantime.
+    _ = child.kill();
+}
+
+/// Kills the process tree of `child`. `taskkill` finds the tree from `child`, so `child` is left for it to kill.
+/// It isn't waited on, so that the UI doesn't wait for it.
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) {
+    let taskkill = crate::util::execute::background_command("taskkill")
+        .args(["/T", "/F", "/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match taskkill {
+        Ok(mut taskkill) => {
+            std::thread::spawn(move || taskkill.wait());
+        }
+        Err(_) => _ = child.kill(),
+    }
+}
+
+type TaskKey = (PathBuf, String);
+
+/// Runs tasks in the background and remembers the last exit status of each task.
+/// Statuses are persisted in [AppCache].
+pub struct TaskRunner {
+    ctx: Context,
+    runs: HashMap<TaskKey, Arc<TaskRun>>,
+    statuses: Arc<Mutex<HashMap<TaskKey, TaskStatus>>>,
+}
+
+impl TaskRunner {
+    /// The relative path of the cache file in `<program>/cache/`
+    const CACHE_REL_PATH: &str = "task_status";
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new(ctx: Context, cache: &AppCache) -> Self {
+        let statuses = cache.load::<_, HashMap<TaskKey, TaskStatus>>(Self::CACHE_REL_PATH).unwrap_or_default();
+        Self {
+            ctx,
+            runs: HashMap::new(),
+            statuses: Arc::new(Mutex::new(statuses)),
+        }
+    }
+
+    /// The run of a task from this session.
+    pub fn run_of(&self, dir: &Path, task: &Task) -> Option<Arc<TaskRun>> {
+        self.runs.get(&(dir.to_owned(), task.id())).cloned()
+    }
+
+    pub fn last_status(&self, dir: &Path, task: &Task) -> Option<TaskStatus> {
+        self.statuses.lock().unwrap().get(&(dir.to_owned(), task.id())).copied()
+    }
+
+    /// Starts a task with additional environment variables. Output is streamed into the returned [TaskRun].
+    pub fn run(&mut self, dir: &Path, task: &Task, env: &[(String, String)]) -> Result<Arc<TaskRun>, TaskError> {
+        let key = (dir.to_owned(), task.id());
+        if let Some(run) = self.runs.get(&key).filter(|run| run.is_running()) {
+            return Ok(run.clone());
+        }
+        let mut command = shell_command(&task.command).ok_or_else(|| TaskError::InvalidCommand(task.command.clone()))?;
+        // The task leads its own process group so that `kill` reaches the processes it starts.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command
+            .current_dir(dir)
+            .envs(env.iter().map(|(name, value)| (name, value)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let mut output = TaskOutput::default();
+        output.push(format!("> {}\n", task.command));
+        let run = Arc::new(TaskRun {
+            task: task.clone(),
+            output: Mutex::new(output),
+            child: Mutex::new(None),
+            status: Mutex::new(None),
+        });
+        let readers = [
+            child.stdout.take().map(|stdout| Box::new(stdout) as Box<dyn Read + Send>),
+            child.stderr.take().map(|stderr| Box::new(stderr) as Box<dyn Read + Send>),
+        ];
+        *run.child.lock().unwrap() = Some(child);
+        let reader_threads = readers.into_iter().flatten().map(|reader| {
+            let run = run.clone();
+            let ctx = self.ctx.clone();
+            std::thread::spawn(move || {
+                let mut reader = BufReader::new(reader);
+                let mut line = Vec::new();
+                while reader.read_until(b'\n', &mut line).is_ok_and(|read| read > 0) {
+                    run.output.lock().unwrap().push(String::from_utf8_lossy(&line));
+                    line.clear();
+                    ctx.request_repaint();
+                }
+            })
+        }).collect::<Vec<_>>();
+        let waiter_run = run.clone();
+        let waiter_key = key.clone();
+        let statuses = self.statuses.clone();
+        let ctx = self.ctx.clone();
+        std::thread::spawn(move || {
+            // The child is polled rather than waited on so that `kill` can lock it.
+            let exit_status = loop {
+                let result = waiter_run.child.lock().unwrap().as_mut().map(Child::try_wait);
+                match result {
+                    Some(Ok(Some(exit_status))) => break Some(exit_status),
+                    Some(Ok(None)) => std::thread::sleep(Self::POLL_INTERVAL),
+                    Some(Err(_)) | None => break None,
+                }
+            };
+            for thread in reader_threads {
+                _ = thread.join();
+            }
+            let status = TaskStatus {
+                exit_code: exit_status.and_then(|exit_status| exit_status.code()),
+                finished: chrono::Utc::now().timestamp(),
+            };
+            waiter_run.output.lock().unwrap().push(match status.exit_code {
+                Some(code) => format!("\nProcess exited with code {code}.\n"),
+                None => String::from("\nProcess was terminated.\n"),
+            });
+            *waiter_run.status.lock().unwrap() = Some(status);
+            statuses.lock().unwrap().insert(waiter_key, status);
+            ctx.request_repaint();
+        });
+        self.runs.insert(key, run.clone());
+        Ok(run)
+    }
+
+    pub fn save(&self, cache: &AppCache) -> crate::error::Result<()> {
+        cache.save(Self::CACHE_REL_PATH, &*self.statuses.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tasks_test() {
+        let makefile = "\
+CC := gcc
+VERSION = 1:2
+.PHONY: all clean
+all: build
+build test: deps
+\t$(CC) main.c
+%.o: %.c
+clean:
+\trm -rf out
+";
+        assert_eq!(parse_make_targets(makefile), vec!["all", "build", "test", "clean"]);
+
+        let justfile = "\
+set shell := [\"bash\", \"-c\"]
+version := \"1.0\"
+
+# Build the project
+build:
+    cargo build
+
+@test filter='': build
+    cargo test {{filter}}
+
+[private]
+helper:
+    echo hidden
+
+_also_hidden:
+    echo hidden
+";
+        assert_eq!(parse_just_recipes(justfile), vec![
+            (String::from("build"), Some(String::from("Build the project"))),
+            (String::from("test"), None),
+        ]);
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        std::fs::write(dir.path().join("package.json"), r#"{ "scripts": { "dev": "vite", "lint": "eslint ." } }"#).unwrap();
+        std::fs::write(dir.path().join("yarn.lock"), "").unwrap();
+        std::fs::write(dir.path().join("pyproject.toml"), "[project.scripts]\ncli = \"pkg.cli:main\"\n\n[tool.pdm.scripts]\nlint = \"ruff check .\"\n").unwrap();
+        std::fs::write(dir.path().join("justfile"), "").unwrap();
+        std::fs::create_dir(dir.path().join(".cargo")).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::write(dir.path().join(".cargo").join("config.toml"), "[alias\n").unwrap();
+        let detected = detect_tasks(dir.path());
+        assert_eq!(detected.errors.len(), 1);
+        assert_eq!(detected.errors[0].0, TaskSource::CargoAlias);
+        let tasks = detected.tasks.into_iter().filter(|task| task.source != TaskSource::Cargo).collect::<Vec<_>>();
+        let commands = tasks.iter().map(|task| task.command.as_str()).collect::<Vec<_>>();
+        assert_eq!(commands, vec!["yarn run dev", "yarn run lint", "cli", "pdm run lint"]);
+        assert_eq!(tasks[3].description.as_deref(), Some("ruff check ."));
+
+        // Names with shell metacharacters are passed to the runner as one argument.
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        std::fs::write(dir.path().join("package.json"), r#"{ "scripts": { "x;touch $(id)": "vite" } }"#).unwrap();
+        let detected = detect_tasks(dir.path());
+        assert_eq!(shlex::split(&detected.tasks[0].command), Some(vec![
+            String::from("npm"),
+            String::from("run"),
+            String::from("x;touch $(id)"),
+        ]));
+    }
+
+    #[test]
+    fn task_output_test() {
+        let mut output = TaskOutput::default();
+        for i in 0..TaskOutput::MAX_LINES + 2 {
+            output.push(format!("{i}\n"));
+        }
+        let text = output.text();
+        assert!(text.starts_with("[2 earlier lines were dropped]\n2\n3\n"));
+        assert!(text.ends_with(&format!("{}\n", TaskOutput::MAX_LINES + 1)));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kill_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let mut runner = TaskRunner::new(Context::default(), &AppCache::new(dir.path()));
+        let task = Task::new(TaskSource::Make, "sleep", "sleep 30 && echo done", None);
+        let run = runner.run(dir.path(), &task, &[]).expect("Failed to start task.");
+        run.kill();
+        for _ in 0..100 {
+            if !run.is_running() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!run.is_running());
+        assert_eq!(runner.last_status(dir.path(), &task).map(|status| status.exit_code), Some(None));
+        // Killing a task that has exited does nothing.
+        run.kill();
+        assert!(!run.output().lines().any(|line| line == "done"));
+    }
+}
@@ -0,0 +1,342 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+use crate::projects::ProjectType;
+
+/// The manifest that describes a template. It lives in the root of the template directory.
+pub const MANIFEST_FILE: &str = "template.toml";
+
+/// Directories that are never copied into a template, even when they aren't ignored.
+pub const BUILD_DIRECTORIES: &[&str] = &[
+    ".git",
+    "target",
+    "node_modules",
+    "dist",
+    "build",
+    "__pycache__",
+    ".venv",
+    "venv",
+    ".mypy_cache",
+    ".pytest_cache",
+];
+
+/// Variables that the Create Project wizard fills in automatically.
+pub const NAME_VARIABLE: &str = "name";
+pub const DESCRIPTION_VARIABLE: &str = "description";
+
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Walk Error: {0}")]
+    WalkError(#[from] ignore::Error),
+    #[error("Toml Decode Error: {0}")]
+    TomlDecodeError(#[from] toml::de::Error),
+    #[error("Toml Encode Error: {0}")]
+    TomlEncodeError(#[from] toml::ser::Error),
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+    #[error("Invalid variable name: {0:?}. Use letters, digits, `_` and `-`.")]
+    InvalidVariable(String),
+    #[error("A template named {0:?} already exists.")]
+    AlreadyExists(String),
+    #[error("The variable {0:?} is defined more than once.")]
+    DuplicateVariable(String),
+}
+
+/// A literal string in the source project that is replaced with `{{name}}` in the template.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TemplateVariable {
+    pub name: String,
+    /// The literal that the variable replaced. Used as the default value.
+    pub default: String,
+}
+
+impl TemplateVariable {
+    #[inline]
+    pub fn new<S0: Into<String>, S1: Into<String>>(name: S0, default: S1) -> Self {
+        Self {
+            name: name.into(),
+            default: default.into(),
+        }
+    }
+
+    #[inline]
+    pub fn placeholder(&self) -> String {
+        placeholder(&self.name)
+    }
+}
+
+#[inline]
+pub fn placeholder(name: &str) -> String {
+    format!("{{{{{name}}}}}")
+}
+
+pub fn is_valid_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateManifest {
+    pub name: String,
+    pub description: String,
+    pub project_type: ProjectType,
+    pub variables: Vec<TemplateVariable>,
+}
+
+impl TemplateManifest {
+    pub fn to_toml(&self) -> Result<String, TemplateError> {
+        let mut table = toml::Table::new();
+        table.insert("name".into(), self.name.clone().into());
+        table.insert("description".into(), self.description.clone().into());
+        table.insert("project_type".into(), self.project_type.text().into());
+        let variables = self.variables.iter().map(|variable| {
+            let mut var_table = toml::Table::new();
+            var_table.insert("name".into(), variable.name.clone().into());
+            var_table.insert("default".into(), variable.default.clone().into());
+            toml::Value::Table(var_table)
+        }).collect::<Vec<_>>();
+        table.insert("variables".into(), toml::Value::Array(variables));
+        Ok(toml::to_string_pretty(&table)?)
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, TemplateError> {
+        fn get_str<'a>(table: &'a toml::Table, key: &str) -> Result<&'a str, TemplateError> {
+            table.get(key)
+                .and_then(toml::Value::as_str)
+                .ok_or_else(|| TemplateError::InvalidManifest(format!("missing `{key}`")))
+        }
+        let table: toml::Table = source.parse()?;
+        let name = get_str(&table, "name")?.to_owned();
+        let description = table.get("description").and_then(toml::Value::as_str).unwrap_or("").to_owned();
+        let project_type = get_str(&table, "project_type")?;
+        let project_type = ProjectType::from_text(project_type)
+            .ok_or_else(|| TemplateError::InvalidManifest(format!("unknown project type `{project_type}`")))?;
+        let mut variables = Vec::new();
+        if let Some(array) = table.get("variables").and_then(toml::Value::as_array) {
+            for value in array {
+                let var_table = value.as_table()
+                    .ok_or_else(|| TemplateError::InvalidManifest(String::from("`variables` must be an array of tables")))?;
+                variables.push(TemplateVariable::new(get_str(var_table, "name")?, get_str(var_table, "default")?));
+            }
+        }
+        Ok(Self {
+            name,
+            description,
+            project_type,
+            variables,
+        })
+    }
+}
+
+/// A template found in the templates directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub path: PathBuf,
+    pub manifest: TemplateManifest,
+}
+
+impl Template {
+    pub fn load<P: Into<PathBuf>>(path: P) -> Result<Self, TemplateError> {
+        let path: PathBuf = path.into();
+        let source = std::fs::read_to_string(path.join(MANIFEST_FILE))?;
+        Ok(Self {
+            manifest: TemplateManifest::from_toml(&source)?,
+            path,
+        })
+    }
+
+    /// The default value of every variable, keyed by variable name.
+    pub fn default_values(&self) -> HashMap<String, String> {
+        self.manifest.variables.iter()
+            .map(|variable| (variable.name.clone(), variable.default.clone()))
+            .collect()
+    }
+
+    /// Copies the template into `destination`, replacing `{{variable}}` placeholders in file contents and paths.
+    /// Variables missing from `values` use their default.
+    pub fn instantiate<P: AsRef<Path>>(&self, destination: P, values: &HashMap<String, String>) -> Result<Vec<PathBuf>, TemplateError> {
+        let destination = destination.as_ref();
+        let replacements = self.manifest.variables.iter().map(|variable| {
+            let value = values.get(&variable.name).unwrap_or(&variable.default);
+            (variable.placeholder(), value.clone())
+        }).collect::<Vec<_>>();
+        let substitute = |text: &str| {
+            replacements.iter().fold(text.to_owned(), |text, (placeholder, value)| text.replace(placeholder, value))
+        };
+        let mut written = Vec::new();
+        for source in walk_files(&self.path, false)? {
+            let rel_path = source.strip_prefix(&self.path).expect("Walked path is not inside the template.");
+            if rel_path == Path::new(MANIFEST_FILE) {
+                continue;
+            }
+            let target = destination.join(substitute(&rel_path.to_string_lossy()));
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            copy_with(&source, &target, substitute)?;
+            written.push(target);
+        }
+        Ok(written)
+    }
+}
+
+/// Copies a file, passing text files through `transform`. Files that aren't UTF-8 are copied as-is.
+fn copy_with<F: Fn(&str) -> String>(source: &Path, target: &Path, transform: F) -> std::io::Result<()> {
+    let bytes = std::fs::read(source)?;
+    match String::from_utf8(bytes) {
+        Ok(text) => std::fs::write(target, transform(&text)),
+        Err(err) => std::fs::write(target, err.into_bytes()),
+    }
+}
+
+/// Collects every file under `root`, skipping ignored files and [BUILD_DIRECTORIES].
+/// Hidden files such as `.gitignore` are included.
+pub fn walk_files<P: AsRef<Path>>(root: P, respect_ignore: bool) -> Result<Vec<PathBuf>, TemplateError> {
+    let mut files = Vec::new();
+    let walker = ignore::WalkBuilder::new(root.as_ref())
+        .hidden(false)
+        .require_git(false)
+        .git_ignore(respect_ignore)
+        .git_exclude(respect_ignore)
+        .git_global(respect_ignore)
+        .ignore(respect_ignore)
+        .parents(false)
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().map(|ty| ty.is_dir()).unwrap_or(false);
+            !(is_dir && entry.file_name().to_str().map(|name| BUILD_DIRECTORIES.contains(&name)).unwrap_or(false))
+        })
+        .build();
+    for entry in walker {
+        let entry = entry?;
+        if entry.file_type().map(|ty| ty.is_file()).unwrap_or(false) {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+/// Lists the templates in `templates_dir`. Directories without a valid manifest are skipped.
+pub fn list_templates<P: AsRef<Path>>(templates_dir: P) -> Vec<Template> {
+    let Ok(read_dir) = std::fs::read_dir(templates_dir.as_ref()) else {
+        return Vec::new();
+    };
+    let mut templates = read_dir.filter_map(|entry| {
+        let path = entry.ok()?.path();
+        if !path.is_dir() {
+            return None;
+        }
+        Template::load(path).ok()
+    }).collect::<Vec<_>>();
+    templates.sort_by(|lhs, rhs| lhs.manifest.name.cmp(&rhs.manifest.name));
+    templates
+}
+
+/// Converts a template name into a directory name.
+fn template_dir_name(name: &str) -> String {
+    name.trim().chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
+
+/// Copies `project` into `templates_dir`, replacing each variable's literal with its placeholder and writing the manifest.
+pub fn save_as_template<P0: AsRef<Path>, P1: AsRef<Path>>(project: P0, templates_dir: P1, manifest: &TemplateManifest) -> Result<Template, TemplateError> {
+    for (index, variable) in manifest.variables.iter().enumerate() {
+        if !is_valid_variable_name(&variable.name) {
+            return Err(TemplateError::InvalidVariable(variable.name.clone()));
+        }
+        if manifest.variables[..index].iter().any(|earlier| earlier.name == variable.name) {
+            return Err(TemplateError::DuplicateVariable(variable.name.clone()));
+        }
+    }
+    let project = project.as_ref();
+    let dir_name = template_dir_name(&manifest.name);
+    if dir_name.is_empty() {
+        return Err(TemplateError::InvalidManifest(String::from("the template name is empty")));
+    }
+    let template_path = templates_dir.as_ref().join(&dir_name);
+    if template_path.exists() {
+        return Err(TemplateError::AlreadyExists(manifest.name.clone()));
+    }
+    // Longest literals first so that a literal that contains another is replaced as a whole.
+    let mut replacements = manifest.variables.iter()
+        .filter(|variable| !variable.default.is_empty())
+        .map(|variable| (variable.default.clone(), variable.placeholder()))
+        .collect::<Vec<_>>();
+    replacements.sort_by_key(|(literal, _)| std::cmp::Reverse(literal.len()));
+    let substitute = |text: &str| {
+        replacements.iter().fold(text.to_owned(), |text, (literal, placeholder)| text.replace(literal, placeholder))
+    };
+    let files = walk_files(project, true)?;
+    std::fs::create_dir_all(&template_path)?;
+    let write = || -> Result<(), TemplateError> {
+        for source in files {
+            let rel_path = source.strip_prefix(project).expect("Walked path is not inside the project.");
+            let target = template_path.join(substitute(&rel_path.to_string_lossy()));
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            copy_with(&source, &target, substitute)?;
+        }
+        std::fs::write(template_path.join(MANIFEST_FILE), manifest.to_toml()?)?;
+        Ok(())
+    };
+    // A partial template would make saving it again fail with `AlreadyExists`.
+    if let Err(err) = write() {
+        if let Err(remove_err) = std::fs::remove_dir_all(&template_path) {
+            eprintln!("Failed to remove partially saved template {}: {remove_err}", template_path.display());
+        }
+        return Err(err);
+    }
+    Ok(Template {
+        path: template_path,
+        manifest: manifest.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_round_trip_test() -> Result<(), TemplateError> {
+        let project = tempfile::tempdir()?;
+        let templates_dir = tempfile::tempdir()?;
+        let output = tempfile::tempdir()?;
+        std::fs::create_dir_all(project.path().join("src"))?;
+        std::fs::create_dir_all(project.path().join("target/debug"))?;
+        std::fs::write(project.path().join("Cargo.toml"), "[package]\nname = \"projector\"\nauthors = [\"Erisian\"]\n")?;
+        std::fs::write(project.path().join("src/projector.rs"), "// projector by Erisian\n")?;
+        std::fs::write(project.path().join("target/debug/projector"), "binary")?;
+        std::fs::write(project.path().join("ignored.log"), "log")?;
+        std::fs::write(project.path().join(".gitignore"), "*.log\n")?;
+
+        let manifest = TemplateManifest {
+            name: String::from("Rust Binary"),
+            description: String::from("A binary crate."),
+            project_type: ProjectType::Rust,
+            variables: vec![
+                TemplateVariable::new(NAME_VARIABLE, "projector"),
+                TemplateVariable::new("author", "Erisian"),
+            ],
+        };
+        let mut duplicated = manifest.clone();
+        duplicated.variables.push(TemplateVariable::new("author", "Someone"));
+        assert!(matches!(save_as_template(project.path(), templates_dir.path(), &duplicated), Err(TemplateError::DuplicateVariable(name)) if name == "author"));
+        let template = save_as_template(project.path(), templates_dir.path(), &manifest)?;
+        assert!(template.path.join("src/{{name}}.rs").exists());
+        assert!(!template.path.join("target").exists());
+        assert!(!template.path.join("ignored.log").exists());
+        assert!(template.path.join(".gitignore").exists());
+
+        let templates = list_templates(templates_dir.path());
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].manifest, manifest);
+
+        let mut values = HashMap::new();
+        values.insert(String::from(NAME_VARIABLE), String::from("bourne"));
+        templates[0].instantiate(output.path(), &values)?;
+        let cargo_toml = std::fs::read_to_string(output.path().join("Cargo.toml"))?;
+        assert_eq!(cargo_toml, "[package]\nname = \"bourne\"\nauthors = [\"Erisian\"]\n");
+        assert!(output.path().join("src/bourne.rs").exists());
+        assert!(!output.path().join(MANIFEST_FILE).exists());
+        Ok(())
+    }
+}
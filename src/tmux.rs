@@ -0,0 +1,328 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use eframe::egui::Context;
+
+use crate::{settings::General, util::execute::background_command};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TmuxError {
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("`tmux {args}` failed:\n{stderr}")]
+    CommandFailed {
+        args: String,
+        stderr: String,
+    },
+    #[error("Invalid tmux session name pattern: {0}")]
+    InvalidSessionName(String),
+    #[error("Invalid tmux terminal command: {0}")]
+    InvalidTerminalCommand(String),
+}
+
+/// Runs `tmux` with `args` and returns its stdout.
+pub fn tmux(args: &[&str]) -> Result<String, TmuxError> {
+    let output = background_command("tmux").args(args).output()?;
+    if !output.status.success() {
+        return Err(TmuxError::CommandFailed {
+            args: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A window of the layout that new sessions are created with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxWindow {
+    pub name: String,
+    /// A tmux layout such as `tiled` or `main-vertical`.
+    pub layout: Option<String>,
+    /// The command that is typed into each pane. Empty for a shell.
+    pub panes: Vec<String>,
+}
+
+/// Parses a session layout. Each line is a window: `<name> [<layout>]: <pane command> | <pane command>`.
+///
+/// ```text
+/// editor: nvim .
+/// dev [even-horizontal]: cargo watch -x check | cargo test
+/// ```
+pub fn parse_layout(text: &str) -> Vec<TmuxWindow> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|line| {
+            let (header, panes) = line.split_once(':').unwrap_or((line, ""));
+            let (name, layout) = match header.split_once('[') {
+                Some((name, layout)) => (name, Some(layout.trim_end().trim_end_matches(']').trim().to_owned())),
+                None => (header, None),
+            };
+            TmuxWindow {
+                name: name.trim().to_owned(),
+                layout: layout.filter(|layout| !layout.is_empty()),
+                panes: panes.split('|').map(|pane| pane.trim().to_owned()).collect(),
+            }
+        })
+        .collect()
+}
+
+/// A short hash of a project's path, so that projects with the same directory name get different sessions.
+/// FNV-1a is used because it's stable across builds, unlike the standard library's hasher.
+fn path_hash(project: &Path) -> String {
+    let hash = project.as_os_str().as_encoded_bytes().iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:06x}", hash & 0xff_ffff)
+}
+
+/// The session name of a project. tmux doesn't allow `.` or `:` in session names.
+pub fn session_name(pattern: &str, project: &Path) -> Result<String, TmuxError> {
+    let name = project.file_name().and_then(|name| name.to_str()).unwrap_or("project");
+    use strfmt::strfmt;
+    let session = strfmt!(pattern, name => name.to_owned(), hash => path_hash(project))
+        .map_err(|_| TmuxError::InvalidSessionName(pattern.to_owned()))?;
+    let session = session.trim().replace(['.', ':'], "_");
+    if session.is_empty() {
+        return Err(TmuxError::InvalidSessionName(pattern.to_owned()));
+    }
+    Ok(session)
+}
+
+/// `=` makes tmux match the session name exactly instead of as a prefix.
+#[inline]
+fn exact(session: &str) -> String {
+    format!("={session}")
+}
+
+pub fn has_session(session: &str) -> bool {
+    tmux(&["has-session", "-t", &exact(session)]).is_ok()
+}
+
+/// The names of the running sessions. Empty when the tmux server isn't running.
+pub fn list_sessions() -> HashSet<String> {
+    tmux(&["list-sessions", "-F", "#{session_name}"])
+        .map(|output| output.lines().map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+pub fn kill_session(session: &str) -> Result<(), TmuxError> {
+    tmux(&["kill-session", "-t", &exact(session)])?;
+    Ok(())
+}
+
+/// Creates a detached session in `dir` with the windows and panes of `layout`. `env` is set in every pane.
+pub fn create_session(session: &str, dir: &Path, layout: &[TmuxWindow], env: &[(String, String)]) -> Result<(), TmuxError> {
+    let dir = dir.display().to_string();
+    let env_args = env.iter().flat_map(|(name, value)| [String::from("-e"), format!("{name}={value}")]).collect::<Vec<_>>();
+    let default_window = [TmuxWindow { name: String::new(), layout: None, panes: vec![String::new()] }];
+    let layout = if layout.is_empty() { &default_window[..] } else { layout };
+    let mut first_pane = None;
+    for window in layout {
+        // Every command prints the id of the pane that it created so that panes are targeted by id.
+        let mut args = match first_pane {
+            None => vec!["new-session", "-d", "-s", session],
+            Some(_) => vec!["new-window", "-t", session],
+        };
+        args.extend(["-P", "-F", "#{pane_id}", "-c", &dir]);
+        if !window.name.is_empty() {
+            args.extend(["-n", &window.name]);
+        }
+        args.extend(env_args.iter().map(String::as_str));
+        let window_pane = tmux(&args)?.trim().to_owned();
+        first_pane.get_or_insert_with(|| window_pane.clone());
+        for (index, command) in window.panes.iter().enumerate() {
+            let pane = if index == 0 {
+                window_pane.clone()
+            } else {
+                let mut args = vec!["split-window", "-t", &window_pane, "-P", "-F", "#{pane_id}", "-c", &dir];
+                args.extend(env_args.iter().map(String::as_str));
+                tmux(&args)?.trim().to_owned()
+            };
+            // Commands are typed into the shell so that the pane stays open when they exit.
+            if !command.is_empty() {
+                tmux(&["send-keys", "-t", &pane, command, "Enter"])?;
+            }
+        }
+        if let Some(window_layout) = &window.layout {
+            tmux(&["select-layout", "-t", &window_pane, window_layout])?;
+        }
+    }
+    if let Some(first_pane) = first_pane {
+        tmux(&["select-window", "-t", &first_pane])?;
+    }
+    Ok(())
+}
+
+/// Creates the project's session if it isn't running and returns the command that opens a terminal attached to it.
+pub fn prepare_terminal(general: &General, dir: &Path, env: &[(String, String)]) -> Result<String, TmuxError> {
+    let session = session_name(&general.tmux_session_name, dir)?;
+    if !has_session(&session) {
+        create_session(&session, dir, &parse_layout(&general.tmux_layout), env)?;
+    }
+    let attach = format!("tmux attach-session -t {}", shlex::try_quote(&exact(&session)).map_err(|_| TmuxError::InvalidSessionName(session.clone()))?);
+    use strfmt::strfmt;
+    strfmt!(&general.tmux_terminal_command, path => crate::util::execute::quote_path(dir), tmux => attach)
+        .map_err(|_| TmuxError::InvalidTerminalCommand(general.tmux_terminal_command.clone()))
+}
+
+#[derive(Default)]
+struct SessionList {
+    sessions: HashSet<String>,
+    /// When the sessions were listed.
+    listed: Option<Instant>,
+    /// Incremented by [TmuxSessions::invalidate], so that a list that was started before is not counted as fresh.
+    generation: u64,
+}
+
+/// The outcome of a tmux operation that ran in the background.
+pub enum TmuxEvent {
+    /// The session is running, and the terminal is opened with `command`.
+    TerminalReady {
+        command: String,
+        env: Vec<(String, String)>,
+    },
+    Failed(String),
+}
+
+/// The running tmux sessions, refreshed in the background. Sessions are also created and killed in the background,
+/// because every window and pane is another `tmux` process.
+pub struct TmuxSessions {
+    ctx: Context,
+    state: Arc<Mutex<SessionList>>,
+    sender: mpsc::Sender<TmuxEvent>,
+    receiver: mpsc::Receiver<TmuxEvent>,
+}
+
+impl TmuxSessions {
+    const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(ctx: Context) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            ctx,
+            state: Arc::new(Mutex::new(SessionList::default())),
+            sender,
+            receiver,
+        }
+    }
+
+    /// Runs `task` on a background thread, then refreshes the list of sessions.
+    fn run_in_background<F: FnOnce() -> Option<TmuxEvent> + Send + 'static>(&self, task: F) {
+        let state = self.state.clone();
+        let sender = self.sender.clone();
+        let ctx = self.ctx.clone();
+        std::thread::spawn(move || {
+            let event = task();
+            {
+                let mut state = state.lock().unwrap();
+                state.listed = None;
+                state.generation += 1;
+            }
+            if let Some(event) = event {
+                _ = sender.send(event);
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    /// Creates the project's session if it isn't running (see [prepare_terminal]). The command that opens the
+    /// terminal is received from [TmuxSessions::poll_event].
+    pub fn open_terminal(&self, general: &General, dir: &Path, env: Vec<(String, String)>) {
+        let general = general.clone();
+        let dir: PathBuf = dir.to_owned();
+        self.run_in_background(move || Some(match prepare_terminal(&general, &dir, &env) {
+            Ok(command) => TmuxEvent::TerminalReady { command, env },
+            Err(err) => TmuxEvent::Failed(format!("There was an error executing Open Terminal Here shell command: {err}")),
+        }));
+    }
+
+    pub fn kill(&self, session: &str) {
+        let session = session.to_owned();
+        self.run_in_background(move || {
+            kill_session(&session).err().map(|err| TmuxEvent::Failed(format!("Failed to kill tmux session: {err}")))
+        });
+    }
+
+    /// The next operation that finished since the last call.
+    pub fn poll_event(&self) -> Option<TmuxEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Whether a session is running. The list is refreshed on a background thread when it's out of date.
+    pub fn is_running(&self, session: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let stale = state.listed.is_none_or(|listed| listed.elapsed() >= Self::REFRESH_INTERVAL);
+        if stale {
+            // Marks the list as fresh until the refresh finishes so that only one refresh runs at a time.
+            state.listed = Some(Instant::now());
+            let generation = state.generation;
+            let shared = self.state.clone();
+            let ctx = self.ctx.clone();
+            std::thread::spawn(move || {
+                let sessions = list_sessions();
+                let mut state = shared.lock().unwrap();
+                state.sessions = sessions;
+                if state.generation == generation {
+                    state.listed = Some(Instant::now());
+                }
+                drop(state);
+                ctx.request_repaint();
+            });
+        }
+        state.sessions.contains(session)
+    }
+
+    /// Forces the list to be refreshed the next time it's read.
+    pub fn invalidate(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.listed = None;
+        state.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_test() {
+        let layout = parse_layout("editor: nvim .\n# comment\n\ndev [even-horizontal]: cargo watch -x check | cargo test\nshell\n");
+        assert_eq!(layout, vec![
+            TmuxWindow { name: String::from("editor"), layout: None, panes: vec![String::from("nvim .")] },
+            TmuxWindow {
+                name: String::from("dev"),
+                layout: Some(String::from("even-horizontal")),
+                panes: vec![String::from("cargo watch -x check"), String::from("cargo test")],
+            },
+            TmuxWindow { name: String::from("shell"), layout: None, panes: vec![String::new()] },
+        ]);
+        assert_eq!(session_name("{name}", Path::new("/code/my.project")).unwrap(), "my_project");
+        let work = session_name("{name}-{hash}", Path::new("/work/app")).unwrap();
+        let personal = session_name("{name}-{hash}", Path::new("/personal/app")).unwrap();
+        assert!(work.starts_with("app-") && work.len() == "app-".len() + 6);
+        assert_ne!(work, personal);
+        assert_eq!(work, session_name("{name}-{hash}", Path::new("/work/app")).unwrap());
+        assert_eq!(session_name("proj-{name}", Path::new("/code/app")).unwrap(), "proj-app");
+        assert!(session_name("{nope}", Path::new("/code/app")).is_err());
+    }
+
+    #[test]
+    fn background_test() {
+        let sessions = TmuxSessions::new(Context::default());
+        // Fails whether or not tmux is installed, since the session doesn't exist.
+        sessions.kill("projector-test-no-such-session");
+        let mut event = None;
+        for _ in 0..100 {
+            event = sessions.poll_event();
+            if event.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(matches!(event, Some(TmuxEvent::Failed(report)) if report.starts_with("Failed to kill tmux session")));
+    }
+}
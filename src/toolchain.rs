@@ -0,0 +1,411 @@
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use eframe::egui::Context;
+
+use crate::{
+    util::{background::BackgroundCache, execute::background_command},
+    venv::detect_venv,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ToolchainKind {
+    Rust,
+    Node,
+    Python,
+}
+
+impl ToolchainKind {
+    pub const fn text(self) -> &'static str {
+        match self {
+            ToolchainKind::Rust => "Rust",
+            ToolchainKind::Node => "Node",
+            ToolchainKind::Python => "Python",
+        }
+    }
+}
+
+/// The syntax of a version requirement. They differ in how partial versions compare: npm's `>3.9` means `>=3.10.0`,
+/// while PEP 440's `>3.9` accepts `3.9.1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequirementSyntax {
+    /// npm and Cargo. A partial version stands for every version that starts with it.
+    Npm,
+    /// Python. A partial version is padded with zeros, unless it ends with a `.*` wildcard.
+    Pep440,
+}
+
+/// A toolchain version that a project pins or requires.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ToolchainPin {
+    pub kind: ToolchainKind,
+    /// As written in the file, such as `1.75`, `nightly-2024-01-01`, `^18.0.0`, or `>=3.9,<3.13`.
+    pub requirement: String,
+    /// The file (and key) that the requirement was read from, such as `Cargo.toml (rust-version)`.
+    pub source: String,
+    /// The version of the local toolchain. `None` when the toolchain isn't installed.
+    pub installed: Option<String>,
+    /// `None` when the requirement can't be checked (`lts/*`, unknown syntax) or the toolchain isn't installed.
+    pub satisfied: Option<bool>,
+}
+
+impl ToolchainPin {
+    #[inline]
+    pub fn is_unsatisfied(&self) -> bool {
+        self.satisfied == Some(false) || (self.installed.is_none() && self.satisfied.is_none())
+    }
+}
+
+/// The numeric components of a version (`v18.17.0`, `3.12.1`, `1.75.0-nightly`, `3.11.*`). Parsing stops at the
+/// first component that isn't a number. `x`, `X` and `*` components are wildcards and end the version.
+pub fn parse_version(text: &str) -> Vec<u64> {
+    let text = text.trim().trim_start_matches(['v', 'V', '=']);
+    let mut components = Vec::new();
+    for part in text.split('.') {
+        let digits = part.chars().take_while(char::is_ascii_digit).collect::<String>();
+        let Ok(number) = digits.parse() else {
+            break;
+        };
+        components.push(number);
+        if digits.len() != part.len() {
+            break;
+        }
+    }
+    components
+}
+
+fn compare(lhs: &[u64], rhs: &[u64]) -> Ordering {
+    let len = lhs.len().max(rhs.len());
+    (0..len)
+        .map(|index| lhs.get(index).copied().unwrap_or(0).cmp(&rhs.get(index).copied().unwrap_or(0)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[inline]
+fn has_prefix(version: &[u64], prefix: &[u64]) -> bool {
+    version.len() >= prefix.len() && version[..prefix.len()] == *prefix
+}
+
+/// Checks a single comparator, such as `>=18`, `^1.2.3`, `~=3.10`, or `3.11.*`.
+fn satisfies_comparator(version: &[u64], comparator: &str, syntax: RequirementSyntax) -> Option<bool> {
+    const OPERATORS: [&str; 10] = ["~=", "==", "!=", ">=", "<=", ">", "<", "^", "~", "="];
+    let operator = OPERATORS.iter().find(|operator| comparator.starts_with(**operator)).copied().unwrap_or("");
+    let operand = comparator[operator.len()..].trim();
+    if operand.is_empty() || operand == "*" || operand.eq_ignore_ascii_case("x") {
+        return Some(true);
+    }
+    let required = parse_version(operand);
+    if required.is_empty() {
+        return None;
+    }
+    let ordering = compare(version, &required);
+    // Whether `required` stands for every version that starts with it.
+    let partial = syntax == RequirementSyntax::Npm || operand.ends_with(".*");
+    Some(match operator {
+        ">=" => ordering.is_ge(),
+        ">" => ordering.is_gt() && !(partial && has_prefix(version, &required)),
+        "<=" => ordering.is_le() || (partial && has_prefix(version, &required)),
+        "<" => ordering.is_lt(),
+        "!=" if partial => !has_prefix(version, &required),
+        "!=" => ordering.is_ne(),
+        "==" if !partial => ordering.is_eq(),
+        "^" => {
+            // The first non-zero component must match.
+            let significant = required.iter().position(|&component| component != 0).map_or(required.len(), |index| index + 1);
+            ordering.is_ge() && has_prefix(version, &required[..significant.min(required.len())])
+        }
+        "~" => ordering.is_ge() && has_prefix(version, &required[..required.len().min(2)]),
+        "~=" => ordering.is_ge() && has_prefix(version, &required[..required.len().saturating_sub(1).max(1)]),
+        // `==3.11.*`, `=18`, and `18` match every version that starts with the operand.
+        _ => has_prefix(version, &required),
+    })
+}
+
+/// Checks a version requirement, in npm (`>=18 <21 || ^16.14`, `16 - 18`) or PEP 440 (`>=3.9,<3.13`) syntax.
+/// Returns `None` when the requirement can't be understood.
+pub fn satisfies(version: &str, requirement: &str, syntax: RequirementSyntax) -> Option<bool> {
+    let version = parse_version(version);
+    if version.is_empty() {
+        return None;
+    }
+    let mut any = false;
+    for alternative in requirement.split("||") {
+        let alternative = alternative.trim();
+        if let Some((low, high)) = alternative.split_once(" - ") {
+            let low = parse_version(low);
+            let high = parse_version(high);
+            if low.is_empty() || high.is_empty() {
+                return None;
+            }
+            any |= compare(&version, &low).is_ge() && (compare(&version, &high).is_le() || has_prefix(&version, &high));
+            continue;
+        }
+        // `>= 18` is one comparator.
+        let mut normalized = alternative.replace(',', " ");
+        for operator in ["~=", "==", "!=", ">=", "<=", ">", "<", "^", "~", "="] {
+            normalized = normalized.replace(&format!("{operator} "), operator);
+        }
+        let mut all = true;
+        for comparator in normalized.split_whitespace() {
+            all &= satisfies_comparator(&version, comparator, syntax)?;
+        }
+        any |= all;
+    }
+    Some(any)
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    Some(line.to_owned())
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn toml_str<'a>(table: &'a toml::Table, keys: &[&str]) -> Option<&'a str> {
+    let (last, path) = keys.split_last()?;
+    let table = path.iter().try_fold(table, |table, key| table.get(*key)?.as_table())?;
+    table.get(*last)?.as_str()
+}
+
+/// Runs `program --version` in `dir` and returns the first version-looking word of the output.
+fn installed_version(dir: &Path, program: &Path) -> Option<String> {
+    let output = background_command(program)
+        .arg("--version")
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(if output.stdout.is_empty() { &output.stderr } else { &output.stdout }).into_owned();
+    text.split_whitespace()
+        .find(|word| !parse_version(word).is_empty())
+        .map(str::to_owned)
+}
+
+/// Rust pins: `rust-toolchain.toml` or `rust-toolchain` (a channel), and `rust-version` in `Cargo.toml` (a minimum).
+pub fn rust_pins(dir: &Path) -> Vec<(String, String)> {
+    let mut pins = Vec::new();
+    let channel = read_toml(&dir.join("rust-toolchain.toml"))
+        .and_then(|toolchain| toml_str(&toolchain, &["toolchain", "channel"]).map(str::to_owned))
+        .map(|channel| (channel, String::from("rust-toolchain.toml")))
+        .or_else(|| {
+            let path = dir.join("rust-toolchain");
+            // The legacy file is either a bare channel or TOML.
+            let channel = read_toml(&path)
+                .and_then(|toolchain| toml_str(&toolchain, &["toolchain", "channel"]).map(str::to_owned))
+                .or_else(|| read_trimmed(&path))?;
+            Some((channel, String::from("rust-toolchain")))
+        });
+    pins.extend(channel);
+    if let Some(manifest) = read_toml(&dir.join("Cargo.toml")) {
+        let rust_version = toml_str(&manifest, &["package", "rust-version"])
+            .or_else(|| toml_str(&manifest, &["workspace", "package", "rust-version"]));
+        if let Some(rust_version) = rust_version {
+            pins.push((format!(">={rust_version}"), String::from("Cargo.toml (rust-version)")));
+        }
+    }
+    pins
+}
+
+/// Node pins: `.nvmrc`, `.node-version`, and `engines.node` in `package.json`.
+pub fn node_pins(dir: &Path) -> Vec<(String, String)> {
+    let mut pins = Vec::new();
+    for file in [".nvmrc", ".node-version"] {
+        if let Some(version) = read_trimmed(&dir.join(file)) {
+            pins.push((version, file.to_owned()));
+        }
+    }
+    let engines = std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+        .and_then(|manifest| manifest.get("engines")?.get("node")?.as_str().map(str::to_owned));
+    if let Some(engines) = engines {
+        pins.push((engines, String::from("package.json (engines.node)")));
+    }
+    pins
+}
+
+/// Python pins: `.python-version`, and `requires-python` (or Poetry's `python` dependency) in `pyproject.toml`.
+pub fn python_pins(dir: &Path) -> Vec<(String, String)> {
+    let mut pins = Vec::new();
+    if let Some(version) = read_trimmed(&dir.join(".python-version")) {
+        pins.push((version, String::from(".python-version")));
+    }
+    if let Some(pyproject) = read_toml(&dir.join("pyproject.toml")) {
+        let requires = toml_str(&pyproject, &["project", "requires-python"])
+            .map(|requires| (requires, "requires-python"))
+            .or_else(|| toml_str(&pyproject, &["tool", "poetry", "dependencies", "python"]).map(|requires| (requires, "tool.poetry.dependencies.python")));
+        if let Some((requires, key)) = requires {
+            pins.push((requires.to_owned(), format!("pyproject.toml ({key})")));
+        }
+    }
+    pins
+}
+
+/// Checks a Rust requirement. Channels (`stable`, `beta`, `nightly-2024-01-01`) are checked against `rustc --version`.
+fn rust_satisfies(installed: &str, version_line: &str, requirement: &str) -> Option<bool> {
+    let channel = requirement.split('-').next().unwrap_or(requirement);
+    match channel {
+        "stable" => Some(!version_line.contains("-beta") && !version_line.contains("-nightly")),
+        "beta" => Some(version_line.contains("-beta")),
+        "nightly" => Some(version_line.contains("-nightly")),
+        _ => satisfies(installed, requirement, RequirementSyntax::Npm),
+    }
+}
+
+/// Reads the toolchain requirements of the project in `dir` and checks them against the local toolchains.
+/// The check is offline: `rustc`, `node`, and `python` are run with `--version`. `rustup` is not allowed to
+/// install a missing toolchain.
+pub fn detect_toolchains<P: AsRef<Path>>(dir: P) -> Vec<ToolchainPin> {
+    fn inner(dir: &Path) -> Vec<ToolchainPin> {
+        let mut pins = Vec::new();
+        let rust = rust_pins(dir);
+        if !rust.is_empty() {
+            // rustup picks the toolchain from `rust-toolchain.toml` in the working directory.
+            let version_line = background_command("rustc")
+                .arg("--version")
+                .current_dir(dir)
+                .env("RUSTUP_AUTO_INSTALL", "0")
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned());
+            let installed = version_line.as_deref().and_then(|line| line.split_whitespace().nth(1)).map(str::to_owned);
+            for (requirement, source) in rust {
+                let satisfied = match (&installed, &version_line) {
+                    (Some(installed), Some(line)) => rust_satisfies(installed, line, &requirement),
+                    _ => None,
+                };
+                pins.push(ToolchainPin { kind: ToolchainKind::Rust, requirement, source, installed: installed.clone(), satisfied });
+            }
+        }
+        let node = node_pins(dir);
+        if !node.is_empty() {
+            let installed = installed_version(dir, Path::new("node"));
+            for (requirement, source) in node {
+                let satisfied = installed.as_deref().and_then(|installed| satisfies(installed, &requirement, RequirementSyntax::Npm));
+                pins.push(ToolchainPin { kind: ToolchainKind::Node, requirement, source, installed: installed.clone(), satisfied });
+            }
+        }
+        let python = python_pins(dir);
+        if !python.is_empty() {
+            let interpreter = detect_venv(dir).map_or_else(
+                || PathBuf::from(if cfg!(target_os = "windows") { "python" } else { "python3" }),
+                |venv| venv.python(),
+            );
+            let installed = installed_version(dir, &interpreter);
+            for (requirement, source) in python {
+                let satisfied = installed.as_deref().and_then(|installed| satisfies(installed, &requirement, RequirementSyntax::Pep440));
+                pins.push(ToolchainPin { kind: ToolchainKind::Python, requirement, source, installed: installed.clone(), satisfied });
+            }
+        }
+        pins
+    }
+    inner(dir.as_ref())
+}
+
+/// Detects and checks the toolchains of projects in the background.
+pub struct ToolchainCache {
+    cache: BackgroundCache<Arc<Vec<ToolchainPin>>>,
+}
+
+impl ToolchainCache {
+    /// How long a check is used before it runs again.
+    const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            cache: BackgroundCache::new("toolchain-detection", ctx, Self::REFRESH_INTERVAL, Default::default(), |path| {
+                let pins = detect_toolchains(path);
+                (!pins.is_empty()).then(|| Arc::new(pins))
+            }),
+        }
+    }
+
+    /// Returns the last known toolchains of a project and queues a check if it is out of date.
+    #[inline]
+    pub fn get<P: AsRef<Path>>(&self, dir: P) -> Option<Arc<Vec<ToolchainPin>>> {
+        self.cache.get(dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requirement_test() {
+        assert_eq!(parse_version("v18.17.0"), vec![18, 17, 0]);
+        assert_eq!(parse_version("1.75.0-nightly"), vec![1, 75, 0]);
+        assert_eq!(parse_version("3.11.*"), vec![3, 11]);
+        assert_eq!(parse_version("lts/*"), Vec::<u64>::new());
+
+        let check = |version: &str, requirement: &str| satisfies(version, requirement, RequirementSyntax::Npm);
+        let check_pep440 = |version: &str, requirement: &str| satisfies(version, requirement, RequirementSyntax::Pep440);
+        // npm
+        assert_eq!(check("18.17.0", ">=18 <21"), Some(true));
+        assert_eq!(check("21.0.0", ">=18 <21"), Some(false));
+        assert_eq!(check("16.20.1", "^18.0.0 || ^16.14"), Some(true));
+        assert_eq!(check("17.0.0", "^18.0.0 || ^16.14"), Some(false));
+        assert_eq!(check("0.2.5", "^0.2.3"), Some(true));
+        assert_eq!(check("0.3.0", "^0.2.3"), Some(false));
+        assert_eq!(check("16.14.9", "~16.14"), Some(true));
+        assert_eq!(check("16.15.0", "~16.14"), Some(false));
+        assert_eq!(check("18.4.0", "18.x"), Some(true));
+        assert_eq!(check("18.4.0", "16 - 18"), Some(true));
+        assert_eq!(check("19.0.0", "16 - 18"), Some(false));
+        assert_eq!(check("20.1.0", "v20"), Some(true));
+        assert_eq!(check("20.1.0", "lts/*"), None);
+        assert_eq!(check("3.9.1", ">3.9"), Some(false));
+        assert_eq!(check("3.9.5", "<=3.9"), Some(true));
+        // PEP 440
+        assert_eq!(check_pep440("3.12.1", ">=3.9,<3.13"), Some(true));
+        assert_eq!(check_pep440("3.13.0", ">=3.9, <3.13"), Some(false));
+        assert_eq!(check_pep440("3.10.4", "~=3.10"), Some(true));
+        assert_eq!(check_pep440("4.0.0", "~=3.10"), Some(false));
+        assert_eq!(check_pep440("3.11.2", "==3.11.*"), Some(true));
+        assert_eq!(check_pep440("3.9.1", ">=3.8,!=3.9.1"), Some(false));
+        assert_eq!(check_pep440("3.9.1", ">3.9"), Some(true));
+        assert_eq!(check_pep440("3.9.5", "<=3.9"), Some(false));
+        assert_eq!(check_pep440("3.11.2", "==3.11"), Some(false));
+        assert_eq!(check_pep440("3.11.0", "==3.11"), Some(true));
+        assert_eq!(check_pep440("3.9.5", "!=3.9.*"), Some(false));
+        assert_eq!(check_pep440("3.12.0", "^3.9"), Some(true));
+        assert_eq!(check_pep440("3.12.0", "3.12"), Some(true));
+        // Rust
+        assert_eq!(check("1.80.0", ">=1.75"), Some(true));
+        assert_eq!(check("1.70.0", ">=1.75"), Some(false));
+        assert_eq!(rust_satisfies("1.80.0", "rustc 1.80.0 (051478957 2024-07-21)", "stable"), Some(true));
+        assert_eq!(rust_satisfies("1.82.0", "rustc 1.82.0-nightly (abc 2024-08-01)", "nightly-2024-08-01"), Some(true));
+        assert_eq!(rust_satisfies("1.80.0", "rustc 1.80.0 (051478957 2024-07-21)", "1.79"), Some(false));
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        std::fs::write(dir.path().join("rust-toolchain.toml"), "[toolchain]\nchannel = \"1.75.0\"\n").unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"a\"\nrust-version = \"1.70\"\n").unwrap();
+        std::fs::write(dir.path().join(".nvmrc"), "v18.17.0\n").unwrap();
+        std::fs::write(dir.path().join("package.json"), r#"{ "engines": { "node": ">=18" } }"#).unwrap();
+        std::fs::write(dir.path().join(".python-version"), "3.11\n").unwrap();
+        std::fs::write(dir.path().join("pyproject.toml"), "[tool.poetry.dependencies]\npython = \"^3.9\"\n").unwrap();
+        assert_eq!(rust_pins(dir.path()), vec![
+            (String::from("1.75.0"), String::from("rust-toolchain.toml")),
+            (String::from(">=1.70"), String::from("Cargo.toml (rust-version)")),
+        ]);
+        assert_eq!(node_pins(dir.path()), vec![
+            (String::from("v18.17.0"), String::from(".nvmrc")),
+            (String::from(">=18"), String::from("package.json (engines.node)")),
+        ]);
+        assert_eq!(python_pins(dir.path()), vec![
+            (String::from("3.11"), String::from(".python-version")),
+            (String::from("^3.9"), String::from("pyproject.toml (tool.poetry.dependencies.python)")),
+        ]);
+    }
+}
@@ -0,0 +1,387 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolKind {
+    Editor,
+    /// An editor that runs inside of a terminal.
+    TerminalEditor,
+    Terminal,
+}
+
+/// A program that Projector knows how to open a project with.
+#[derive(Debug)]
+pub struct ToolPreset {
+    pub name: &'static str,
+    pub kind: ToolKind,
+    /// Executable names that are searched for on the `PATH`, in order.
+    pub programs: &'static [&'static str],
+    /// Install locations that are checked when the program isn't on the `PATH`.
+    /// `~` is the home directory, `%VAR%` is an environment variable, and `{program}` is each of `programs`.
+    pub locations: &'static [&'static str],
+    /// The command template. `{program}` is replaced with the detected program.
+    /// Editors in a terminal use this as the `{command}` of the terminal's `run_template`.
+    pub template: &'static str,
+    /// Terminals: the template that runs `{command}` in the terminal.
+    pub run_template: Option<&'static str>,
+}
+
+const JETBRAINS_TOOLBOX_SCRIPTS: &[&str] = &[
+    "~/.local/share/JetBrains/Toolbox/scripts/{program}",
+    "~/Library/Application Support/JetBrains/Toolbox/scripts/{program}",
+    "%LOCALAPPDATA%\\JetBrains\\Toolbox\\scripts\\{program}.cmd",
+];
+
+const fn jetbrains(name: &'static str, programs: &'static [&'static str]) -> ToolPreset {
+    ToolPreset {
+        name,
+        kind: ToolKind::Editor,
+        programs,
+        locations: JETBRAINS_TOOLBOX_SCRIPTS,
+        template: "{program} {path}",
+        run_template: None,
+    }
+}
+
+/// Known editors and terminals, in order of preference.
+pub const PRESETS: &[ToolPreset] = &[
+    ToolPreset {
+        name: "Visual Studio Code",
+        kind: ToolKind::Editor,
+        programs: &["code"],
+        locations: &[
+            "%LOCALAPPDATA%\\Programs\\Microsoft VS Code\\bin\\code.cmd",
+            "%ProgramFiles%\\Microsoft VS Code\\bin\\code.cmd",
+            "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
+            "/usr/share/code/bin/code",
+            "/snap/bin/code",
+        ],
+        template: "{program} {path}",
+        run_template: None,
+    },
+    ToolPreset {
+        name: "VSCodium",
+        kind: ToolKind::Editor,
+        programs: &["codium"],
+        locations: &[
+            "%LOCALAPPDATA%\\Programs\\VSCodium\\bin\\codium.cmd",
+            "/Applications/VSCodium.app/Contents/Resources/app/bin/codium",
+            "/usr/share/codium/bin/codium",
+            "/snap/bin/codium",
+        ],
+        template: "{program} {path}",
+        run_template: None,
+    },
+    ToolPreset {
+        name: "Zed",
+        kind: ToolKind::Editor,
+        // Some distributions package Zed as `zeditor`.
+        programs: &["zed", "zeditor"],
+        locations: &[
+            "~/.local/bin/{program}",
+            "/Applications/Zed.app/Contents/MacOS/cli",
+        ],
+        template: "{program} {path}",
+        run_template: None,
+    },
+    ToolPreset {
+        name: "Sublime Text",
+        kind: ToolKind::Editor,
+        programs: &["subl"],
+        locations: &[
+            "%ProgramFiles%\\Sublime Text\\subl.exe",
+            "/Applications/Sublime Text.app/Contents/SharedSupport/bin/subl",
+            "/opt/sublime_text/sublime_text",
+        ],
+        template: "{program} {path}",
+        run_template: None,
+    },
+    jetbrains("IntelliJ IDEA", &["idea", "idea.sh"]),
+    jetbrains("RustRover", &["rustrover", "rustrover.sh"]),
+    jetbrains("PyCharm", &["pycharm", "pycharm.sh"]),
+    jetbrains("WebStorm", &["webstorm", "webstorm.sh"]),
+    jetbrains("CLion", &["clion", "clion.sh"]),
+    ToolPreset {
+        name: "Emacs",
+        kind: ToolKind::Editor,
+        programs: &["emacs"],
+        locations: &["/Applications/Emacs.app/Contents/MacOS/Emacs"],
+        template: "{program} {path}",
+        run_template: None,
+    },
+    ToolPreset {
+        name: "Neovim",
+        kind: ToolKind::TerminalEditor,
+        programs: &["nvim"],
+        locations: &["/opt/homebrew/bin/nvim"],
+        template: "{program} {path}",
+        run_template: None,
+    },
+    ToolPreset {
+        name: "Windows Terminal",
+        kind: ToolKind::Terminal,
+        programs: &["wt"],
+        locations: &["%LOCALAPPDATA%\\Microsoft\\WindowsApps\\wt.exe"],
+        template: "{program} --startingDirectory {path}",
+        run_template: Some("{program} --startingDirectory {path} {command}"),
+    },
+    ToolPreset {
+        name: "kitty",
+        kind: ToolKind::Terminal,
+        programs: &["kitty"],
+        locations: &["~/.local/kitty.app/bin/kitty", "/Applications/kitty.app/Contents/MacOS/kitty"],
+        template: "{program} --directory {path}",
+        run_template: Some("{program} --directory {path} {command}"),
+    },
+    ToolPreset {
+        name: "Alacritty",
+        kind: ToolKind::Terminal,
+        programs: &["alacritty"],
+        locations: &["/Applications/Alacritty.app/Contents/MacOS/alacritty"],
+        template: "{program} --working-directory {path}",
+        run_template: Some("{program} --working-directory {path} -e {command}"),
+    },
+    ToolPreset {
+        name: "WezTerm",
+        kind: ToolKind::Terminal,
+        programs: &["wezterm"],
+        locations: &["%ProgramFiles%\\WezTerm\\wezterm.exe", "/Applications/WezTerm.app/Contents/MacOS/wezterm"],
+        template: "{program} start --cwd {path}",
+        run_template: Some("{program} start --cwd {path} -- {command}"),
+    },
+    ToolPreset {
+        name: "GNOME Terminal",
+        kind: ToolKind::Terminal,
+        programs: &["gnome-terminal"],
+        locations: &[],
+        template: "{program} --working-directory={path}",
+        run_template: Some("{program} --working-directory={path} -- {command}"),
+    },
+    ToolPreset {
+        name: "Konsole",
+        kind: ToolKind::Terminal,
+        programs: &["konsole"],
+        locations: &[],
+        template: "{program} --workdir {path}",
+        run_template: Some("{program} --workdir {path} -e {command}"),
+    },
+    ToolPreset {
+        name: "xterm",
+        kind: ToolKind::Terminal,
+        programs: &["xterm"],
+        locations: &[],
+        // xterm has no option for the working directory.
+        template: "sh -c 'cd {path} && exec {program}'",
+        run_template: Some("sh -c 'cd {path} && exec {program} -e {command}'"),
+    },
+    ToolPreset {
+        name: "Terminal",
+        kind: ToolKind::Terminal,
+        programs: &[],
+        locations: &["/System/Applications/Utilities/Terminal.app"],
+        template: "open -a Terminal {path}",
+        run_template: None,
+    },
+];
+
+/// A preset whose program was found.
+#[derive(Debug, Clone)]
+pub struct DetectedTool {
+    pub preset: &'static ToolPreset,
+    /// The program as it appears in commands: the name when it is on the `PATH`, otherwise the quoted path.
+    pub program: String,
+}
+
+/// A command for a settings field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandPreset {
+    pub name: String,
+    pub command: String,
+}
+
+#[cfg(target_os = "windows")]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Finds `program` in `dirs`. On Windows, the extensions in `PATHEXT` are tried as well.
+pub fn find_in_dirs<I: IntoIterator<Item = PathBuf>>(program: &str, dirs: I) -> Option<PathBuf> {
+    let extensions = if cfg!(target_os = "windows") {
+        let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| String::from(".COM;.EXE;.BAT;.CMD"));
+        std::iter::once(String::new())
+            .chain(pathext.split(';').filter(|ext| !ext.is_empty()).map(str::to_lowercase))
+            .collect::<Vec<_>>()
+    } else {
+        vec![String::new()]
+    };
+    dirs.into_iter().find_map(|dir| {
+        extensions.iter()
+            .map(|ext| dir.join(format!("{program}{ext}")))
+            .find(|path| is_executable(path))
+    })
+}
+
+/// Finds `program` on the `PATH`.
+pub fn find_on_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    find_in_dirs(program, std::env::split_paths(&path))
+}
+
+/// Expands `~` and `%VAR%` in an install location. Returns `None` if a variable isn't set.
+fn expand_location(location: &str, program: &str) -> Option<PathBuf> {
+    let mut location = location.replace("{program}", program);
+    if let Some(rest) = location.strip_prefix("~/") {
+        let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
+        return Some(home.join(rest));
+    }
+    while let Some(start) = location.find('%') {
+        let end = start + 1 + location[start + 1..].find('%')?;
+        let value = std::env::var(&location[start + 1..end]).ok()?;
+        location.replace_range(start..=end, &value);
+    }
+    Some(PathBuf::from(location))
+}
+
+/// Escapes `{` and `}` so that a path can be put in a command template.
+fn escape_template(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Finds the program of a preset. Returns the text that the program is run with in commands.
+fn detect_program(preset: &ToolPreset) -> Option<String> {
+    if let Some(program) = preset.programs.iter().find(|program| find_on_path(program).is_some()) {
+        return Some((*program).to_owned());
+    }
+    let programs = if preset.programs.is_empty() { &[""][..] } else { preset.programs };
+    programs.iter()
+        .flat_map(|program| preset.locations.iter().filter_map(|location| expand_location(location, program)))
+        .find(|path| path.exists())
+        .map(|path| escape_template(&format!(r#""{}""#, path.display())))
+}
+
+/// Probes the `PATH` and standard install locations for every preset.
+pub fn detect_tools() -> Vec<DetectedTool> {
+    PRESETS.iter()
+        .filter_map(|preset| Some(DetectedTool { preset, program: detect_program(preset)? }))
+        .collect()
+}
+
+/// The tools that are installed. Detected the first time this is called.
+pub fn detected_tools() -> &'static [DetectedTool] {
+    static DETECTED: OnceLock<Vec<DetectedTool>> = OnceLock::new();
+    DETECTED.get_or_init(detect_tools)
+}
+
+impl DetectedTool {
+    /// The command that opens the tool.
+    pub fn command(&self) -> String {
+        self.preset.template.replace("{program}", &self.program)
+    }
+
+    /// Terminals: the command that runs `command` in the terminal.
+    pub fn run_command(&self, command: &str) -> Option<String> {
+        self.preset.run_template.map(|template| template.replace("{program}", &self.program).replace("{command}", command))
+    }
+}
+
+fn terminals(tools: &[DetectedTool]) -> impl Iterator<Item = &DetectedTool> {
+    tools.iter().filter(|tool| tool.preset.kind == ToolKind::Terminal)
+}
+
+/// Presets for the editor command. Editors that run in a terminal are paired with each terminal.
+pub fn editor_presets(tools: &[DetectedTool]) -> Vec<CommandPreset> {
+    let mut presets = Vec::new();
+    for tool in tools {
+        match tool.preset.kind {
+            ToolKind::Editor => presets.push(CommandPreset { name: tool.preset.name.to_owned(), command: tool.command() }),
+            ToolKind::TerminalEditor => presets.extend(terminals(tools).filter_map(|terminal| Some(CommandPreset {
+                name: format!("{} ({})", tool.preset.name, terminal.preset.name),
+                command: terminal.run_command(&tool.command())?,
+            }))),
+            ToolKind::Terminal => (),
+        }
+    }
+    presets
+}
+
+/// Presets for the shell command.
+pub fn terminal_presets(tools: &[DetectedTool]) -> Vec<CommandPreset> {
+    terminals(tools)
+        .map(|tool| CommandPreset { name: tool.preset.name.to_owned(), command: tool.command() })
+        .collect()
+}
+
+/// Presets for the tmux terminal command, which runs `{tmux}` in a terminal.
+pub fn tmux_terminal_presets(tools: &[DetectedTool]) -> Vec<CommandPreset> {
+    terminals(tools)
+        .filter_map(|tool| Some(CommandPreset { name: tool.preset.name.to_owned(), command: tool.run_command("{tmux}")? }))
+        .collect()
+}
+
+/// The default editor command: the first installed editor.
+pub fn default_editor_command() -> Option<String> {
+    editor_presets(detected_tools()).into_iter().next().map(|preset| preset.command)
+}
+
+/// The default shell command: the first installed terminal.
+pub fn default_terminal_command() -> Option<String> {
+    terminal_presets(detected_tools()).into_iter().next().map(|preset| preset.command)
+}
+
+/// The default tmux terminal command: the first installed terminal that can run a command.
+pub fn default_tmux_terminal_command() -> Option<String> {
+    tmux_terminal_presets(detected_tools()).into_iter().next().map(|preset| preset.command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str) -> &'static ToolPreset {
+        PRESETS.iter().find(|preset| preset.name == name).unwrap()
+    }
+
+    #[test]
+    fn presets_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        let program = dir.path().join(if cfg!(target_os = "windows") { "tool.exe" } else { "tool" });
+        std::fs::write(&program, "").unwrap();
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(find_in_dirs("tool", [dir.path().to_path_buf()]), None);
+            std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        assert_eq!(find_in_dirs("tool", [dir.path().join("missing"), dir.path().to_path_buf()]), Some(program));
+        assert_eq!(find_in_dirs("missing", [dir.path().to_path_buf()]), None);
+
+        assert_eq!(expand_location("/opt/{program}/bin", "nvim"), Some(PathBuf::from("/opt/nvim/bin")));
+        assert_eq!(expand_location("%PROJECTOR_TEST_UNSET_VAR%\\x", "nvim"), None);
+
+        let tools = vec![
+            DetectedTool { preset: preset("Zed"), program: String::from("zeditor") },
+            DetectedTool { preset: preset("Neovim"), program: String::from("nvim") },
+            DetectedTool { preset: preset("kitty"), program: String::from(r#""/opt/kitty {{1}}/kitty""#) },
+            DetectedTool { preset: preset("Terminal"), program: String::from(r#""/System/Applications/Utilities/Terminal.app""#) },
+        ];
+        assert_eq!(editor_presets(&tools), vec![
+            CommandPreset { name: String::from("Zed"), command: String::from("zeditor {path}") },
+            CommandPreset { name: String::from("Neovim (kitty)"), command: String::from(r#""/opt/kitty {{1}}/kitty" --directory {path} nvim {path}"#) },
+        ]);
+        assert_eq!(terminal_presets(&tools), vec![
+            CommandPreset { name: String::from("kitty"), command: String::from(r#""/opt/kitty {{1}}/kitty" --directory {path}"#) },
+            CommandPreset { name: String::from("Terminal"), command: String::from("open -a Terminal {path}") },
+        ]);
+        assert_eq!(tmux_terminal_presets(&tools), vec![
+            CommandPreset { name: String::from("kitty"), command: String::from(r#""/opt/kitty {{1}}/kitty" --directory {path} {tmux}"#) },
+        ]);
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use eframe::egui::Context;
+
+struct Entry<V> {
+    /// `None` when the computation had no result.
+    value: Option<V>,
+    /// Whether `value` was loaded or computed. An unknown entry is queued but hasn't been computed yet.
+    known: bool,
+    /// `None` for entries that were loaded from disk or invalidated and haven't been refreshed yet.
+    refreshed: Option<Instant>,
+    pending: bool,
+    /// Incremented by [BackgroundCache::invalidate]. A value that was computed for an older generation may already be
+    /// out of date, so it doesn't count as a refresh.
+    generation: u64,
+}
+
+impl<V> Entry<V> {
+    const fn new(value: Option<V>) -> Self {
+        Self { known: value.is_some(), value, refreshed: None, pending: false, generation: 0 }
+    }
+}
+
+/// Computes a value per path on a background thread so that the UI never waits on the file system or external programs.
+/// `get` returns the last known value immediately and queues a refresh when it is out of date.
+pub struct BackgroundCache<V> {
+    entries: Arc<Mutex<HashMap<PathBuf, Entry<V>>>>,
+    sender: mpsc::Sender<(PathBuf, u64)>,
+    refresh_interval: Duration,
+}
+
+impl<V: Clone + Send + 'static> BackgroundCache<V> {
+    /// `initial` values are shown until they are refreshed. `compute` runs on a thread named `name`.
+    pub fn new<F>(name: &str, ctx: Context, refresh_interval: Duration, initial: HashMap<PathBuf, V>, compute: F) -> Self
+    where F: Fn(&Path) -> Option<V> + Send + 'static {
+        let entries = initial.into_iter()
+            .map(|(path, value)| (path, Entry::new(Some(value))))
+            .collect::<HashMap<_, _>>();
+        let entries = Arc::new(Mutex::new(entries));
+        let (sender, receiver) = mpsc::channel::<(PathBuf, u64)>();
+        let worker_entries = entries.clone();
+        std::thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || {
+                for (path, generation) in receiver {
+                    let value = compute(&path);
+                    let mut entries = worker_entries.lock().unwrap();
+                    let entry = entries.entry(path).or_insert(Entry::new(None));
+                    entry.value = value;
+                    entry.known = true;
+                    entry.pending = false;
+                    if entry.generation == generation {
+                        entry.refreshed = Some(Instant::now());
+                    }
+                    drop(entries);
+                    ctx.request_repaint();
+                }
+            })
+            .expect("Failed to spawn background cache thread.");
+        Self {
+            entries,
+            sender,
+            refresh_interval,
+        }
+    }
+
+    /// Returns the last known value and queues a refresh if it is out of date.
+    #[inline]
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<V> {
+        self.lookup(path).flatten()
+    }
+
+    /// Like [BackgroundCache::get], but tells apart a value that is unknown because it hasn't been computed yet
+    /// (`None`) from a computation that had no result (`Some(None)`).
+    pub fn lookup<P: AsRef<Path>>(&self, path: P) -> Option<Option<V>> {
+        let path = path.as_ref();
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(path.to_owned()).or_insert(Entry::new(None));
+        let stale = entry.refreshed.is_none_or(|refreshed| refreshed.elapsed() >= self.refresh_interval);
+        if stale && !entry.pending && self.sender.send((path.to_owned(), entry.generation)).is_ok() {
+            entry.pending = true;
+        }
+        entry.known.then(|| entry.value.clone())
+    }
+
+    /// Marks a value as out of date so that it is refreshed the next time it is requested. A refresh that is already
+    /// running doesn't count, because it may have read the old state.
+    pub fn invalidate<P: AsRef<Path>>(&self, path: P) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(path.as_ref()) {
+            entry.refreshed = None;
+            entry.generation += 1;
+        }
+    }
+
+    /// Every value that is currently known.
+    pub fn snapshot(&self) -> HashMap<PathBuf, V> {
+        self.entries.lock().unwrap()
+            .iter()
+            .filter_map(|(path, entry)| Some((path.clone(), entry.value.clone()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn invalidate_while_pending_test() {
+        let computed = Arc::new(AtomicUsize::new(0));
+        let (release, wait) = mpsc::channel::<()>();
+        let wait = Mutex::new(wait);
+        let counter = computed.clone();
+        let cache = BackgroundCache::new("test-cache", Context::default(), Duration::from_secs(3600), HashMap::new(), move |_| {
+            wait.lock().unwrap().recv().ok();
+            Some(counter.fetch_add(1, Ordering::SeqCst))
+        });
+        let path = Path::new("/project");
+        let wait_for = |count: usize| {
+            let start = Instant::now();
+            while computed.load(Ordering::SeqCst) < count {
+                assert!(start.elapsed() < Duration::from_secs(5), "The value was never computed.");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            // The value is published just after the counter is incremented.
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        assert_eq!(cache.lookup(path), None);
+        cache.invalidate(path);
+        release.send(()).unwrap();
+        wait_for(1);
+        // The refresh started before the invalidation, so another one is queued.
+        assert_eq!(cache.get(path), Some(0));
+        release.send(()).unwrap();
+        wait_for(2);
+        assert_eq!(cache.get(path), Some(1));
+        assert_eq!(computed.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn lookup_test() {
+        let cache = BackgroundCache::new("test-cache", Context::default(), Duration::from_secs(3600), HashMap::new(), |path| {
+            path.ends_with("some").then_some(1)
+        });
+        let wait_for = |path: &Path| {
+            let start = Instant::now();
+            loop {
+                if let Some(value) = cache.lookup(path) {
+                    return value;
+                }
+                assert!(start.elapsed() < Duration::from_secs(5), "The value was never computed.");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        };
+        assert_eq!(wait_for(Path::new("/some")), Some(1));
+        assert_eq!(wait_for(Path::new("/none")), None);
+    }
+}
@@ -61,9 +61,9 @@ pub fn execute_shell_script<S: AsRef<str>>(script: S) -> Result<ExitStatus, std:
 /// but I don't use MacOS, so you'll have to implement that yourself)
 pub fn shell_command<S: AsRef<str>>(command: S) -> Option<Command> {
     fn inner(command: &str) -> Option<Command> {
-        let args = shlex::split(command)?;
         #[cfg(target_os = "windows")]
         {
+            let args = shlex::split(command)?;
             let mut cmd = Command::new("cmd");
             cmd.arg("/C");
             cmd.args(&args);
@@ -72,12 +72,14 @@ pub fn shell_command<S: AsRef<str>>(command: S) -> Option<Command> {
         }
         #[cfg(target_os = "linux")]
         {
+            // `sh -c` expects the whole command as one argument. It's still split so that malformed commands are rejected.
+            shlex::split(command)?;
             let mut cmd = Command::new("sh");
-            shell_command.arg("-c");
-            shell_command.args(&args);
-            Some(shell_command)
+            cmd.arg("-c");
+            cmd.arg(command);
+            Some(cmd)
         }
-        #[cfg(not(target_os = "windows"))]
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
         {
             unimplemented!("I didn't implement shell_command() for this target.");
         }
@@ -85,6 +87,54 @@ pub fn shell_command<S: AsRef<str>>(command: S) -> Option<Command> {
     inner(command.as_ref())
 }
 
+/// Like [shell_command], but the program is started directly rather than through the system shell, so nothing in the
+/// arguments is interpreted by it. `cmd` would run the text after a `&` in a URL as another command, for example.
+pub fn program_command<S: AsRef<str>>(command: S) -> Option<Command> {
+    let args = shlex::split(command.as_ref())?;
+    let (program, args) = args.split_first()?;
+    let mut cmd = background_command(program);
+    cmd.args(args);
+    Some(cmd)
+}
+
+/// Quotes a URL for a shell command. Characters that a shell could interpret inside of double quotes, as well as
+/// whitespace and control characters, are percent-encoded. None of them may appear in a URL unencoded.
+pub fn quote_url(url: &str) -> String {
+    let mut quoted = String::with_capacity(url.len() + 2);
+    quoted.push('"');
+    for c in url.chars() {
+        match c {
+            '$' | '`' | '"' | '\\' | '!' => quoted.push_str(&format!("%{:02X}", c as u32)),
+            c if c.is_whitespace() || c.is_control() => {
+                let mut buffer = [0; 4];
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    quoted.push_str(&format!("%{byte:02X}"));
+                }
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes a path as a single word of a shell command. Paths can contain anything that a shell interprets (a directory
+/// of a cloned repository could be named `$(...)`), so they're single-quoted.
+pub fn quote_path(path: &std::path::Path) -> String {
+    shlex::try_quote(&path.to_string_lossy())
+        .expect("Paths don't contain nul bytes.")
+        .into_owned()
+}
+
+/// Creates a [Command] for a program that is run in the background (no terminal window is created on Windows).
+pub fn background_command<S: AsRef<std::ffi::OsStr>>(program: S) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(program);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(1<<27);
+    cmd
+}
+
 pub fn exec_shell<S: AsRef<str>>(command: S) -> Result<ExitStatus, ExecError> {
     fn inner(command: &str) -> Result<ExitStatus, ExecError> {
         let mut cmd = shell_command(command).ok_or_else(|| ExecError::InvalidCommand(command.to_owned()))?;
@@ -93,6 +143,56 @@ pub fn exec_shell<S: AsRef<str>>(command: S) -> Result<ExitStatus, ExecError> {
     inner(command.as_ref())
 }
 
+/// Starts shell commands without waiting for them, so that programs that don't return right away (editors,
+/// terminals, file explorers) don't block the UI. Each process is reaped on a background thread, which reports
+/// a failing exit status.
+pub struct DetachedCommands {
+    ctx: eframe::egui::Context,
+    sender: std::sync::mpsc::Sender<String>,
+    receiver: std::sync::mpsc::Receiver<String>,
+}
+
+impl DetachedCommands {
+    pub fn new(ctx: eframe::egui::Context) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self { ctx, sender, receiver }
+    }
+
+    /// Starts `command` with additional environment variables. `action` names the command in the failure report.
+    pub fn spawn(&self, action: &str, command: &str, env: &[(String, String)]) -> Result<(), ExecError> {
+        let cmd = shell_command(command).ok_or_else(|| ExecError::InvalidCommand(command.to_owned()))?;
+        self.spawn_command(action, cmd, env)
+    }
+
+    /// Starts `command` without the system shell (see [program_command]).
+    pub fn spawn_program(&self, action: &str, command: &str, env: &[(String, String)]) -> Result<(), ExecError> {
+        let cmd = program_command(command).ok_or_else(|| ExecError::InvalidCommand(command.to_owned()))?;
+        self.spawn_command(action, cmd, env)
+    }
+
+    fn spawn_command(&self, action: &str, mut cmd: Command, env: &[(String, String)]) -> Result<(), ExecError> {
+        cmd.envs(env.iter().map(|(name, value)| (name, value)));
+        let mut child = cmd.spawn()?;
+        let action = action.to_owned();
+        let sender = self.sender.clone();
+        let ctx = self.ctx.clone();
+        std::thread::spawn(move || {
+            if let Ok(exit_status) = child.wait() {
+                if !exit_status.success() {
+                    _ = sender.send(format!("{action} shell command failed with an exit status of {}", exit_status.code().unwrap_or(-1)));
+                    ctx.request_repaint();
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// The report of a command that failed since the last call.
+    pub fn poll_failure(&self) -> Option<String> {
+        self.receiver.try_recv().ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +203,50 @@ mod tests {
         // system(r#"code "C:\Users\derek\Documents\code\rust\bourne""#);
         exec_shell(r#"explorer.exe "." && echo test"#).expect("Failed to execute.");
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn detached_commands_test() {
+        let commands = DetachedCommands::new(eframe::egui::Context::default());
+        commands.spawn("Succeed", "exit 0", &[]).unwrap();
+        commands.spawn("Fail", "sleep 0.1; exit $CODE", &[(String::from("CODE"), String::from("3"))]).unwrap();
+        let start = std::time::Instant::now();
+        let report = loop {
+            if let Some(report) = commands.poll_failure() {
+                break report;
+            }
+            assert!(start.elapsed() < std::time::Duration::from_secs(5), "The failure was never reported.");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert_eq!(report, "Fail shell command failed with an exit status of 3");
+        assert_eq!(commands.poll_failure(), None);
+    }
+
+    #[test]
+    fn quote_url_test() {
+        assert_eq!(quote_url("https://example.com/a b?q=$(id)`id`\"\\"), r#""https://example.com/a%20b?q=%24(id)%60id%60%22%5C""#);
+        #[cfg(target_os = "linux")]
+        {
+            let url = "https://example.com/x$(echo${IFS}injected)`echo injected`";
+            let output = shell_command(format!("printf %s {}", quote_url(url))).unwrap().output().unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout), "https://example.com/x%24(echo%24{IFS}injected)%60echo%20injected%60");
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn program_command_test() {
+        let url = "https://example.com/?a=1&b=2|x";
+        let output = program_command(format!("printf %s {}", quote_url(url))).unwrap().output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), url);
+        assert!(program_command("").is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn quote_path_test() {
+        let path = std::path::Path::new("/home/user/it's $(id) `id` \\ \"x\"");
+        let output = shell_command(format!("printf %s {}", quote_path(path))).unwrap().output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), path.to_string_lossy());
+    }
 }
\ No newline at end of file
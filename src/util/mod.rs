@@ -4,4 +4,5 @@ pub mod execute;
 pub mod marker;
 pub mod alt;
 pub mod fsutil;
-pub mod time;
\ No newline at end of file
+pub mod time;
+pub mod background;
\ No newline at end of file
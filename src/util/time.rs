@@ -232,9 +232,42 @@ impl RepeatTimer {
     }
 }
 
+/// Formats the time between `time` and `now` as "5 minutes ago", "3 days ago", etc.
+pub fn relative_time(time: chrono::DateTime<chrono::Utc>, now: chrono::DateTime<chrono::Utc>) -> String {
+    const UNITS: &[(i64, &str)] = &[
+        (60 * 60 * 24 * 365, "year"),
+        (60 * 60 * 24 * 30, "month"),
+        (60 * 60 * 24 * 7, "week"),
+        (60 * 60 * 24, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+    ];
+    let seconds = (now - time).num_seconds();
+    if seconds < 0 {
+        return String::from("in the future");
+    }
+    match UNITS.iter().find(|&&(unit, _)| seconds >= unit) {
+        Some(&(unit, name)) => {
+            let count = seconds / unit;
+            format!("{count} {name}{} ago", if count == 1 { "" } else { "s" })
+        }
+        None => String::from("just now"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn relative_time_test() {
+        let now = chrono::Utc::now();
+        assert_eq!(relative_time(now, now), "just now");
+        assert_eq!(relative_time(now - chrono::Duration::seconds(90), now), "1 minute ago");
+        assert_eq!(relative_time(now - chrono::Duration::hours(5), now), "5 hours ago");
+        assert_eq!(relative_time(now - chrono::Duration::days(15), now), "2 weeks ago");
+        assert_eq!(relative_time(now - chrono::Duration::days(800), now), "2 years ago");
+    }
     
     #[test]
     fn timer_test() {
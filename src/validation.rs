@@ -0,0 +1,328 @@
+use crate::projects::ProjectType;
+
+/// The rule set that a name was checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// Directory names on every OS (Windows rules are always applied).
+    Path,
+    Cargo,
+    Python,
+    Npm,
+}
+
+impl Rule {
+    pub const fn text(self) -> &'static str {
+        match self {
+            Rule::Path => "directory",
+            Rule::Cargo => "Cargo package",
+            Rule::Python => "Python package",
+            Rule::Npm => "npm package",
+        }
+    }
+
+    /// The ecosystem rules that apply to a project type, in addition to [Rule::Path].
+    pub const fn for_project_type(project_type: ProjectType) -> Option<Self> {
+        match project_type {
+            ProjectType::Rust => Some(Rule::Cargo),
+            ProjectType::Python => Some(Rule::Python),
+            ProjectType::Web => Some(Rule::Npm),
+            ProjectType::Other => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum NameError {
+    #[error("The name is empty.")]
+    Empty,
+    #[error("{} names can be at most {max} characters long (this one is {len}).", rule.text())]
+    TooLong { rule: Rule, len: usize, max: usize },
+    #[error("{c:?} is not allowed in {} names.", rule.text())]
+    InvalidChar { rule: Rule, c: char },
+    #[error("{} names cannot start with {c:?}.", rule.text())]
+    InvalidStart { rule: Rule, c: char },
+    #[error("{} names cannot end with {c:?}.", rule.text())]
+    InvalidEnd { rule: Rule, c: char },
+    #[error("{} names cannot contain uppercase letters.", rule.text())]
+    Uppercase { rule: Rule },
+    #[error("`{name}` is a reserved keyword and cannot be used as a {} name.", rule.text())]
+    Keyword { rule: Rule, name: String },
+    #[error("`{name}` is reserved and cannot be used as a {} name.", rule.text())]
+    Reserved { rule: Rule, name: String },
+    #[error("`{name}` is not a valid Python identifier, so `import {name}` would fail.")]
+    NotImportable { name: String },
+}
+
+impl NameError {
+    #[inline]
+    pub const fn rule(&self) -> Option<Rule> {
+        match self {
+            NameError::Empty => None,
+            NameError::TooLong { rule, .. }
+            | NameError::InvalidChar { rule, .. }
+            | NameError::InvalidStart { rule, .. }
+            | NameError::InvalidEnd { rule, .. }
+            | NameError::Uppercase { rule }
+            | NameError::Keyword { rule, .. }
+            | NameError::Reserved { rule, .. } => Some(*rule),
+            NameError::NotImportable { .. } => Some(Rule::Python),
+        }
+    }
+}
+
+const WINDOWS_INVALID_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+    // Reserved for future use.
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try", "typeof", "unsized",
+    "virtual", "yield",
+];
+
+/// Names that Cargo refuses because they conflict with the standard library or Cargo's build directories.
+const CARGO_RESERVED_NAMES: &[&str] = &[
+    "alloc", "core", "proc-macro", "proc_macro", "std", "test",
+    "build", "deps", "examples", "incremental",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+    "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+const NPM_RESERVED_NAMES: &[&str] = &["node_modules", "favicon.ico"];
+
+/// Node's built-in modules. Packages with these names would be shadowed.
+const NODE_BUILTINS: &[&str] = &[
+    "assert", "buffer", "child_process", "cluster", "console", "constants", "crypto", "dgram", "dns", "domain",
+    "events", "fs", "http", "http2", "https", "inspector", "module", "net", "os", "path", "perf_hooks", "process",
+    "punycode", "querystring", "readline", "repl", "stream", "string_decoder", "sys", "timers", "tls", "trace_events",
+    "tty", "url", "util", "v8", "vm", "wasi", "worker_threads", "zlib",
+];
+
+/// Checks that `name` is a valid directory name on Windows, macOS, and Linux.
+pub fn validate_path_name(name: &str, errors: &mut Vec<NameError>) {
+    const RULE: Rule = Rule::Path;
+    const MAX_LEN: usize = 255;
+    if name == "." || name == ".." {
+        errors.push(NameError::Reserved { rule: RULE, name: name.to_owned() });
+        return;
+    }
+    if name.len() > MAX_LEN {
+        errors.push(NameError::TooLong { rule: RULE, len: name.len(), max: MAX_LEN });
+    }
+    if let Some(c) = name.chars().find(|&c| c.is_control() || WINDOWS_INVALID_CHARS.contains(&c)) {
+        errors.push(NameError::InvalidChar { rule: RULE, c });
+    }
+    if let Some(c) = name.chars().last().filter(|&c| c == '.' || c == ' ') {
+        errors.push(NameError::InvalidEnd { rule: RULE, c });
+    }
+    // Windows also reserves these names when they are followed by an extension (`con.txt`).
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if WINDOWS_RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+        errors.push(NameError::Reserved { rule: RULE, name: stem.to_owned() });
+    }
+}
+
+/// Checks the rules that `cargo new` enforces for package names.
+pub fn validate_cargo_name(name: &str, errors: &mut Vec<NameError>) {
+    const RULE: Rule = Rule::Cargo;
+    const MAX_LEN: usize = 64;
+    if name.len() > MAX_LEN {
+        errors.push(NameError::TooLong { rule: RULE, len: name.len(), max: MAX_LEN });
+    }
+    if let Some(c) = name.chars().next().filter(|&c| !(c.is_ascii_alphabetic() || c == '_')) {
+        errors.push(NameError::InvalidStart { rule: RULE, c });
+    }
+    if let Some(c) = name.chars().find(|&c| !(c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+        errors.push(NameError::InvalidChar { rule: RULE, c });
+    }
+    if RUST_KEYWORDS.contains(&name) {
+        errors.push(NameError::Keyword { rule: RULE, name: name.to_owned() });
+    }
+    if CARGO_RESERVED_NAMES.contains(&name) {
+        errors.push(NameError::Reserved { rule: RULE, name: name.to_owned() });
+    }
+}
+
+/// Normalizes a distribution name the way PyPI does (PEP 503): lowercase, with runs of `-`, `_`, and `.` collapsed into `-`.
+pub fn pypi_normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut in_separator = false;
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !in_separator {
+                normalized.push('-');
+            }
+            in_separator = true;
+        } else {
+            normalized.extend(c.to_lowercase());
+            in_separator = false;
+        }
+    }
+    normalized
+}
+
+/// The name that the package would be imported as.
+pub fn python_import_name(name: &str) -> String {
+    pypi_normalize(name).replace('-', "_")
+}
+
+pub fn is_python_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Checks that `name` is a valid PyPI distribution name (PEP 508) and that its import name is importable.
+pub fn validate_python_name(name: &str, errors: &mut Vec<NameError>) {
+    const RULE: Rule = Rule::Python;
+    if let Some(c) = name.chars().find(|&c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))) {
+        errors.push(NameError::InvalidChar { rule: RULE, c });
+    }
+    if let Some(c) = name.chars().next().filter(|c| !c.is_ascii_alphanumeric()) {
+        errors.push(NameError::InvalidStart { rule: RULE, c });
+    }
+    if let Some(c) = name.chars().last().filter(|c| !c.is_ascii_alphanumeric()) {
+        errors.push(NameError::InvalidEnd { rule: RULE, c });
+    }
+    let import_name = python_import_name(name);
+    if PYTHON_KEYWORDS.contains(&import_name.as_str()) {
+        errors.push(NameError::Keyword { rule: RULE, name: import_name });
+    } else if !is_python_identifier(&import_name) {
+        errors.push(NameError::NotImportable { name: import_name });
+    }
+}
+
+/// Checks the rules enforced by `validate-npm-package-name` for new packages.
+pub fn validate_npm_name(name: &str, errors: &mut Vec<NameError>) {
+    const RULE: Rule = Rule::Npm;
+    const MAX_LEN: usize = 214;
+    if name.len() > MAX_LEN {
+        errors.push(NameError::TooLong { rule: RULE, len: name.len(), max: MAX_LEN });
+    }
+    if let Some(c) = name.chars().next().filter(|&c| c == '.' || c == '_') {
+        errors.push(NameError::InvalidStart { rule: RULE, c });
+    }
+    if name.chars().any(|c| c.is_uppercase()) {
+        errors.push(NameError::Uppercase { rule: RULE });
+    }
+    if let Some(c) = name.chars().find(|&c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c.is_uppercase() || matches!(c, '-' | '_' | '.'))) {
+        errors.push(NameError::InvalidChar { rule: RULE, c });
+    }
+    if NPM_RESERVED_NAMES.contains(&name) || NODE_BUILTINS.contains(&name) {
+        errors.push(NameError::Reserved { rule: RULE, name: name.to_owned() });
+    }
+}
+
+/// Validates a project name for a project type. Returns every problem that was found.
+pub fn validate_name(name: &str, project_type: ProjectType) -> Vec<NameError> {
+    let mut errors = Vec::new();
+    if name.is_empty() {
+        errors.push(NameError::Empty);
+        return errors;
+    }
+    validate_path_name(name, &mut errors);
+    match Rule::for_project_type(project_type) {
+        Some(Rule::Cargo) => validate_cargo_name(name, &mut errors),
+        Some(Rule::Python) => validate_python_name(name, &mut errors),
+        Some(Rule::Npm) => validate_npm_name(name, &mut errors),
+        Some(Rule::Path) | None => (),
+    }
+    errors
+}
+
+/// Splits a name into lowercase words on anything that isn't alphanumeric and on camelCase boundaries.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Suggests fixed versions of `name` (such as `my-project` and `my_project` for `My Project`) that pass validation.
+pub fn suggest_names(name: &str, project_type: ProjectType) -> Vec<String> {
+    let words = words(name)
+        .into_iter()
+        .map(|word| word.chars().filter(char::is_ascii_alphanumeric).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let mut suggestions = Vec::new();
+    for separator in ["-", "_"] {
+        let mut candidate = words.join(separator);
+        if candidate.starts_with(|c: char| c.is_ascii_digit()) {
+            candidate.insert_str(0, &format!("project{separator}"));
+        }
+        if candidate != name && !suggestions.contains(&candidate) && validate_name(&candidate, project_type).is_empty() {
+            suggestions.push(candidate);
+        }
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_name_test() {
+        assert!(validate_name("projector", ProjectType::Rust).is_empty());
+        assert!(validate_name("my-crate_2", ProjectType::Rust).is_empty());
+        assert!(validate_name("fn", ProjectType::Rust).contains(&NameError::Keyword { rule: Rule::Cargo, name: String::from("fn") }));
+        assert!(validate_name("test", ProjectType::Rust).contains(&NameError::Reserved { rule: Rule::Cargo, name: String::from("test") }));
+        assert!(validate_name("1up", ProjectType::Rust).contains(&NameError::InvalidStart { rule: Rule::Cargo, c: '1' }));
+        assert!(validate_name("-crate", ProjectType::Rust).contains(&NameError::InvalidStart { rule: Rule::Cargo, c: '-' }));
+        assert!(validate_name("_crate", ProjectType::Rust).is_empty());
+        // Windows rules apply on every OS.
+        assert!(validate_name("con", ProjectType::Other).contains(&NameError::Reserved { rule: Rule::Path, name: String::from("con") }));
+        assert!(validate_name("Aux.txt", ProjectType::Other).contains(&NameError::Reserved { rule: Rule::Path, name: String::from("Aux") }));
+        assert!(validate_name("what?", ProjectType::Other).contains(&NameError::InvalidChar { rule: Rule::Path, c: '?' }));
+        assert!(validate_name("trailing.", ProjectType::Other).contains(&NameError::InvalidEnd { rule: Rule::Path, c: '.' }));
+
+        assert!(validate_name("my-package", ProjectType::Python).is_empty());
+        assert!(validate_name("class", ProjectType::Python).contains(&NameError::Keyword { rule: Rule::Python, name: String::from("class") }));
+        assert!(validate_name("2fast", ProjectType::Python).contains(&NameError::NotImportable { name: String::from("2fast") }));
+        assert_eq!(pypi_normalize("My__Package.Name"), "my-package-name");
+
+        assert!(validate_name("my-app", ProjectType::Web).is_empty());
+        assert!(validate_name("MyApp", ProjectType::Web).contains(&NameError::Uppercase { rule: Rule::Npm }));
+        assert!(validate_name("fs", ProjectType::Web).contains(&NameError::Reserved { rule: Rule::Npm, name: String::from("fs") }));
+        assert!(validate_name("_private", ProjectType::Web).contains(&NameError::InvalidStart { rule: Rule::Npm, c: '_' }));
+    }
+
+    #[test]
+    fn suggest_names_test() {
+        assert_eq!(suggest_names("My Project", ProjectType::Rust), vec![String::from("my-project"), String::from("my_project")]);
+        assert_eq!(suggest_names("myCoolApp!", ProjectType::Web), vec![String::from("my-cool-app"), String::from("my_cool_app")]);
+        assert_eq!(suggest_names("3D Engine", ProjectType::Python), vec![String::from("project-3d-engine"), String::from("project_3d_engine")]);
+        assert_eq!(suggest_names("my-project", ProjectType::Rust), vec![String::from("my_project")]);
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use eframe::egui::Context;
+
+use crate::{
+    appdata::AppCache,
+    util::{background::BackgroundCache, execute::{background_command, quote_path}},
+};
+
+/// Directories in a project that are checked for a virtual environment, in order.
+pub const IN_PROJECT_DIRS: [&str; 3] = [".venv", "venv", "env"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, bincode::Encode, bincode::Decode)]
+pub enum VenvKind {
+    /// A venv in the project directory that isn't managed by a tool Projector knows about.
+    InProject,
+    Poetry,
+    Uv,
+    Pipenv,
+}
+
+impl VenvKind {
+    pub const fn text(self) -> &'static str {
+        match self {
+            VenvKind::InProject => "venv",
+            VenvKind::Poetry => "Poetry",
+            VenvKind::Uv => "uv",
+            VenvKind::Pipenv => "Pipenv",
+        }
+    }
+}
+
+/// A Python virtual environment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, bincode::Encode, bincode::Decode)]
+pub struct Venv {
+    pub kind: VenvKind,
+    /// The environment directory (the one with `pyvenv.cfg`).
+    pub path: PathBuf,
+    /// The interpreter version, such as `3.12.1`.
+    pub version: Option<String>,
+}
+
+/// The directory of a venv that contains the executables.
+pub fn scripts_dir(venv: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        venv.join("Scripts")
+    } else {
+        venv.join("bin")
+    }
+}
+
+/// The interpreter of a venv.
+pub fn python_path(venv: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        scripts_dir(venv).join("python.exe")
+    } else {
+        scripts_dir(venv).join("python")
+    }
+}
+
+#[inline]
+fn is_venv(dir: &Path) -> bool {
+    dir.join("pyvenv.cfg").is_file()
+}
+
+/// Reads the interpreter version from `pyvenv.cfg`. `venv` writes `version`, uv and virtualenv write `version_info`.
+pub fn parse_pyvenv_version(cfg: &str) -> Option<String> {
+    let mut version_info = None;
+    for line in cfg.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "version" => return Some(value.trim().to_owned()),
+            // `3.12.1.final.0`
+            "version_info" => version_info = Some(value.trim().splitn(4, '.').take(3).collect::<Vec<_>>().join(".")),
+            _ => (),
+        }
+    }
+    version_info
+}
+
+fn interpreter_version(venv: &Path) -> Option<String> {
+    if let Some(version) = std::fs::read_to_string(venv.join("pyvenv.cfg")).ok().as_deref().and_then(parse_pyvenv_version) {
+        return Some(version);
+    }
+    let output = background_command(python_path(venv)).arg("--version").output().ok()?;
+    // Python 2 printed the version to stderr.
+    let text = String::from_utf8_lossy(if output.stdout.is_empty() { &output.stderr } else { &output.stdout }).into_owned();
+    text.trim().strip_prefix("Python ").map(str::to_owned)
+}
+
+/// Runs a tool that prints the path of the project's environment.
+fn tool_venv(dir: &Path, program: &str, args: &[&str]) -> Option<PathBuf> {
+    let output = background_command(program).args(args).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    is_venv(&path).then_some(path)
+}
+
+fn pyproject_has_poetry(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("pyproject.toml"))
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok())
+        .is_some_and(|pyproject| pyproject.get("tool").and_then(|tool| tool.get("poetry")).is_some())
+}
+
+/// Finds the virtual environment of the Python project in `dir`.
+///
+/// In-project environments (`.venv`, `venv`, `env`) are found without running anything. Poetry and Pipenv
+/// environments outside of the project are found by running `poetry env info --path` or `pipenv --venv`.
+pub fn detect_venv<P: AsRef<Path>>(dir: P) -> Option<Venv> {
+    fn inner(dir: &Path) -> Option<Venv> {
+        let poetry = pyproject_has_poetry(dir);
+        let pipenv = dir.join("Pipfile").is_file();
+        let in_project = IN_PROJECT_DIRS.iter().map(|name| dir.join(name)).find(|path| is_venv(path));
+        let (kind, path) = match in_project {
+            Some(path) => {
+                let kind = if dir.join("uv.lock").is_file() {
+                    VenvKind::Uv
+                } else if poetry {
+                    VenvKind::Poetry
+                } else if pipenv {
+                    VenvKind::Pipenv
+                } else {
+                    VenvKind::InProject
+                };
+                (kind, path)
+            }
+            None if poetry => (VenvKind::Poetry, tool_venv(dir, "poetry", &["env", "info", "--path"])?),
+            None if pipenv => (VenvKind::Pipenv, tool_venv(dir, "pipenv", &["--venv"])?),
+            None => return None,
+        };
+        Some(Venv {
+            kind,
+            version: interpreter_version(&path),
+            path,
+        })
+    }
+    inner(dir.as_ref())
+}
+
+impl Venv {
+    #[inline]
+    pub fn python(&self) -> PathBuf {
+        python_path(&self.path)
+    }
+
+    /// The variables that activate the environment for a process, like the `activate` script does.
+    pub fn activation_env(&self) -> Vec<(String, String)> {
+        let scripts = scripts_dir(&self.path);
+        let mut paths = vec![scripts];
+        if let Some(path) = std::env::var_os("PATH") {
+            paths.extend(std::env::split_paths(&path));
+        }
+        let path = std::env::join_paths(paths).unwrap_or_else(|_| OsString::new());
+        let mut env = vec![
+            (String::from("VIRTUAL_ENV"), self.path.display().to_string()),
+            (String::from("PATH"), path.to_string_lossy().into_owned()),
+        ];
+        if let Some(name) = self.path.file_name().and_then(|name| name.to_str()) {
+            env.push((String::from("VIRTUAL_ENV_PROMPT"), name.to_owned()));
+        }
+        env
+    }
+}
+
+/// The `{venv}` and `{python}` placeholders of command templates. Without an environment, `{venv}` is empty
+/// and `{python}` is the interpreter on the `PATH`.
+pub fn placeholders(venv: Option<&Venv>) -> (String, String) {
+    match venv {
+        Some(venv) => (quote_path(&venv.path), quote_path(&venv.python())),
+        None => (String::new(), String::from(if cfg!(target_os = "windows") { "python" } else { "python3" })),
+    }
+}
+
+/// Detects virtual environments in the background. Results are persisted in [AppCache].
+pub struct VenvCache {
+    cache: BackgroundCache<Venv>,
+}
+
+impl VenvCache {
+    /// The relative path of the cache file in `<program>/cache/`
+    const CACHE_REL_PATH: &str = "venvs";
+    /// How long an environment is used before it is detected again.
+    const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(ctx: Context, cache: &AppCache) -> Self {
+        let loaded = cache.load::<_, HashMap<PathBuf, Venv>>(Self::CACHE_REL_PATH).unwrap_or_default();
+        Self {
+            cache: BackgroundCache::new("venv-detection", ctx, Self::REFRESH_INTERVAL, loaded, |path| detect_venv(path)),
+        }
+    }
+
+    /// Returns the last known environment and queues a refresh if it is out of date.
+    #[inline]
+    pub fn get<P: AsRef<Path>>(&self, dir: P) -> Option<Venv> {
+        self.cache.get(dir)
+    }
+
+    /// Returns `None` while the environment hasn't been detected yet, and `Some(None)` when the project has none.
+    /// Queues a refresh if it is out of date.
+    #[inline]
+    pub fn lookup<P: AsRef<Path>>(&self, dir: P) -> Option<Option<Venv>> {
+        self.cache.lookup(dir)
+    }
+
+    pub fn save(&self, cache: &AppCache) -> crate::error::Result<()> {
+        cache.save(Self::CACHE_REL_PATH, &self.cache.snapshot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_venv_test() {
+        assert_eq!(parse_pyvenv_version("home = /usr/bin\ninclude-system-site-packages = false\nversion = 3.11.4\n").as_deref(), Some("3.11.4"));
+        assert_eq!(parse_pyvenv_version("home = /usr/bin\nimplementation = CPython\nversion_info = 3.12.1.final.0\n").as_deref(), Some("3.12.1"));
+        assert_eq!(parse_pyvenv_version("home = /usr/bin\n"), None);
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir.");
+        assert_eq!(detect_venv(dir.path()), None);
+        std::fs::create_dir(dir.path().join("venv")).unwrap();
+        std::fs::write(dir.path().join("venv").join("pyvenv.cfg"), "version = 3.10.2\n").unwrap();
+        let venv = detect_venv(dir.path()).expect("Failed to detect venv.");
+        assert_eq!(venv.kind, VenvKind::InProject);
+        assert_eq!(venv.version.as_deref(), Some("3.10.2"));
+        assert_eq!(venv.path, dir.path().join("venv"));
+
+        std::fs::create_dir(dir.path().join(".venv")).unwrap();
+        std::fs::write(dir.path().join(".venv").join("pyvenv.cfg"), "version_info = 3.13.0.final.0\n").unwrap();
+        std::fs::write(dir.path().join("uv.lock"), "").unwrap();
+        let venv = detect_venv(dir.path()).expect("Failed to detect venv.");
+        assert_eq!(venv.kind, VenvKind::Uv);
+        assert_eq!(venv.path, dir.path().join(".venv"));
+        let env = venv.activation_env();
+        assert_eq!(env[0].1, dir.path().join(".venv").display().to_string());
+        assert!(env[1].1.starts_with(&scripts_dir(&venv.path).display().to_string()));
+    }
+}
@@ -1,40 +1,373 @@
-use std::path::{Path, PathBuf};
-use eframe::egui::{Layout, TextEdit, Widget};
+use std::{path::{Path, PathBuf}, sync::{Arc, Mutex}};
+use eframe::egui::{
+    text::{CCursor, CCursorRange},
+    text_edit::TextEditState,
+    Color32, Context, Id, Key, Layout, Modifiers, Response, RichText, TextEdit, Ui, Widget,
+};
 
+use crate::dgui::{dir_browser::PathPicker, directory_view::{complete_path, PathType}};
 
-pub struct PathInput<'a> {
-    value: &'a mut String,
+/// The relative path of the recent path inputs in `<program>/config/`
+pub const RECENT_INPUTS_REL_PATH: &str = ".recent_inputs";
+
+/// The most recent inputs that are kept.
+const MAX_RECENT_INPUTS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PathInputError {
+    #[error("A path is required.")]
+    Empty,
+    #[error("The home directory could not be found.")]
+    NoHomeDirectory,
+    #[error("The environment variable `{0}` is not set.")]
+    UnsetVariable(String),
+    #[error("{0} does not exist.")]
+    NotFound(PathBuf),
+    #[error("{0} is not a directory.")]
+    NotADirectory(PathBuf),
+    #[error("{0} is not a file.")]
+    NotAFile(PathBuf),
+    #[error("{0} is not writable.")]
+    NotWritable(PathBuf),
 }
 
-impl<'a> PathInput<'a> {
-    pub fn new(value: &'a mut String) -> Self {
-        Self { value }
+/// Expands a leading `~` to the home directory, and `$VAR`, `${VAR}`, and `%VAR%` to the values of environment variables.
+pub fn expand_path(text: &str) -> Result<PathBuf, PathInputError> {
+    let text = text.trim();
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    if let Some(after) = text.strip_prefix('~') {
+        if after.is_empty() || after.starts_with(std::path::is_separator) {
+            let home = directories::BaseDirs::new().ok_or(PathInputError::NoHomeDirectory)?;
+            expanded.push_str(&home.home_dir().display().to_string());
+            rest = after;
+        }
+    }
+    let var = |name: &str| std::env::var(name).map_err(|_| PathInputError::UnsetVariable(name.to_owned()));
+    while let Some(start) = rest.find(['$', '%']) {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, remaining) = if rest[start..].starts_with('%') {
+            match after.find('%') {
+                Some(end) if end > 0 => (&after[..end], &after[end + 1..]),
+                // A lone `%` is part of the path.
+                _ => ("", after),
+            }
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", after),
+            }
+        } else {
+            let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+        if name.is_empty() {
+            expanded.push_str(&rest[start..start + 1]);
+        } else {
+            expanded.push_str(&var(name)?);
+        }
+        rest = remaining;
     }
+    expanded.push_str(rest);
+    Ok(PathBuf::from(expanded))
 }
 
-pub struct OptionalPathInput<'a> {
-    value: &'a mut Option<String>,
+/// The checks that the value of a path input must pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathValidation {
+    /// The path must exist and be of the input's type.
+    pub exists: bool,
+    /// The path must be writable. A path that doesn't exist is writable if its parent directory is.
+    pub writable: bool,
 }
 
-impl<'a> OptionalPathInput<'a> {
-    pub fn new(value: &'a mut Option<String>) -> Self {
-        Self { value }
+fn is_writable(path: &Path) -> bool {
+    if path.is_dir() {
+        tempfile::tempfile_in(path).is_ok()
+    } else if path.exists() {
+        std::fs::OpenOptions::new().append(true).open(path).is_ok()
+    } else {
+        path.parent().is_some_and(is_writable)
     }
 }
 
-impl<'a> Widget for PathInput<'a> {
-    fn ui(self, ui: &mut eframe::egui::Ui) -> eframe::egui::Response {
-        ui.with_layout(Layout::right_to_left(eframe::egui::Align::Center), |ui| {
-            let btn_resp = ui.button("...");
-            if btn_resp.clicked() {
-                if let Some(result) = rfd::FileDialog::new().pick_file() {
-                    *self.value = result.display().to_string();
+/// Expands and checks `text`. `path_type` is the type of path that is expected.
+pub fn validate_path(text: &str, path_type: PathType, validation: PathValidation) -> Result<PathBuf, PathInputError> {
+    if text.trim().is_empty() {
+        return Err(PathInputError::Empty);
+    }
+    let path = expand_path(text)?;
+    if path.exists() {
+        match path_type {
+            PathType::Directories if !path.is_dir() => return Err(PathInputError::NotADirectory(path)),
+            PathType::Files if !path.is_file() => return Err(PathInputError::NotAFile(path)),
+            _ => (),
+        }
+    } else if validation.exists {
+        return Err(PathInputError::NotFound(path));
+    }
+    if validation.writable && !is_writable(&path) {
+        return Err(PathInputError::NotWritable(path));
+    }
+    Ok(path)
+}
+
+/// The text of a completed path. Directories end with a separator so that the next segment can be completed.
+fn completion_text(path: &Path) -> String {
+    let mut text = path.display().to_string();
+    if path.is_dir() && !text.ends_with(std::path::is_separator) {
+        text.push(std::path::MAIN_SEPARATOR);
+    }
+    text
+}
+
+/// Completes the last segment of `text`. A segment that matches a single entry is completed to that entry, and a
+/// segment that matches several is completed to their longest common prefix.
+pub fn complete_segment(text: &str, path_type: PathType) -> Option<String> {
+    let expanded = expand_path(text).ok()?.display().to_string();
+    let completions = complete_path(&expanded, path_type, false);
+    let (first, rest) = completions.split_first()?;
+    if rest.is_empty() {
+        return Some(completion_text(first));
+    }
+    let names = completions.iter()
+        .filter_map(|path| path.file_name().and_then(|name| name.to_str()))
+        .collect::<Vec<_>>();
+    let first_name = names.first()?;
+    let common = first_name.char_indices()
+        .map(|(index, c)| index + c.len_utf8())
+        .take_while(|&end| names.iter().all(|name| name.get(..end).is_some_and(|prefix| prefix.eq_ignore_ascii_case(&first_name[..end]))))
+        .last()
+        .unwrap_or(0);
+    let parent = first.parent()?;
+    Some(parent.join(&first_name[..common]).display().to_string())
+}
+
+fn recent_inputs(ctx: &Context) -> Arc<Mutex<(Vec<String>, bool)>> {
+    ctx.data_mut(|data| data.get_temp_mut_or_default::<Arc<Mutex<(Vec<String>, bool)>>>(Id::new("path_input_recents")).clone())
+}
+
+/// Sets the recent inputs that were loaded from [RECENT_INPUTS_REL_PATH].
+pub fn set_recent_inputs(ctx: &Context, inputs: Vec<String>) {
+    recent_inputs(ctx).lock().unwrap().0 = inputs;
+}
+
+/// Returns the recent inputs if they were changed since the last call.
+pub fn take_changed_recent_inputs(ctx: &Context) -> Option<Vec<String>> {
+    let recents = recent_inputs(ctx);
+    let mut recents = recents.lock().unwrap();
+    std::mem::take(&mut recents.1).then(|| recents.0.clone())
+}
+
+fn push_recent_input(ctx: &Context, input: &str) {
+    let recents = recent_inputs(ctx);
+    let mut recents = recents.lock().unwrap();
+    if recents.0.first().is_some_and(|first| first == input) {
+        return;
+    }
+    recents.0.retain(|recent| recent != input);
+    recents.0.insert(0, input.to_owned());
+    recents.0.truncate(MAX_RECENT_INPUTS);
+    recents.1 = true;
+}
+
+/// The options of a [PathInput].
+#[derive(Debug, Clone, Copy)]
+struct PathInputOptions<'a> {
+    path_type: PathType,
+    validation: PathValidation,
+    hint_text: &'a str,
+}
+
+/// The last validation of a path input. Validating touches the file system (checking that a path is writable
+/// creates a file), so it only runs again when the text or the options change.
+#[derive(Debug, Clone)]
+struct CachedValidation {
+    text: String,
+    path_type: PathType,
+    validation: PathValidation,
+    result: Result<PathBuf, PathInputError>,
+}
+
+fn cached_validate_path(ctx: &Context, id: Id, text: &str, path_type: PathType, validation: PathValidation) -> Result<PathBuf, PathInputError> {
+    let cached = ctx.data(|data| data.get_temp::<CachedValidation>(id))
+        .filter(|cached| cached.text == text && cached.path_type == path_type && cached.validation == validation);
+    if let Some(cached) = cached {
+        return cached.result;
+    }
+    let result = validate_path(text, path_type, validation);
+    ctx.data_mut(|data| data.insert_temp(id, CachedValidation { text: text.to_owned(), path_type, validation, result: result.clone() }));
+    result
+}
+
+/// A text field, a recent inputs dropdown, and a browse button. Tab completes the path, and problems with the
+/// path are shown under the field.
+fn path_input_ui(ui: &mut Ui, text: &mut String, options: PathInputOptions) -> Response {
+    ui.vertical(|ui| {
+        let mut changed = false;
+        let mut text_id = Id::NULL;
+        let mut response = ui.with_layout(Layout::right_to_left(eframe::egui::Align::Center), |ui| {
+            let browse = ui.button("...");
+            let picker = match options.path_type {
+                PathType::Directories => PathPicker::folder(browse.id),
+                _ => PathPicker::file(browse.id),
+            };
+            let start_dir = expand_path(text).ok().filter(|path| path.is_dir());
+            let picker = match start_dir {
+                Some(dir) => picker.start_dir(dir),
+                None => picker,
+            };
+            if let Some(path) = picker.show(ui.ctx(), browse.clicked()) {
+                *text = path.display().to_string();
+                changed = true;
+            }
+            let recents = recent_inputs(ui.ctx()).lock().unwrap().0.clone();
+            let recents_button = ui.add_enabled_ui(!recents.is_empty(), |ui| {
+                ui.menu_button("🕘", |ui| {
+                    // Only checked while the menu is open. Writability isn't checked, because it creates a file.
+                    let validation = PathValidation { writable: false, ..options.validation };
+                    for recent in recents.iter().filter(|recent| validate_path(recent, options.path_type, validation).is_ok()) {
+                        if ui.button(recent).clicked() {
+                            *text = recent.clone();
+                            changed = true;
+                            ui.close_menu();
+                        }
+                    }
+                }).response.on_hover_text("Recent paths")
+            }).inner;
+            let id = browse.id.with("path_input_text");
+            text_id = id;
+            // Tab completes the path instead of moving the focus.
+            if ui.memory(|memory| memory.has_focus(id)) && ui.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Tab)) {
+                if let Some(completed) = complete_segment(text, options.path_type) {
+                    if completed != *text {
+                        *text = completed;
+                        changed = true;
+                    }
+                    if let Some(mut state) = TextEditState::load(ui.ctx(), id) {
+                        let end = text.chars().count();
+                        state.cursor.set_char_range(Some(CCursorRange::one(CCursor::new(end))));
+                        state.store(ui.ctx(), id);
+                    }
                 }
             }
-            let text_input = TextEdit::singleline(self.value)
+            let text_input = TextEdit::singleline(text)
+                .id(id)
+                .hint_text(options.hint_text)
                 .desired_width(ui.available_width())
                 .show(ui);
-            btn_resp.union(text_input.response)
-        }).inner
+            browse.union(recents_button).union(text_input.response)
+        }).inner;
+        let result = cached_validate_path(ui.ctx(), text_id.with("validation"), text, options.path_type, options.validation);
+        match &result {
+            Ok(_) if (changed || response.lost_focus()) && !text.trim().is_empty() => push_recent_input(ui.ctx(), text.trim()),
+            Err(err) => {
+                ui.label(RichText::new(err.to_string()).small().color(Color32::from_rgb(230, 90, 90)));
+            }
+            _ => (),
+        }
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }).inner
+}
+
+/// A path text field. See [path_input_ui].
+pub struct PathInput<'a> {
+    value: &'a mut String,
+    path_type: PathType,
+    validation: PathValidation,
+    hint_text: &'a str,
+}
+
+impl<'a> PathInput<'a> {
+    pub fn new(value: &'a mut String) -> Self {
+        Self {
+            value,
+            path_type: PathType::Files,
+            validation: PathValidation::default(),
+            hint_text: "",
+        }
+    }
+
+    /// Picks directories instead of files.
+    pub fn directory(mut self) -> Self {
+        self.path_type = PathType::Directories;
+        self
+    }
+
+    pub fn must_exist(mut self, exists: bool) -> Self {
+        self.validation.exists = exists;
+        self
     }
-}
\ No newline at end of file
+
+    pub fn writable(mut self, writable: bool) -> Self {
+        self.validation.writable = writable;
+        self
+    }
+
+    pub fn hint_text(mut self, hint_text: &'a str) -> Self {
+        self.hint_text = hint_text;
+        self
+    }
+}
+
+impl<'a> Widget for PathInput<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        path_input_ui(ui, self.value, PathInputOptions {
+            path_type: self.path_type,
+            validation: self.validation,
+            hint_text: self.hint_text,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_input_test() {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        assert_eq!(expand_path("$CARGO_MANIFEST_DIR/src").unwrap(), Path::new(&manifest_dir).join("src"));
+        assert_eq!(expand_path("${CARGO_MANIFEST_DIR}/src").unwrap(), Path::new(&manifest_dir).join("src"));
+        assert_eq!(expand_path("%CARGO_MANIFEST_DIR%").unwrap(), Path::new(&manifest_dir));
+        assert_eq!(expand_path("100% done").unwrap(), Path::new("100% done"));
+        assert_eq!(expand_path("$PROJECTOR_UNSET_VARIABLE"), Err(PathInputError::UnsetVariable(String::from("PROJECTOR_UNSET_VARIABLE"))));
+        if let Some(dirs) = directories::BaseDirs::new() {
+            assert_eq!(expand_path("~").unwrap(), dirs.home_dir());
+            assert_eq!(expand_path("~/code").unwrap(), dirs.home_dir().join("code"));
+        }
+        assert_eq!(expand_path("a~b").unwrap(), Path::new("a~b"));
+
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir(root.join("projects")).unwrap();
+        std::fs::create_dir(root.join("programs")).unwrap();
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+        let text = |path: &Path| path.display().to_string();
+        let exists = PathValidation { exists: true, writable: false };
+        assert_eq!(validate_path(&text(root), PathType::Directories, exists), Ok(root.to_path_buf()));
+        assert_eq!(validate_path("  ", PathType::Directories, exists), Err(PathInputError::Empty));
+        assert_eq!(validate_path(&text(&root.join("notes.txt")), PathType::Directories, exists), Err(PathInputError::NotADirectory(root.join("notes.txt"))));
+        assert_eq!(validate_path(&text(&root.join("missing")), PathType::Files, exists), Err(PathInputError::NotFound(root.join("missing"))));
+        assert!(validate_path(&text(&root.join("missing")), PathType::Files, PathValidation { exists: false, writable: true }).is_ok());
+
+        // The result is kept until the text changes.
+        let ctx = Context::default();
+        let id = Id::new("validation_test");
+        let new_dir = root.join("new");
+        assert!(cached_validate_path(&ctx, id, &text(&new_dir), PathType::Directories, exists).is_err());
+        std::fs::create_dir(&new_dir).unwrap();
+        assert!(cached_validate_path(&ctx, id, &text(&new_dir), PathType::Directories, exists).is_err());
+        assert!(cached_validate_path(&ctx, id, &text(root), PathType::Directories, exists).is_ok());
+        assert!(cached_validate_path(&ctx, id, &text(&new_dir), PathType::Directories, exists).is_ok());
+
+        assert_eq!(complete_segment(&text(&root.join("pro")), PathType::Directories), Some(text(&root.join("pro"))));
+        assert_eq!(complete_segment(&text(&root.join("proj")), PathType::Directories), Some(completion_text(&root.join("projects"))));
+        assert_eq!(complete_segment(&text(&root.join("no")), PathType::Files), Some(text(&root.join("notes.txt"))));
+        assert_eq!(complete_segment(&text(&root.join("no")), PathType::Directories), None);
+    }
+}